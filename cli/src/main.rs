@@ -0,0 +1,263 @@
+use std::{path::{Path, PathBuf}, process::ExitCode};
+
+use clap::{Parser, Subcommand, ValueEnum};
+use common_data_lib::{cards::Deck, creatures::{Creature, CreatureContainer, EncounterData, HitPoints, Side}, encounter_file, initiative::InitiativeMode, BackendError};
+
+/// Prepares and inspects encounter files without opening the app.
+#[derive(Debug, Parser)]
+#[command(name = "initiative", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Creates an empty encounter. The format is chosen from the file extension.
+    New {
+        file: PathBuf,
+        #[arg(long, value_enum, default_value_t = ModeArg::Individual)]
+        mode: ModeArg,
+        /// The side initiative rolled by the players, for side initiative.
+        #[arg(long, default_value_t = 0)]
+        players: isize,
+        /// The side initiative rolled by the monsters, for side initiative.
+        #[arg(long, default_value_t = 0)]
+        monsters: isize,
+        /// Replaces the file if it already exists.
+        #[arg(long)]
+        force: bool
+    },
+    /// Adds creatures to an encounter.
+    Add {
+        file: PathBuf,
+        #[arg(required = true)]
+        names: Vec<String>,
+        #[arg(long, allow_negative_numbers = true)]
+        initiative: Option<isize>,
+        /// Added to the d20 when the creatures' initiative is rolled.
+        #[arg(long, allow_negative_numbers = true)]
+        modifier: Option<isize>,
+        #[arg(long)]
+        hp: Option<usize>,
+        #[arg(long, value_enum)]
+        side: Option<SideArg>
+    },
+    /// Rolls a d20 plus the modifier for every creature that has no initiative yet, or deals everyone a new card in card based initiative.
+    Roll {
+        file: PathBuf,
+        /// Rolls again for creatures that already have an initiative. Not needed for cards, which are always all dealt again.
+        #[arg(long)]
+        all: bool
+    },
+    /// Prints the order creatures will act in, along with any ties that need resolving.
+    Order {
+        file: PathBuf
+    },
+    /// Converts an encounter between the JSON and TOML formats.
+    Convert {
+        input: PathBuf,
+        output: PathBuf
+    },
+    /// Checks that encounter files can be read and make sense.
+    Validate {
+        #[arg(required = true)]
+        files: Vec<PathBuf>
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ModeArg {
+    Individual,
+    Side,
+    Popcorn,
+    Reroll,
+    Cards
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum SideArg {
+    Players,
+    Monsters
+}
+
+impl From<SideArg> for Side {
+    fn from(value: SideArg) -> Self {
+        match value {
+            SideArg::Players => Side::Players,
+            SideArg::Monsters => Side::Monsters
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match run(cli.command) {
+        Ok(_) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(command: Command) -> Result<(), BackendError> {
+    match command {
+        Command::New { file, mode, players, monsters, force } => new_encounter(&file, mode, players, monsters, force),
+        Command::Add { file, names, initiative, modifier, hp, side } => add_creatures(&file, &names, initiative, modifier, hp, side),
+        Command::Roll { file, all } => roll_initiative(&file, all),
+        Command::Order { file } => print_order(&file),
+        Command::Convert { input, output } => convert(&input, &output),
+        Command::Validate { files } => validate(&files)
+    }
+}
+
+fn new_encounter(path: &Path, mode: ModeArg, players: isize, monsters: isize, force: bool) -> Result<(), BackendError> {
+    if path.exists() && !force {
+        return Err(BackendError::argument_error("file", format!("'{}' already exists", path.to_string_lossy())));
+    }
+
+    let mode = match mode {
+        ModeArg::Individual => InitiativeMode::Individual,
+        ModeArg::Side => InitiativeMode::Side { players, monsters },
+        ModeArg::Popcorn => InitiativeMode::Popcorn,
+        ModeArg::Reroll => InitiativeMode::RerollEachRound,
+        ModeArg::Cards => InitiativeMode::Cards
+    };
+
+    encounter_file::save_encounter(path, &EncounterData::new(&CreatureContainer::default(), mode))?;
+    println!("Created '{}' using {} initiative", path.to_string_lossy(), mode.to_string().to_lowercase());
+
+    Ok(())
+}
+
+/// Loads an encounter, selecting every creature so they all take part in rolls and the order.
+fn load(path: &Path) -> Result<(CreatureContainer, InitiativeMode), BackendError> {
+    let data = encounter_file::load_encounter(path)?;
+    data.validate()?;
+
+    let mode = data.initiative_mode();
+    let mut creatures = CreatureContainer::from(data);
+    for creature in creatures.iter_mut() {
+        creature.set_selected(true);
+    }
+
+    Ok((creatures, mode))
+}
+
+fn save(path: &Path, creatures: &CreatureContainer, mode: InitiativeMode) -> Result<(), BackendError> {
+    encounter_file::save_encounter(path, &EncounterData::new(creatures, mode))
+}
+
+fn add_creatures(path: &Path, names: &[String], initiative: Option<isize>, modifier: Option<isize>, hp: Option<usize>, side: Option<SideArg>) -> Result<(), BackendError> {
+    let (mut creatures, mode) = load(path)?;
+
+    for name in names.iter().map(|name| name.trim()).filter(|name| !name.is_empty()) {
+        let mut creature = Creature::from(name);
+        creature.set_initiative(initiative.unwrap_or_default());
+        creature.set_initiative_modifier(modifier.unwrap_or_default());
+        creature.set_hit_points(hp.map(HitPoints::new));
+        if let Some(side) = side {
+            creature.set_side(side.into());
+        }
+
+        println!("Added {}", creature.name());
+        creatures.push(creature);
+    }
+
+    creatures.sort();
+    save(path, &creatures, mode)
+}
+
+fn roll_initiative(path: &Path, all: bool) -> Result<(), BackendError> {
+    let (mut creatures, mode) = load(path)?;
+
+    if all && mode == InitiativeMode::Cards {
+        return Err(BackendError::argument_error("all", "Card based initiative always deals every creature a new card"));
+    }
+
+    if mode == InitiativeMode::Cards {
+        creatures.deal_cards(&mut Deck::default());
+        for creature in creatures.iter() {
+            if let Some(card) = creature.card() {
+                println!("{}: {}", creature.name(), card);
+            }
+        }
+    } else {
+        for creature in creatures.iter_mut().filter(|c| all || c.initiative() == 0) {
+            creature.set_initiative(creature.roll_initiative());
+            println!("{}: {}", creature.name(), creature.initiative());
+        }
+    }
+
+    save(path, &creatures, mode)
+}
+
+fn print_order(path: &Path) -> Result<(), BackendError> {
+    let (creatures, mode) = load(path)?;
+
+    println!("{} initiative", mode);
+    for (index, creature) in creatures.finalize(mode.rules().as_ref()).iter().enumerate() {
+        let mut details = vec![creature.initiative().to_string()];
+        if let Some(card) = creature.card() {
+            details.push(card.to_string());
+        }
+
+        if matches!(mode, InitiativeMode::Side { .. }) {
+            details.push(creature.side().to_string());
+        }
+
+        if let Some(hit_points) = creature.hit_points() {
+            details.push(format!("{} HP", hit_points));
+        }
+
+        if creature.hidden() {
+            details.push(String::from("hidden"));
+        }
+
+        let slot = if creature.is_slot() { " (extra turn)" } else { "" };
+        println!("{:>3}. {}{} [{}]", index + 1, creature.name(), slot, details.join(", "));
+    }
+
+    let conflicts = creatures.get_conflicts();
+    if !conflicts.is_empty() {
+        println!();
+        println!("Ties to resolve:");
+        for group in conflicts {
+            let names = group.creatures().iter().map(|c| c.name()).collect::<Vec<_>>();
+            println!("  {}: {}", group.initiative(), names.join(", "));
+        }
+    }
+
+    Ok(())
+}
+
+fn convert(input: &Path, output: &Path) -> Result<(), BackendError> {
+    let data = encounter_file::load_encounter(input)?;
+    data.validate()?;
+    encounter_file::save_encounter(output, &data)?;
+
+    println!("Converted '{}' to '{}'", input.to_string_lossy(), output.to_string_lossy());
+
+    Ok(())
+}
+
+/// Checks every file rather than stopping at the first bad one, failing if any of them is invalid.
+fn validate(paths: &[PathBuf]) -> Result<(), BackendError> {
+    let mut invalid = 0;
+    for path in paths {
+        match encounter_file::load_encounter(path).and_then(|data| data.validate()) {
+            Ok(_) => println!("{}: ok", path.to_string_lossy()),
+            Err(err) => {
+                invalid += 1;
+                eprintln!("{}: {}", path.to_string_lossy(), err);
+            }
+        }
+    }
+
+    match invalid {
+        0 => Ok(()),
+        count => Err(BackendError::logic_error(format!("{} of {} files are invalid", count, paths.len())))
+    }
+}
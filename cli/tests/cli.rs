@@ -0,0 +1,142 @@
+//! Runs the built `initiative` binary the way scripts do, checking its output and exit codes.
+
+use std::{path::{Path, PathBuf}, process::{Command, Output}};
+
+use common_data_lib::{creatures::CreatureContainer, encounter_file, initiative::InitiativeMode};
+
+/// A directory for one test's files, removed again when the test is done with it.
+struct TestDir(PathBuf);
+
+impl TestDir {
+    fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("initiative-cli-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+
+    fn file(&self, name: &str) -> PathBuf {
+        self.0.join(name)
+    }
+}
+
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+fn initiative(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_initiative"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn path(path: &Path) -> &str {
+    path.to_str().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).to_string()
+}
+
+#[test]
+fn test_prepare_encounter() {
+    let dir = TestDir::new("prepare");
+    let file = dir.file("ambush.enc");
+
+    assert!(initiative(&["new", path(&file)]).status.success());
+    assert!(initiative(&["add", path(&file), "Goblin", "Orc", "--initiative", "12", "--hp", "7"]).status.success());
+    assert!(initiative(&["add", path(&file), "Fighter", "--initiative", "18", "--side", "players"]).status.success());
+    assert!(initiative(&["add", path(&file), "Rogue", "--modifier", "25"]).status.success());
+
+    let output = initiative(&["order", path(&file)]);
+    assert!(output.status.success());
+    let order = stdout(&output);
+    assert!(order.find("Fighter").unwrap() < order.find("Goblin").unwrap());
+    assert!(order.contains("Ties to resolve:"));
+
+    // Only creatures without an initiative are rolled for unless every creature should be.
+    let output = initiative(&["roll", path(&file)]);
+    assert!(output.status.success());
+    assert!(stdout(&output).starts_with("Rogue: "));
+    assert!(initiative(&["roll", path(&file), "--all"]).status.success());
+
+    let creatures = CreatureContainer::from(encounter_file::load_encounter(&file).unwrap());
+    assert_eq!(creatures.len(), 4);
+    for creature in creatures.iter() {
+        let bonus = creature.initiative_modifier();
+        assert!((1 + bonus..=20 + bonus).contains(&creature.initiative()));
+    }
+}
+
+#[test]
+fn test_exit_codes() {
+    let dir = TestDir::new("exit-codes");
+    let file = dir.file("ambush.enc");
+    assert!(initiative(&["new", path(&file)]).status.success());
+
+    // Failures exit with a non-zero code and print the error.
+    let output = initiative(&["new", path(&file)]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("Error with argument 'file'"));
+
+    let output = initiative(&["order", path(&dir.file("missing.enc"))]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(!stderr(&output).is_empty());
+
+    let cards = dir.file("cards.toml");
+    assert!(initiative(&["new", path(&cards), "--mode", "cards"]).status.success());
+    assert!(initiative(&["add", path(&cards), "Goblin"]).status.success());
+    assert!(initiative(&["roll", path(&cards)]).status.success());
+    let output = initiative(&["roll", path(&cards), "--all"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("Error with argument 'all'"));
+
+    // Usage errors are reported by clap with its own exit code.
+    assert_eq!(initiative(&["add", path(&file)]).status.code(), Some(2));
+}
+
+#[test]
+fn test_validate() {
+    let dir = TestDir::new("validate");
+    let good = dir.file("good.toml");
+    let bad = dir.file("bad.enc");
+    assert!(initiative(&["new", path(&good)]).status.success());
+    std::fs::write(&bad, "{ \"creatures\": [{ \"name\": \"Goblin\" }] }").unwrap();
+
+    assert!(initiative(&["validate", path(&good)]).status.success());
+
+    // Every file is checked, with the failures going to stderr so the ok lines can be filtered separately.
+    let output = initiative(&["validate", path(&bad), path(&good)]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).contains("good.toml: ok"));
+    assert!(!stdout(&output).contains("bad.enc"));
+    assert!(stderr(&output).contains("bad.enc"));
+    assert!(stderr(&output).contains("1 of 2 files are invalid"));
+}
+
+#[test]
+fn test_convert() {
+    let dir = TestDir::new("convert");
+    let json = dir.file("ambush.enc");
+    let toml = dir.file("ambush.toml");
+    let back = dir.file("ambush.json");
+
+    assert!(initiative(&["new", path(&json), "--mode", "side", "--players", "14", "--monsters", "9"]).status.success());
+    assert!(initiative(&["add", path(&json), "Goblin", "--initiative", "12", "--hp", "7"]).status.success());
+    assert!(initiative(&["add", path(&json), "Fighter", "--side", "players"]).status.success());
+
+    assert!(initiative(&["convert", path(&json), path(&toml)]).status.success());
+    assert!(std::fs::read_to_string(&toml).unwrap().contains("players = 14"));
+    assert!(initiative(&["convert", path(&toml), path(&back)]).status.success());
+
+    let original = encounter_file::load_encounter(&json).unwrap();
+    assert_eq!(encounter_file::load_encounter(&toml).unwrap(), original);
+    assert_eq!(encounter_file::load_encounter(&back).unwrap(), original);
+    assert_eq!(original.initiative_mode(), InitiativeMode::Side { players: 14, monsters: 9 });
+}
@@ -0,0 +1,359 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+
+use crate::{creatures::{Creature, HitPoints, Side}, stat_block::StatBlock, BackendError};
+
+/// How far along a prepared encounter is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum EncounterStatus {
+    #[default]
+    Prepared,
+    InProgress,
+    Completed
+}
+
+impl EncounterStatus {
+    pub fn all() -> &'static [EncounterStatus] {
+        &[EncounterStatus::Prepared, EncounterStatus::InProgress, EncounterStatus::Completed]
+    }
+}
+
+impl std::fmt::Display for EncounterStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Prepared => write!(f, "Prepared"),
+            Self::InProgress => write!(f, "In progress"),
+            Self::Completed => write!(f, "Completed"),
+        }
+    }
+}
+
+/// The two lists of reusable creatures a campaign keeps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Roster {
+    /// The player characters, who turn up in most encounters.
+    Party,
+    /// Monsters and NPCs kept for use in future encounters.
+    Library
+}
+
+impl std::fmt::Display for Roster {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Party => write!(f, "Party"),
+            Self::Library => write!(f, "Library"),
+        }
+    }
+}
+
+/// A creature kept in a campaign roster. Only what carries over between encounters is kept, so rolls and cards are
+/// left behind.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RosterCreature {
+    id: Uuid,
+    name: String,
+    #[serde(default)]
+    initiative_modifier: isize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    hit_points: Option<HitPoints>,
+    #[serde(default)]
+    side: Side,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    stat_block: Option<StatBlock>
+}
+
+impl From<&Creature> for RosterCreature {
+    fn from(value: &Creature) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            name: value.name().to_string(),
+            initiative_modifier: value.initiative_modifier(),
+            hit_points: value.hit_points().map(|hp| HitPoints::new(hp.maximum())),
+            side: value.side(),
+            stat_block: value.stat_block().cloned()
+        }
+    }
+}
+
+impl RosterCreature {
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn initiative_modifier(&self) -> isize {
+        self.initiative_modifier
+    }
+
+    pub fn hit_points(&self) -> Option<HitPoints> {
+        self.hit_points
+    }
+
+    pub fn side(&self) -> Side {
+        self.side
+    }
+
+    pub fn stat_block(&self) -> Option<&StatBlock> {
+        self.stat_block.as_ref()
+    }
+
+    /// A new creature for an encounter, with its own ID so the same roster creature can be added more than once.
+    pub fn to_creature(&self) -> Creature {
+        let mut creature = Creature::from(self.name.as_str());
+        creature.set_initiative_modifier(self.initiative_modifier);
+        creature.set_hit_points(self.hit_points);
+        creature.set_side(self.side);
+        creature.set_stat_block(self.stat_block.clone());
+        creature
+    }
+}
+
+/// An encounter prepared as part of a campaign, saved as a file inside the campaign directory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CampaignEncounter {
+    id: Uuid,
+    name: String,
+    /// Relative to the campaign directory.
+    file: PathBuf,
+    #[serde(default)]
+    status: EncounterStatus
+}
+
+impl CampaignEncounter {
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn file(&self) -> &Path {
+        &self.file
+    }
+
+    pub fn status(&self) -> EncounterStatus {
+        self.status
+    }
+}
+
+/// A change in an encounter's status, kept as a record of what happened at the table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// Seconds since the Unix epoch.
+    timestamp: u64,
+    encounter: String,
+    status: EncounterStatus
+}
+
+impl HistoryEntry {
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    pub fn encounter(&self) -> &str {
+        &self.encounter
+    }
+
+    pub fn status(&self) -> EncounterStatus {
+        self.status
+    }
+}
+
+/// Everything that belongs to a campaign besides its encounter files.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct Campaign {
+    name: String,
+    #[serde(default)]
+    party: Vec<RosterCreature>,
+    #[serde(default)]
+    library: Vec<RosterCreature>,
+    #[serde(default)]
+    encounters: Vec<CampaignEncounter>,
+    #[serde(default)]
+    history: Vec<HistoryEntry>
+}
+
+impl Campaign {
+    /// The directory inside a campaign that its encounter files are kept in.
+    pub const ENCOUNTERS_DIRECTORY: &'static str = "encounters";
+
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Self::default()
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn encounters(&self) -> &[CampaignEncounter] {
+        &self.encounters
+    }
+
+    pub fn encounter(&self, id: Uuid) -> Option<&CampaignEncounter> {
+        self.encounters.iter().find(|e| e.id == id)
+    }
+
+    /// Finds the encounter saved to the given file, relative to the campaign directory.
+    pub fn encounter_for_file(&self, file: &Path) -> Option<&CampaignEncounter> {
+        self.encounters.iter().find(|e| e.file == file)
+    }
+
+    pub fn history(&self) -> &[HistoryEntry] {
+        &self.history
+    }
+
+    pub fn roster(&self, roster: Roster) -> &[RosterCreature] {
+        match roster {
+            Roster::Party => &self.party,
+            Roster::Library => &self.library
+        }
+    }
+
+    fn roster_mut(&mut self, roster: Roster) -> &mut Vec<RosterCreature> {
+        match roster {
+            Roster::Party => &mut self.party,
+            Roster::Library => &mut self.library
+        }
+    }
+
+    pub fn add_to_roster(&mut self, roster: Roster, creature: RosterCreature) {
+        let creatures = self.roster_mut(roster);
+        creatures.push(creature);
+        creatures.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+
+    pub fn remove_from_roster(&mut self, roster: Roster, id: Uuid) -> Option<RosterCreature> {
+        let creatures = self.roster_mut(roster);
+        let index = creatures.iter().position(|c| c.id == id)?;
+        Some(creatures.remove(index))
+    }
+
+    /// Prepares a new encounter, choosing a file name for it from its name that no other encounter uses.
+    pub fn add_encounter(&mut self, name: impl Into<String>) -> &CampaignEncounter {
+        let name = name.into();
+        let stem = name.chars()
+            .map(|c| if c.is_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+            .collect::<String>()
+            .split('-')
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("-");
+        let stem = if stem.is_empty() { String::from("encounter") } else { stem };
+
+        let mut file = Path::new(Self::ENCOUNTERS_DIRECTORY).join(format!("{}.enc", stem));
+        let mut count = 1;
+        while self.encounter_for_file(&file).is_some() {
+            count += 1;
+            file = Path::new(Self::ENCOUNTERS_DIRECTORY).join(format!("{}-{}.enc", stem, count));
+        }
+
+        self.encounters.push(CampaignEncounter {
+            id: Uuid::new_v4(),
+            name,
+            file,
+            status: EncounterStatus::Prepared
+        });
+
+        self.encounters.last().unwrap()
+    }
+
+    pub fn remove_encounter(&mut self, id: Uuid) -> Option<CampaignEncounter> {
+        let index = self.encounters.iter().position(|e| e.id == id)?;
+        Some(self.encounters.remove(index))
+    }
+
+    /// Moves an encounter on to a new status, adding it to the history. Setting the status it already has does nothing.
+    pub fn set_status(&mut self, id: Uuid, status: EncounterStatus, timestamp: u64) -> Result<(), BackendError> {
+        let encounter = self.encounters.iter_mut()
+            .find(|e| e.id == id)
+            .ok_or(BackendError::argument_error("id", format!("No encounter with id '{}' is in the campaign", id)))?;
+
+        if encounter.status != status {
+            encounter.status = status;
+            self.history.push(HistoryEntry {
+                timestamp,
+                encounter: encounter.name.clone(),
+                status
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// A campaign along with the directory it is kept in.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CampaignWorkspace {
+    pub directory: PathBuf,
+    pub campaign: Campaign
+}
+
+impl CampaignWorkspace {
+    pub fn encounter_path(&self, encounter: &CampaignEncounter) -> PathBuf {
+        self.directory.join(&encounter.file)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encounter_files() {
+        let mut campaign = Campaign::new("Test");
+
+        let file = campaign.add_encounter("Goblin Ambush!").file().to_path_buf();
+        assert_eq!(file, Path::new("encounters").join("goblin-ambush.enc"));
+
+        let file = campaign.add_encounter("Goblin ambush").file().to_path_buf();
+        assert_eq!(file, Path::new("encounters").join("goblin-ambush-2.enc"));
+
+        let file = campaign.add_encounter("???").file().to_path_buf();
+        assert_eq!(file, Path::new("encounters").join("encounter.enc"));
+    }
+
+    #[test]
+    fn test_status_history() {
+        let mut campaign = Campaign::new("Test");
+        let id = campaign.add_encounter("Dragon").id();
+
+        campaign.set_status(id, EncounterStatus::InProgress, 10).unwrap();
+        campaign.set_status(id, EncounterStatus::InProgress, 20).unwrap();
+        campaign.set_status(id, EncounterStatus::Completed, 30).unwrap();
+
+        let history = campaign.history().iter()
+            .map(|entry| (entry.timestamp(), entry.status()))
+            .collect::<Vec<_>>();
+        assert_eq!(history, vec![(10, EncounterStatus::InProgress), (30, EncounterStatus::Completed)]);
+        assert!(campaign.set_status(Uuid::new_v4(), EncounterStatus::Completed, 40).is_err());
+    }
+
+    #[test]
+    fn test_library_stat_blocks() {
+        let mut ogre = Creature::from("Ogre");
+        ogre.set_hit_points(Some(HitPoints::new(59)));
+        ogre.hit_points_mut().unwrap().damage(20);
+        ogre.set_stat_block(Some(StatBlock { armor_class: 11, challenge: String::from("2"), ..StatBlock::default() }));
+
+        let mut campaign = Campaign::new("Test");
+        campaign.add_to_roster(Roster::Library, RosterCreature::from(&ogre));
+
+        // Templates keep the stat block and maximum hit points, and every creature made from one gets a copy.
+        let template = &campaign.roster(Roster::Library)[0];
+        let first = template.to_creature();
+        let second = template.to_creature();
+        assert_ne!(first.id(), second.id());
+        assert_eq!(first.hit_points(), Some(HitPoints::new(59)));
+        assert_eq!(first.stat_block(), ogre.stat_block());
+        assert_eq!(second.stat_block().unwrap().armor_class, 11);
+    }
+}
@@ -0,0 +1,276 @@
+use rand::seq::SliceRandom;
+use serde::{Serialize, Deserialize};
+
+/// Card suits, from lowest to highest as they break ties between cards of the same rank.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Suit {
+    Clubs,
+    Diamonds,
+    Hearts,
+    Spades
+}
+
+impl Suit {
+    pub fn all() -> &'static [Suit] {
+        &[Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades]
+    }
+}
+
+impl std::fmt::Display for Suit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Clubs => write!(f, "♣"),
+            Self::Diamonds => write!(f, "♦"),
+            Self::Hearts => write!(f, "♥"),
+            Self::Spades => write!(f, "♠"),
+        }
+    }
+}
+
+/// A card from the action deck. Ranks run from 2 up to 14 for the ace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Card {
+    Standard { rank: u8, suit: Suit },
+    Joker { red: bool }
+}
+
+impl Card {
+    /// The initiative the card gives, so that higher cards act first. Jokers beat every other card.
+    pub fn initiative(&self) -> isize {
+        match *self {
+            Self::Standard { rank, suit } => rank as isize * 4 + suit as isize,
+            Self::Joker { red } => 60 + red as isize
+        }
+    }
+
+    pub fn is_joker(&self) -> bool {
+        matches!(self, Self::Joker { .. })
+    }
+
+    fn rank(&self) -> u8 {
+        match *self {
+            Self::Standard { rank, .. } => rank,
+            Self::Joker { .. } => u8::MAX
+        }
+    }
+}
+
+impl PartialOrd for Card {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Card {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.initiative().cmp(&other.initiative())
+    }
+}
+
+impl std::fmt::Display for Card {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::Standard { rank: 11, suit } => write!(f, "J{}", suit),
+            Self::Standard { rank: 12, suit } => write!(f, "Q{}", suit),
+            Self::Standard { rank: 13, suit } => write!(f, "K{}", suit),
+            Self::Standard { rank: 14, suit } => write!(f, "A{}", suit),
+            Self::Standard { rank, suit } => write!(f, "{}{}", rank, suit),
+            Self::Joker { red: true } => write!(f, "Red Joker"),
+            Self::Joker { red: false } => write!(f, "Black Joker"),
+        }
+    }
+}
+
+/// Edges that change how a creature is dealt its card.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CardEdge {
+    /// Draws two cards and keeps the best.
+    LevelHeaded,
+    /// Draws three cards and keeps the best.
+    ImprovedLevelHeaded,
+    /// Draws again while the card is a five or lower.
+    Quick
+}
+
+impl CardEdge {
+    pub fn all() -> &'static [CardEdge] {
+        &[CardEdge::LevelHeaded, CardEdge::ImprovedLevelHeaded, CardEdge::Quick]
+    }
+
+    pub fn abbreviation(&self) -> &'static str {
+        match self {
+            Self::LevelHeaded => "LH",
+            Self::ImprovedLevelHeaded => "ILH",
+            Self::Quick => "Q",
+        }
+    }
+}
+
+impl std::fmt::Display for CardEdge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::LevelHeaded => write!(f, "Level Headed"),
+            Self::ImprovedLevelHeaded => write!(f, "Improved Level Headed"),
+            Self::Quick => write!(f, "Quick"),
+        }
+    }
+}
+
+/// The action deck: 52 standard cards and two jokers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Deck {
+    draw_pile: Vec<Card>,
+    discard_pile: Vec<Card>,
+    /// Dealing a joker means the whole deck is shuffled together at the end of the round.
+    joker_dealt: bool
+}
+
+impl Default for Deck {
+    fn default() -> Self {
+        Self::without([])
+    }
+}
+
+impl Deck {
+    /// A freshly shuffled deck missing the cards that are already out in front of creatures, such as those loaded
+    /// with an encounter, so they can't be dealt a second time.
+    pub fn without(held: impl IntoIterator<Item = Card>) -> Self {
+        let mut draw_pile = Self::full_deck();
+        for card in held {
+            if let Some(index) = draw_pile.iter().position(|c| *c == card) {
+                draw_pile.swap_remove(index);
+            }
+        }
+
+        draw_pile.shuffle(&mut rand::thread_rng());
+
+        Self {
+            draw_pile,
+            discard_pile: Vec::new(),
+            joker_dealt: false
+        }
+    }
+
+    fn full_deck() -> Vec<Card> {
+        Suit::all().iter()
+            .flat_map(|suit| (2..=14).map(|rank| Card::Standard { rank, suit: *suit }))
+            .chain([Card::Joker { red: true }, Card::Joker { red: false }])
+            .collect()
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.draw_pile.len()
+    }
+
+    /// Draws the top card, shuffling the discards back in if the draw pile has run out.
+    pub fn draw(&mut self) -> Card {
+        if self.draw_pile.is_empty() {
+            self.draw_pile.append(&mut self.discard_pile);
+            if self.draw_pile.is_empty() {
+                // Every card is out in front of a creature, so bring in another deck.
+                self.draw_pile = Self::full_deck();
+            }
+
+            self.draw_pile.shuffle(&mut rand::thread_rng());
+        }
+
+        let card = self.draw_pile.pop().unwrap_or(Card::Joker { red: false });
+
+        if card.is_joker() {
+            self.joker_dealt = true;
+        }
+
+        card
+    }
+
+    /// Deals a creature its card for the round, applying its edges, and discards any cards it doesn't keep.
+    pub fn deal(&mut self, edges: &[CardEdge]) -> Card {
+        let draws = if edges.contains(&CardEdge::ImprovedLevelHeaded) {
+            3
+        } else if edges.contains(&CardEdge::LevelHeaded) {
+            2
+        } else {
+            1
+        };
+
+        let mut cards = Vec::new();
+        for _ in 0..draws {
+            let mut card = self.draw();
+            while edges.contains(&CardEdge::Quick) && card.rank() <= 5 {
+                self.discard_pile.push(card);
+                card = self.draw();
+            }
+
+            cards.push(card);
+        }
+
+        cards.sort();
+        let best = cards.pop().unwrap_or_else(|| self.draw());
+        self.discard_pile.append(&mut cards);
+
+        best
+    }
+
+    /// Discards the cards dealt last round, and shuffles everything back together if a joker came up.
+    pub fn start_round(&mut self, dealt: impl IntoIterator<Item = Card>) {
+        self.discard_pile.extend(dealt);
+
+        if self.joker_dealt {
+            self.draw_pile.append(&mut self.discard_pile);
+            self.draw_pile.shuffle(&mut rand::thread_rng());
+            self.joker_dealt = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_card_order() {
+        let two_of_spades = Card::Standard { rank: 2, suit: Suit::Spades };
+        let three_of_clubs = Card::Standard { rank: 3, suit: Suit::Clubs };
+        let three_of_hearts = Card::Standard { rank: 3, suit: Suit::Hearts };
+        let ace_of_spades = Card::Standard { rank: 14, suit: Suit::Spades };
+
+        assert!(two_of_spades < three_of_clubs);
+        assert!(three_of_clubs < three_of_hearts);
+        assert!(ace_of_spades < Card::Joker { red: false });
+        assert_eq!(ace_of_spades.to_string(), "A♠");
+    }
+
+    #[test]
+    fn test_deck_reshuffles_after_joker() {
+        let mut deck = Deck::default();
+        assert_eq!(deck.remaining(), 54);
+
+        let mut dealt = Vec::new();
+        while !dealt.iter().any(Card::is_joker) {
+            dealt.push(deck.draw());
+        }
+
+        deck.start_round(dealt);
+        assert_eq!(deck.remaining(), 54);
+    }
+
+    #[test]
+    fn test_deck_without_held_cards() {
+        let held = [Card::Standard { rank: 7, suit: Suit::Hearts }, Card::Joker { red: true }];
+        let mut deck = Deck::without(held);
+        assert_eq!(deck.remaining(), 52);
+
+        let drawn = (0..52).map(|_| deck.draw()).collect::<Vec<_>>();
+        assert!(held.iter().all(|card| !drawn.contains(card)));
+    }
+
+    #[test]
+    fn test_edges() {
+        let mut deck = Deck::default();
+        for _ in 0..20 {
+            let card = deck.deal(&[CardEdge::Quick, CardEdge::LevelHeaded]);
+            assert!(card.is_joker() || card.rank() > 5);
+            deck.start_round([card]);
+        }
+    }
+}
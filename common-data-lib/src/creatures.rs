@@ -1,9 +1,10 @@
-use std::{cmp::Ordering, slice::{Iter, IterMut}, fs::File, path::Path, collections::HashMap};
+use std::{cmp::Ordering, slice::{Iter, IterMut}, collections::HashMap};
 
+use rand::Rng;
 use serde::{Serialize, Deserialize};
 use uuid::Uuid;
 
-use crate::{BackendError, ToBackendResult};
+use crate::{BackendError, cards::{Card, CardEdge, Deck}, damage::{DamageAdjustment, DamageType}, initiative::{InitiativeMode, InitiativeRules}, stat_block::StatBlock};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub struct BasicCreature {
@@ -75,6 +76,43 @@ pub enum OrderMode {
     Initiative
 }
 
+impl OrderMode {
+    pub fn all() -> &'static [OrderMode] {
+        &[OrderMode::Alphabetical, OrderMode::Initiative]
+    }
+}
+
+impl std::fmt::Display for OrderMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Alphabetical => write!(f, "Alphabetical"),
+            Self::Initiative => write!(f, "Initiative"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum Side {
+    Players,
+    #[default]
+    Monsters
+}
+
+impl Side {
+    pub fn all() -> &'static [Side] {
+        &[Side::Players, Side::Monsters]
+    }
+}
+
+impl std::fmt::Display for Side {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Players => write!(f, "Players"),
+            Self::Monsters => write!(f, "Monsters"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub struct CreatureContainer {
     ordering: OrderMode,
@@ -91,32 +129,10 @@ impl From<Vec<Creature>> for CreatureContainer {
 }
 
 impl CreatureContainer {
-    pub fn load_from(path: &Path) -> Result<Self, BackendError> {
-        let mut file = File::open(path).to_backend_result()?;
-        let content: Vec<CreatureData> = serde_json::from_reader(&mut file).to_backend_result()?;
-        let content = content.into_iter()
-            .map(|c| Creature::from(c))
-            .collect::<Vec<_>>();
-
-        Ok(Self {
-            ordering: OrderMode::Alphabetical,
-            creatures: content
-        })
-    }
-
     pub fn len(&self) -> usize {
         self.creatures.len()
     }
 
-    pub fn save_to(&self, path: &Path) -> Result<(), BackendError> {
-        let mut file = File::create(path).to_backend_result()?;
-        let content = self.creatures.iter()
-            .map(|c| CreatureData::from(c))
-            .collect::<Vec<_>>();
-
-        serde_json::to_writer_pretty(&mut file, &content).to_backend_result()
-    }
-
     pub fn sort(&mut self) {
         match self.ordering {
             OrderMode::Alphabetical => self.creatures.sort_by(alphabetical_order),
@@ -183,6 +199,110 @@ impl CreatureContainer {
         Some(self.remove_by_index(index))
     }
 
+    pub fn remove_with_slots(&mut self, id: Uuid) -> Option<Creature> {
+        let creature = self.remove(id)?;
+        self.creatures.retain(|c| c.slot_of() != Some(id));
+
+        Some(creature)
+    }
+
+    /// Gets the creature that holds the state for an entry, which is the creature a slot belongs to, or the entry itself.
+    pub fn state(&self, id: Uuid) -> Option<&Creature> {
+        match self.get(id)?.slot_of() {
+            Some(owner_id) => self.get(owner_id),
+            None => self.get(id)
+        }
+    }
+
+    pub fn state_mut(&mut self, id: Uuid) -> Option<&mut Creature> {
+        match self.get(id)?.slot_of() {
+            Some(owner_id) => self.get_mut(owner_id),
+            None => self.get_mut(id)
+        }
+    }
+
+    /// Renames a creature along with its extra initiative slots. Names can't be blank or changed to another creature's
+    /// name, though creatures added with the same name can keep it.
+    pub fn rename(&mut self, id: Uuid, name: &str) -> Result<(), BackendError> {
+        let name = name.trim();
+        let owner = self.state(id)
+            .ok_or_else(|| BackendError::argument_error("id", format!("No creature with id '{}' exists", id)))?;
+        let (owner_id, unchanged) = (owner.id(), owner.name.to_lowercase() == name.to_lowercase());
+
+        if name.is_empty() {
+            return Err(BackendError::argument_error("name", "A creature's name can't be blank"));
+        }
+
+        let taken = !unchanged && self.creatures.iter()
+            .any(|c| c.id != owner_id && c.slot_of != Some(owner_id) && c.name.to_lowercase() == name.to_lowercase());
+        if taken {
+            return Err(BackendError::argument_error("name", format!("There is already a creature named '{}'", name)));
+        }
+
+        for creature in self.creatures.iter_mut().filter(|c| c.id == owner_id || c.slot_of == Some(owner_id)) {
+            creature.name = name.to_string();
+        }
+
+        Ok(())
+    }
+
+    /// Copies a creature along with its extra initiative slots, naming the copy with the next number that isn't
+    /// taken, so copying "Orc 2" makes "Orc 3". The copy starts with full hit points and no card. It acts at the given
+    /// initiative, or the original's, with its slots keeping the same distance from it as the original's do.
+    pub fn duplicate(&mut self, id: Uuid, initiative: Option<isize>) -> Result<Creature, BackendError> {
+        let original = self.state(id)
+            .ok_or_else(|| BackendError::argument_error("id", format!("No creature with id '{}' exists", id)))?
+            .clone();
+
+        let copy = Creature {
+            id: Uuid::new_v4(),
+            name: self.next_numbered_name(&original.name),
+            initiative: initiative.unwrap_or(original.initiative),
+            sub_order: 0,
+            hit_points: original.hit_points.map(|hp| HitPoints::new(hp.maximum())),
+            card: None,
+            ..original.clone()
+        };
+
+        let slots = self.slots_of(original.id)
+            .map(|slot| {
+                let mut slot_copy = copy.new_slot(copy.initiative + slot.initiative - original.initiative);
+                slot_copy.selected = slot.selected;
+                slot_copy
+            })
+            .collect::<Vec<_>>();
+
+        self.creatures.push(copy.clone());
+        self.creatures.extend(slots);
+
+        Ok(copy)
+    }
+
+    /// The name with the next number after it that no creature has, starting from 2 for names without a number.
+    fn next_numbered_name(&self, name: &str) -> String {
+        let (base, number) = match name.rsplit_once(' ') {
+            Some((base, number)) => match number.parse::<usize>() {
+                Ok(number) => (base, number),
+                Err(_) => (name, 1)
+            },
+            None => (name, 1)
+        };
+
+        (number + 1..)
+            .map(|n| format!("{} {}", base, n))
+            .find(|candidate| !self.creatures.iter().any(|c| c.name.to_lowercase() == candidate.to_lowercase()))
+            .unwrap_or_else(|| name.to_string())
+    }
+
+    pub fn slots_of(&self, id: Uuid) -> impl Iterator<Item = &Creature> {
+        self.creatures.iter().filter(move |c| c.slot_of() == Some(id))
+    }
+
+    /// Slots take part in the encounter whenever the creature they belong to does.
+    fn is_taking_part(&self, creature: &Creature) -> bool {
+        self.state(creature.id()).map_or(creature.selected(), |c| c.selected())
+    }
+
     pub fn iter(&self) -> Iter<Creature> {
         self.creatures.iter()
     }
@@ -197,7 +317,7 @@ impl CreatureContainer {
 
     pub fn get_conflicts(&self) -> Vec<ConflictGroup> {
         let mut initiative_map = HashMap::<isize, Vec<BasicCreature>>::new();
-        for creature in self.creatures.iter().filter(|c| c.selected()) {
+        for creature in self.creatures.iter().filter(|c| self.is_taking_part(c)) {
             match initiative_map.get_mut(&creature.initiative) {
                 Some(creatures) => creatures.push(BasicCreature::from(creature)),
                 None => {
@@ -220,23 +340,197 @@ impl CreatureContainer {
         groups
     }
 
-    pub fn finalize(&self) -> CreatureContainer {
-        let mut creatures = self.creatures.iter()
-            .filter_map(|c| if c.selected() { Some(c.clone()) } else { None })
+    pub fn finalize(&self, rules: &dyn InitiativeRules) -> CreatureContainer {
+        let ids = self.creatures.iter()
+            .filter(|c| self.is_taking_part(c))
+            .map(|c| c.id())
+            .collect::<Vec<_>>();
+
+        self.order_by_rules(&ids, rules).into_iter()
+            .filter_map(|id| self.get(id).cloned())
+            .collect::<Vec<_>>()
+            .into()
+    }
+
+    pub fn order_by_rules(&self, ids: &[Uuid], rules: &dyn InitiativeRules) -> Vec<Uuid> {
+        let mut creatures = ids.iter()
+            .filter_map(|&id| self.get(id))
+            .map(|c| {
+                // Slots act with the side of the creature they belong to.
+                let mut creature = c.clone();
+                creature.set_side(self.state(c.id()).unwrap_or(c).side());
+                creature
+            })
             .collect::<Vec<_>>();
 
-        creatures.sort_by(initiative_order);
-        creatures.reverse();
+        rules.order(&mut creatures);
+
+        creatures.iter().map(|c| c.id()).collect()
+    }
+
+    /// Where a creature belongs in an existing order, which is before the first entry the initiative rules put after it.
+    pub fn initiative_position(&self, order: &[Uuid], id: Uuid, rules: &dyn InitiativeRules) -> usize {
+        order.iter()
+            .position(|&entry| self.order_by_rules(&[entry, id], rules).first() == Some(&id))
+            .unwrap_or(order.len())
+    }
+
+    /// Deals a card from a fresh deck to every creature taking part, using the edges of the creature each slot belongs
+    /// to. Cards left over from an earlier deal are dropped rather than discarded, as the fresh deck already has them.
+    pub fn deal_cards(&mut self, deck: &mut Deck) {
+        for creature in self.creatures.iter_mut() {
+            creature.take_card();
+        }
+
+        let ids = self.creatures.iter()
+            .filter(|c| self.is_taking_part(c))
+            .map(|c| c.id())
+            .collect::<Vec<_>>();
+
+        for id in ids {
+            let edges = self.state(id).map(|c| c.card_edges().to_vec()).unwrap_or_default();
+            let card = deck.deal(&edges);
+            if let Some(creature) = self.get_mut(id) {
+                creature.deal(card);
+            }
+        }
+    }
+
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct EncounterData {
+    #[serde(default)]
+    initiative_mode: InitiativeMode,
+    creatures: Vec<CreatureData>
+}
+
+impl From<&CreatureContainer> for EncounterData {
+    fn from(value: &CreatureContainer) -> Self {
+        Self {
+            initiative_mode: InitiativeMode::default(),
+            creatures: value.iter().map(CreatureData::from).collect()
+        }
+    }
+}
+
+impl From<Vec<CreatureData>> for EncounterData {
+    fn from(value: Vec<CreatureData>) -> Self {
+        Self {
+            initiative_mode: InitiativeMode::default(),
+            creatures: value
+        }
+    }
+}
+
+impl From<EncounterData> for CreatureContainer {
+    fn from(value: EncounterData) -> Self {
+        value.creatures.into_iter()
+            .map(Creature::from)
+            .collect::<Vec<_>>()
+            .into()
+    }
+}
+
+impl EncounterData {
+    pub fn new(creatures: &CreatureContainer, initiative_mode: InitiativeMode) -> Self {
+        Self {
+            initiative_mode,
+            ..Self::from(creatures)
+        }
+    }
+
+    pub fn creatures(&self) -> &[CreatureData] {
+        &self.creatures
+    }
+
+    pub fn initiative_mode(&self) -> InitiativeMode {
+        self.initiative_mode
+    }
+
+    /// Checks for problems that the file format allows but the app doesn't, such as two creatures sharing an ID or
+    /// an initiative slot whose creature is missing.
+    pub fn validate(&self) -> Result<(), BackendError> {
+        let mut ids = HashMap::new();
+        for (index, creature) in self.creatures.iter().enumerate() {
+            if creature.name.trim().is_empty() {
+                return Err(BackendError::argument_error("creatures", format!("Creature {} has no name", index + 1)));
+            }
+
+            if let Some(other) = ids.insert(creature.id, &creature.name) {
+                return Err(BackendError::argument_error("creatures", format!("{} and {} share the id '{}'", other, creature.name, creature.id)));
+            }
+
+            if let Some(hit_points) = creature.hit_points {
+                if hit_points.current > hit_points.maximum {
+                    return Err(BackendError::argument_error("creatures", format!("{} has more hit points than their maximum", creature.name)));
+                }
+            }
+        }
+
+        for creature in self.creatures.iter() {
+            if let Some(owner_id) = creature.slot_of {
+                let owner = self.creatures.iter().find(|c| c.id == owner_id);
+                match owner {
+                    None => return Err(BackendError::argument_error("creatures", format!("The initiative slot {} belongs to a creature that isn't in the encounter", creature.name))),
+                    Some(owner) if owner.slot_of.is_some() => return Err(BackendError::argument_error("creatures", format!("The initiative slot {} belongs to another slot", creature.name))),
+                    _ => {}
+                }
+            }
+        }
 
-        creatures.into()
+        Ok(())
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct CreaturePatch {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initiative: Option<isize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initiative_modifier: Option<isize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub side: Option<Side>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hidden: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selected: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    /// Replaces the damage types listed in the creature's stat block, starting one if it has none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub damage_vulnerabilities: Option<Vec<DamageType>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub damage_resistances: Option<Vec<DamageType>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub damage_immunities: Option<Vec<DamageType>>
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CreatureData {
     id: Uuid,
     name: String,
     initiative: isize,
+    #[serde(default)]
+    initiative_modifier: isize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    hit_points: Option<HitPoints>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    hidden: bool,
+    #[serde(default)]
+    side: Side,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    slot_of: Option<Uuid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    card: Option<Card>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    card_edges: Vec<CardEdge>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    notes: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    stat_block: Option<StatBlock>,
 }
 
 impl From<&Creature> for CreatureData {
@@ -244,7 +538,16 @@ impl From<&Creature> for CreatureData {
         Self {
             id: value.id(),
             name: value.name().to_string(),
-            initiative: value.initiative()
+            initiative: value.initiative(),
+            initiative_modifier: value.initiative_modifier(),
+            hit_points: value.hit_points(),
+            hidden: value.hidden(),
+            side: value.side(),
+            slot_of: value.slot_of(),
+            card: value.card(),
+            card_edges: value.card_edges().to_vec(),
+            notes: value.notes().to_string(),
+            stat_block: value.stat_block().cloned()
         }
     }
 }
@@ -255,13 +558,74 @@ impl From<Creature> for CreatureData {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HitPoints {
+    current: usize,
+    maximum: usize
+}
+
+impl HitPoints {
+    pub fn new(maximum: usize) -> Self {
+        Self {
+            current: maximum,
+            maximum
+        }
+    }
+
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    pub fn maximum(&self) -> usize {
+        self.maximum
+    }
+
+    /// Changes the maximum hit points, keeping the same amount of damage taken.
+    pub fn set_maximum(&mut self, value: usize) {
+        let damage = self.maximum.saturating_sub(self.current);
+        self.maximum = value;
+        self.current = value.saturating_sub(damage);
+    }
+
+    pub fn damage(&mut self, amount: usize) {
+        self.current = self.current.saturating_sub(amount);
+    }
+
+    pub fn heal(&mut self, amount: usize) {
+        self.current = self.current.saturating_add(amount).min(self.maximum);
+    }
+}
+
+impl std::fmt::Display for HitPoints {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.current, self.maximum)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Creature {
     id: Uuid,
     name: String,
     selected: bool,
     initiative: isize,
-    sub_order: isize
+    /// Added to the d20 whenever the app rolls initiative for the creature.
+    #[serde(default)]
+    initiative_modifier: isize,
+    sub_order: isize,
+    hit_points: Option<HitPoints>,
+    /// Hidden creatures take turns as normal but are left out of anything shown to the players.
+    hidden: bool,
+    side: Side,
+    /// Set on the extra initiative slots of creatures that act more than once a round, pointing at the creature
+    /// they belong to. Slots keep their own initiative and place in the order, but share everything else with it.
+    slot_of: Option<Uuid>,
+    card: Option<Card>,
+    card_edges: Vec<CardEdge>,
+    /// Markdown notes for the GM, such as tactics and loot. Like hidden creatures, they're never shown to the players.
+    #[serde(default)]
+    notes: String,
+    #[serde(default)]
+    stat_block: Option<StatBlock>
 }
 
 impl From<&CreatureData> for Creature {
@@ -271,7 +635,16 @@ impl From<&CreatureData> for Creature {
             name: value.name.clone(),
             selected: false,
             initiative: value.initiative,
+            initiative_modifier: value.initiative_modifier,
             sub_order: 0,
+            hit_points: value.hit_points,
+            hidden: value.hidden,
+            side: value.side,
+            slot_of: value.slot_of,
+            card: value.card,
+            card_edges: value.card_edges.clone(),
+            notes: value.notes.clone(),
+            stat_block: value.stat_block.clone(),
         }
     }
 }
@@ -295,7 +668,16 @@ impl<T: Into<String>> From<T> for Creature {
             name: value.into(),
             selected: false,
             initiative: 0,
+            initiative_modifier: 0,
             sub_order: 0,
+            hit_points: None,
+            hidden: false,
+            side: Side::default(),
+            slot_of: None,
+            card: None,
+            card_edges: Vec::new(),
+            notes: String::new(),
+            stat_block: None,
         }
     }
 }
@@ -325,6 +707,19 @@ impl Creature {
         self.initiative = value;
     }
 
+    pub fn initiative_modifier(&self) -> isize {
+        self.initiative_modifier
+    }
+
+    pub fn set_initiative_modifier(&mut self, value: isize) {
+        self.initiative_modifier = value;
+    }
+
+    /// Rolls a d20 and adds the creature's initiative modifier.
+    pub fn roll_initiative(&self) -> isize {
+        rand::thread_rng().gen_range(1..=20) + self.initiative_modifier
+    }
+
     pub fn sub_order(&self) -> isize {
         self.sub_order
     }
@@ -332,13 +727,110 @@ impl Creature {
     pub fn set_sub_order(&mut self, value: isize) {
         self.sub_order = value;
     }
+
+    pub fn hit_points(&self) -> Option<HitPoints> {
+        self.hit_points
+    }
+
+    pub fn hit_points_mut(&mut self) -> Option<&mut HitPoints> {
+        self.hit_points.as_mut()
+    }
+
+    pub fn set_hit_points(&mut self, value: Option<HitPoints>) {
+        self.hit_points = value;
+    }
+
+    pub fn hidden(&self) -> bool {
+        self.hidden
+    }
+
+    pub fn set_hidden(&mut self, value: bool) {
+        self.hidden = value;
+    }
+
+    pub fn side(&self) -> Side {
+        self.side
+    }
+
+    pub fn set_side(&mut self, value: Side) {
+        self.side = value;
+    }
+
+    pub fn slot_of(&self) -> Option<Uuid> {
+        self.slot_of
+    }
+
+    pub fn is_slot(&self) -> bool {
+        self.slot_of.is_some()
+    }
+
+    pub fn card(&self) -> Option<Card> {
+        self.card
+    }
+
+    pub fn deal(&mut self, card: Card) {
+        self.initiative = card.initiative();
+        self.card = Some(card);
+    }
+
+    pub fn take_card(&mut self) -> Option<Card> {
+        self.card.take()
+    }
+
+    pub fn card_edges(&self) -> &[CardEdge] {
+        &self.card_edges
+    }
+
+    pub fn set_card_edges(&mut self, value: Vec<CardEdge>) {
+        self.card_edges = value;
+    }
+
+    pub fn notes(&self) -> &str {
+        &self.notes
+    }
+
+    pub fn set_notes(&mut self, value: impl Into<String>) {
+        self.notes = value.into();
+    }
+
+    pub fn stat_block(&self) -> Option<&StatBlock> {
+        self.stat_block.as_ref()
+    }
+
+    pub fn set_stat_block(&mut self, value: Option<StatBlock>) {
+        self.stat_block = value;
+    }
+
+    /// How the creature's stat block changes damage of the given type. Untyped damage, and damage to creatures
+    /// without a stat block, is taken as it is.
+    pub fn damage_adjustment(&self, damage_type: Option<DamageType>) -> DamageAdjustment {
+        match (&self.stat_block, damage_type) {
+            (Some(stat_block), Some(damage_type)) => stat_block.damage_adjustment(damage_type),
+            _ => DamageAdjustment::Normal
+        }
+    }
+
+    pub fn matches_search(&self, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        self.name.to_lowercase().contains(&query) || self.notes.to_lowercase().contains(&query)
+    }
+
+    pub fn new_slot(&self, initiative: isize) -> Creature {
+        Self {
+            name: self.name.clone(),
+            selected: self.selected,
+            initiative,
+            slot_of: Some(self.slot_of.unwrap_or(self.id)),
+            ..Creature::from("")
+        }
+    }
 }
 
 fn alphabetical_order(a: &Creature, b: &Creature) -> Ordering {
     a.name.to_lowercase().cmp(&b.name.to_lowercase())
 }
 
-fn initiative_order(a: &Creature, b: &Creature) -> Ordering {
+pub(crate) fn initiative_order(a: &Creature, b: &Creature) -> Ordering {
     match a.initiative.cmp(&b.initiative) {
         Ordering::Equal => {},
         ord => return ord
@@ -373,4 +865,87 @@ mod tests {
         println!("{:#?}", deserialized);
         assert_eq!(creature, deserialized);
     }
+
+    #[test]
+    fn test_validate() {
+        let boss = Creature::from("Boss");
+        let slot = boss.new_slot(10);
+        let valid = CreatureContainer::from(vec![boss.clone(), slot.clone()]);
+        assert!(EncounterData::from(&valid).validate().is_ok());
+
+        let orphan = CreatureContainer::from(vec![slot]);
+        assert!(EncounterData::from(&orphan).validate().is_err());
+
+        let duplicate = CreatureContainer::from(vec![boss.clone(), boss]);
+        assert!(EncounterData::from(&duplicate).validate().is_err());
+    }
+
+    #[test]
+    fn test_rename() {
+        let boss = Creature::from("Boss");
+        let slot = boss.new_slot(10);
+        let (boss_id, slot_id) = (boss.id(), slot.id());
+        let mut creatures = CreatureContainer::from(vec![boss, slot, Creature::from("Goblin")]);
+
+        creatures.rename(slot_id, "  Dragon ").unwrap();
+        assert_eq!(creatures.get(boss_id).unwrap().name(), "Dragon");
+        assert_eq!(creatures.get(slot_id).unwrap().name(), "Dragon");
+
+        // Changing the case of a creature's own name is fine, but taking another creature's name isn't.
+        creatures.rename(boss_id, "DRAGON").unwrap();
+        assert!(creatures.rename(boss_id, "goblin").is_err());
+        assert!(creatures.rename(boss_id, "   ").is_err());
+        assert!(creatures.rename(Uuid::new_v4(), "Orc").is_err());
+    }
+
+    #[test]
+    fn test_duplicate() {
+        let mut orc = Creature::from("Orc 2");
+        orc.set_initiative(15);
+        orc.set_hit_points(Some(HitPoints::new(20)));
+        orc.hit_points_mut().unwrap().damage(5);
+        let slot = orc.new_slot(5);
+        let orc_id = orc.id();
+        let mut creatures = CreatureContainer::from(vec![orc, slot, Creature::from("Orc 3")]);
+
+        let copy = creatures.duplicate(orc_id, Some(12)).unwrap();
+        assert_ne!(copy.id(), orc_id);
+        assert_eq!((copy.name(), copy.initiative()), ("Orc 4", 12));
+        assert_eq!(copy.hit_points().unwrap().current(), 20);
+
+        let slot_copy = creatures.slots_of(copy.id()).next().unwrap();
+        assert_eq!((slot_copy.name(), slot_copy.initiative()), ("Orc 4", 2));
+
+        // The copy has its own state, so damaging it leaves the original alone.
+        creatures.state_mut(slot_copy.id()).unwrap().hit_points_mut().unwrap().damage(10);
+        assert_eq!(creatures.get(orc_id).unwrap().hit_points().unwrap().current(), 15);
+
+        let copy = creatures.duplicate(copy.id(), None).unwrap();
+        assert_eq!((copy.name(), copy.initiative()), ("Orc 5", 12));
+        assert_eq!(creatures.len(), 7);
+
+        let mut goblins = CreatureContainer::from(vec![Creature::from("Goblin")]);
+        let id = goblins.iter().next().unwrap().id();
+        assert_eq!(goblins.duplicate(id, None).unwrap().name(), "Goblin 2");
+    }
+
+    #[test]
+    fn test_slots_share_state() {
+        let mut boss = Creature::from("Boss");
+        boss.set_initiative(20);
+        boss.set_selected(true);
+        let slot = boss.new_slot(10);
+        let (boss_id, slot_id) = (boss.id(), slot.id());
+
+        let mut creatures = CreatureContainer::from(vec![boss, slot, Creature::from("Goblin")]);
+        creatures.state_mut(slot_id).unwrap().set_hit_points(Some(HitPoints::new(50)));
+        assert_eq!(creatures.get(boss_id).unwrap().hit_points(), Some(HitPoints::new(50)));
+
+        creatures.get_mut(slot_id).unwrap().set_selected(false);
+        let finalized = creatures.finalize(&crate::initiative::IndividualInitiative);
+        assert_eq!(finalized.iter().map(|c| c.initiative()).collect::<Vec<_>>(), vec![20, 10]);
+
+        creatures.remove_with_slots(boss_id);
+        assert_eq!(creatures.len(), 1);
+    }
 }
\ No newline at end of file
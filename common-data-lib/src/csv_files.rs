@@ -0,0 +1,405 @@
+use std::{fs::File, path::Path};
+
+use serde::{Serialize, Deserialize};
+
+use crate::{BackendError, ToBackendResult, creatures::{Creature, CreatureContainer, HitPoints}, stat_block::StatBlock};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CsvColumn {
+    Name,
+    Initiative,
+    /// The initiative modifier, written with or without a sign like "+2".
+    Modifier,
+    /// The initiatives of a creature's extra initiative slots, separated by semicolons.
+    ExtraInitiatives,
+    /// Hit points as either the maximum, or the current and maximum written like "7/12".
+    HitPoints,
+    ArmorClass,
+    Selected,
+    Hidden,
+    Notes
+}
+
+impl CsvColumn {
+    pub fn all() -> &'static [CsvColumn] {
+        &[
+            CsvColumn::Name,
+            CsvColumn::Initiative,
+            CsvColumn::Modifier,
+            CsvColumn::ExtraInitiatives,
+            CsvColumn::HitPoints,
+            CsvColumn::ArmorClass,
+            CsvColumn::Selected,
+            CsvColumn::Hidden,
+            CsvColumn::Notes,
+        ]
+    }
+
+    /// Whether the column is left out of exports for the players, along with the hidden creatures themselves.
+    pub fn gm_only(&self) -> bool {
+        matches!(self, Self::Hidden | Self::Notes)
+    }
+
+    pub fn header(&self) -> &'static str {
+        match self {
+            Self::Name => "Name",
+            Self::Initiative => "Initiative",
+            Self::Modifier => "Modifier",
+            Self::ExtraInitiatives => "Extra initiatives",
+            Self::HitPoints => "HP",
+            Self::ArmorClass => "AC",
+            Self::Selected => "Selected",
+            Self::Hidden => "Hidden",
+            Self::Notes => "Notes",
+        }
+    }
+
+    pub fn from_header(header: &str) -> Option<Self> {
+        let header = header.trim();
+        Self::all().iter()
+            .copied()
+            .find(|column| column.header().eq_ignore_ascii_case(header))
+    }
+}
+
+impl std::fmt::Display for CsvColumn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.header())
+    }
+}
+
+/// Maps each column of a CSV file, by position, to the creature field it contains.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct CsvColumnMapping {
+    columns: Vec<Option<CsvColumn>>
+}
+
+impl From<Vec<Option<CsvColumn>>> for CsvColumnMapping {
+    fn from(value: Vec<Option<CsvColumn>>) -> Self {
+        Self { columns: value }
+    }
+}
+
+impl CsvColumnMapping {
+    /// Guesses a mapping by matching the headers against the column names used when exporting.
+    pub fn from_headers(headers: &[String]) -> Self {
+        let mut columns: Vec<Option<CsvColumn>> = Vec::with_capacity(headers.len());
+        for header in headers {
+            let column = CsvColumn::from_header(header)
+                .filter(|column| !columns.contains(&Some(*column)));
+
+            columns.push(column);
+        }
+
+        Self { columns }
+    }
+
+    pub fn columns(&self) -> &[Option<CsvColumn>] {
+        &self.columns
+    }
+
+    pub fn get(&self, index: usize) -> Option<CsvColumn> {
+        self.columns.get(index).copied().flatten()
+    }
+
+    pub fn set(&mut self, index: usize, column: Option<CsvColumn>) {
+        if index >= self.columns.len() {
+            self.columns.resize(index + 1, None);
+        }
+
+        // A field can only be read from one column, so clear any previous use of it.
+        if column.is_some() {
+            for existing in self.columns.iter_mut().filter(|c| **c == column) {
+                *existing = None;
+            }
+        }
+
+        self.columns[index] = column;
+    }
+
+    pub fn index_of(&self, column: CsvColumn) -> Option<usize> {
+        self.columns.iter().position(|c| *c == Some(column))
+    }
+
+    pub fn has_name(&self) -> bool {
+        self.index_of(CsvColumn::Name).is_some()
+    }
+}
+
+pub fn read_csv_headers(path: &Path) -> Result<Vec<String>, BackendError> {
+    let file = File::open(path).to_backend_result()?;
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(file);
+
+    let headers = reader.headers().to_backend_result()?;
+    Ok(headers.iter().map(|h| h.trim().to_string()).collect())
+}
+
+pub fn read_csv_creatures(path: &Path, mapping: &CsvColumnMapping) -> Result<Vec<Creature>, BackendError> {
+    let name_index = mapping.index_of(CsvColumn::Name)
+        .ok_or(BackendError::argument_error("mapping", "A column must be chosen for the creature names"))?;
+
+    let file = File::open(path).to_backend_result()?;
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(file);
+
+    let mut creatures = Vec::new();
+    let mut errors = Vec::new();
+    for (idx, record) in reader.records().enumerate() {
+        // Rows are numbered as they appear in a spreadsheet, so the header is row 1.
+        let row = idx + 2;
+        let record = match record {
+            Ok(record) => record,
+            Err(err) => {
+                errors.push(format!("Row {}: {}", row, err));
+                continue;
+            }
+        };
+
+        if record.iter().all(|field| field.trim().is_empty()) {
+            continue;
+        }
+
+        let name = record.get(name_index).unwrap_or_default().trim();
+        if name.is_empty() {
+            errors.push(format!("Row {}: Creature name is empty", row));
+            continue;
+        }
+
+        let mut creature = Creature::from(name);
+        let mut slot_initiatives = Vec::new();
+        let result = mapping.columns().iter()
+            .enumerate()
+            .filter_map(|(index, column)| column.map(|column| (column, record.get(index).unwrap_or_default())))
+            .try_for_each(|(column, value)| read_field(&mut creature, &mut slot_initiatives, column, value));
+
+        if let Err(err) = result {
+            errors.push(format!("Row {}: {}", row, err));
+            continue;
+        }
+
+        let slots = slot_initiatives.into_iter()
+            .map(|initiative| creature.new_slot(initiative))
+            .collect::<Vec<_>>();
+
+        creatures.push(creature);
+        creatures.extend(slots);
+    }
+
+    if !errors.is_empty() {
+        return Err(BackendError::argument_error("path", errors.join("\n")));
+    }
+
+    Ok(creatures)
+}
+
+/// Writes the creatures to a CSV file, one row per creature. Extra initiative slots have no row of their own and are
+/// written with the creature they belong to, so importing the file again brings back every creature and slot. Exports
+/// may be shared with players, so hidden creatures and GM only columns are only written to a copy for the GM.
+pub fn write_csv_creatures(path: &Path, creatures: &CreatureContainer, for_gm: bool) -> Result<(), BackendError> {
+    let file = File::create(path).to_backend_result()?;
+    let mut writer = csv::Writer::from_writer(file);
+
+    let columns = CsvColumn::all().iter()
+        .filter(|column| for_gm || !column.gm_only())
+        .collect::<Vec<_>>();
+
+    writer.write_record(columns.iter().map(|c| c.header())).to_backend_result()?;
+    for creature in creatures.iter().filter(|c| !c.is_slot() && (for_gm || !c.hidden())) {
+        let record = columns.iter()
+            .map(|column| match column {
+                CsvColumn::Name => creature.name().to_string(),
+                CsvColumn::Initiative => creature.initiative().to_string(),
+                CsvColumn::Modifier => format!("{:+}", creature.initiative_modifier()),
+                CsvColumn::ExtraInitiatives => creatures.slots_of(creature.id())
+                    .map(|slot| slot.initiative().to_string())
+                    .collect::<Vec<_>>()
+                    .join("; "),
+                CsvColumn::HitPoints => match creature.hit_points() {
+                    Some(hp) if hp.current() == hp.maximum() => hp.maximum().to_string(),
+                    Some(hp) => format!("{}/{}", hp.current(), hp.maximum()),
+                    None => String::new()
+                },
+                CsvColumn::ArmorClass => creature.stat_block().map(|block| block.armor_class.to_string()).unwrap_or_default(),
+                CsvColumn::Selected => creature.selected().to_string(),
+                CsvColumn::Hidden => creature.hidden().to_string(),
+                CsvColumn::Notes => creature.notes().to_string(),
+            });
+
+        writer.write_record(record).to_backend_result()?;
+    }
+
+    writer.flush().to_backend_result()
+}
+
+/// Reads one field of a row into the creature, or explains why the value couldn't be used.
+fn read_field(creature: &mut Creature, slot_initiatives: &mut Vec<isize>, column: CsvColumn, value: &str) -> Result<(), String> {
+    let invalid = |what: &str| format!("'{}' is not a valid {}", value, what);
+
+    match column {
+        CsvColumn::Name => {},
+        CsvColumn::Initiative => creature.set_initiative(parse_initiative(value).ok_or_else(|| invalid("initiative"))?),
+        CsvColumn::Modifier => creature.set_initiative_modifier(parse_initiative(value).ok_or_else(|| invalid("modifier"))?),
+        CsvColumn::ExtraInitiatives => *slot_initiatives = parse_extra_initiatives(value).ok_or_else(|| invalid("list of extra initiatives"))?,
+        CsvColumn::HitPoints => creature.set_hit_points(parse_hit_points(value).ok_or_else(|| invalid("hit points value"))?),
+        CsvColumn::ArmorClass => {
+            if let Some(armor_class) = parse_armor_class(value).ok_or_else(|| invalid("armor class"))? {
+                creature.set_stat_block(Some(StatBlock { armor_class, ..StatBlock::default() }));
+            }
+        },
+        CsvColumn::Selected => creature.set_selected(parse_bool(value).ok_or_else(|| invalid("selected value"))?),
+        CsvColumn::Hidden => creature.set_hidden(parse_bool(value).ok_or_else(|| invalid("hidden value"))?),
+        CsvColumn::Notes => creature.set_notes(value.trim()),
+    }
+
+    Ok(())
+}
+
+fn parse_initiative(value: &str) -> Option<isize> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Some(0);
+    }
+
+    value.trim_start_matches('+').parse().ok()
+}
+
+fn parse_extra_initiatives(value: &str) -> Option<Vec<isize>> {
+    value.split(';')
+        .map(str::trim)
+        .filter(|initiative| !initiative.is_empty())
+        .map(|initiative| initiative.trim_start_matches('+').parse().ok())
+        .collect()
+}
+
+fn parse_hit_points(value: &str) -> Option<Option<HitPoints>> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Some(None);
+    }
+
+    let (current, maximum) = match value.split_once('/') {
+        Some((current, maximum)) => (current.trim().parse::<usize>().ok()?, maximum.trim().parse::<usize>().ok()?),
+        None => {
+            let maximum = value.parse::<usize>().ok()?;
+            (maximum, maximum)
+        }
+    };
+
+    if current > maximum {
+        return None;
+    }
+
+    let mut hit_points = HitPoints::new(maximum);
+    hit_points.damage(maximum - current);
+    Some(Some(hit_points))
+}
+
+fn parse_armor_class(value: &str) -> Option<Option<usize>> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Some(None);
+    }
+
+    value.parse().ok().map(Some)
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "" | "false" | "no" | "n" | "0" => Some(false),
+        "true" | "yes" | "y" | "1" | "x" => Some(true),
+        _ => None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mapping_from_headers() {
+        let headers = vec!["initiative".to_string(), "Speed".to_string(), " NAME ".to_string(), "Name".to_string(), "hp".to_string()];
+        let mapping = CsvColumnMapping::from_headers(&headers);
+
+        assert_eq!(mapping.columns(), &[Some(CsvColumn::Initiative), None, Some(CsvColumn::Name), None, Some(CsvColumn::HitPoints)]);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let path = std::env::temp_dir().join(format!("{}.csv", uuid::Uuid::new_v4()));
+        let mut goblin = Creature::from("Goblin, the \"Bold\"");
+        goblin.set_initiative(-2);
+        goblin.set_initiative_modifier(3);
+        goblin.set_selected(true);
+        goblin.set_notes("Carries the *cell key*,\nand a torch");
+        let mut hit_points = HitPoints::new(12);
+        hit_points.damage(5);
+        goblin.set_hit_points(Some(hit_points));
+        goblin.set_stat_block(Some(StatBlock { armor_class: 15, ..StatBlock::default() }));
+
+        let mut boss = Creature::from("Boss");
+        boss.set_hidden(true);
+        boss.set_initiative(15);
+        let slot = boss.new_slot(5);
+        let container = CreatureContainer::from(vec![goblin, Creature::from("Orc"), boss, slot]);
+
+        write_csv_creatures(&path, &container, true).unwrap();
+        let headers = read_csv_headers(&path).unwrap();
+        let creatures = read_csv_creatures(&path, &CsvColumnMapping::from_headers(&headers)).unwrap();
+
+        // The players' copy leaves out the hidden boss along with its slot, and the GM's notes.
+        write_csv_creatures(&path, &container, false).unwrap();
+        let players_copy = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(!players_copy.contains("Boss"));
+        assert!(!players_copy.contains("cell key"));
+        assert!(!players_copy.contains("Hidden"));
+        assert_eq!(players_copy.lines().count(), 3);
+
+        assert_eq!(creatures.len(), 4);
+        let find = |name: &str| creatures.iter().find(|c| c.name() == name && !c.is_slot()).unwrap();
+        let goblin = find("Goblin, the \"Bold\"");
+        assert_eq!(goblin.initiative(), -2);
+        assert_eq!(goblin.initiative_modifier(), 3);
+        assert!(goblin.selected());
+        assert_eq!(goblin.notes(), "Carries the *cell key*,\nand a torch");
+        assert_eq!(goblin.hit_points().map(|hp| (hp.current(), hp.maximum())), Some((7, 12)));
+        assert_eq!(goblin.stat_block().map(|block| block.armor_class), Some(15));
+
+        let orc = find("Orc");
+        assert!(!orc.selected());
+        assert!(orc.hit_points().is_none() && orc.stat_block().is_none());
+
+        // Hidden creatures and their slots come back as they were.
+        let boss = find("Boss");
+        assert!(boss.hidden());
+        let slots = creatures.iter().filter(|c| c.slot_of() == Some(boss.id())).collect::<Vec<_>>();
+        assert_eq!(slots.len(), 1);
+        assert_eq!(slots[0].initiative(), 5);
+    }
+
+    #[test]
+    fn test_row_errors() {
+        let path = std::env::temp_dir().join(format!("{}.csv", uuid::Uuid::new_v4()));
+        std::fs::write(&path, "Init,Who,Health\n12,Goblin,7\nabc,Orc,\n3,,\n4,Ogre,9/5\n").unwrap();
+
+        let mut mapping = CsvColumnMapping::default();
+        mapping.set(0, Some(CsvColumn::Initiative));
+        mapping.set(1, Some(CsvColumn::Name));
+        mapping.set(2, Some(CsvColumn::HitPoints));
+        let result = read_csv_creatures(&path, &mapping);
+        std::fs::remove_file(&path).unwrap();
+
+        match result {
+            Err(BackendError::ArgumentError { message, .. }) => {
+                assert!(message.contains("Row 3"));
+                assert!(message.contains("Row 4"));
+                assert!(message.contains("Row 5: '9/5' is not a valid hit points value"));
+                assert!(!message.contains("Row 2"));
+            },
+            other => panic!("Expected an argument error, got {:?}", other)
+        }
+    }
+}
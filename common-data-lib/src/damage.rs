@@ -0,0 +1,202 @@
+use serde::{Serialize, Deserialize};
+
+use crate::BackendError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DamageType {
+    Acid,
+    Bludgeoning,
+    Cold,
+    Fire,
+    Force,
+    Lightning,
+    Necrotic,
+    Piercing,
+    Poison,
+    Psychic,
+    Radiant,
+    Slashing,
+    Thunder
+}
+
+impl DamageType {
+    pub fn all() -> &'static [DamageType] {
+        &[
+            DamageType::Acid, DamageType::Bludgeoning, DamageType::Cold, DamageType::Fire, DamageType::Force,
+            DamageType::Lightning, DamageType::Necrotic, DamageType::Piercing, DamageType::Poison, DamageType::Psychic,
+            DamageType::Radiant, DamageType::Slashing, DamageType::Thunder
+        ]
+    }
+
+    /// Finds the damage type with the given name, ignoring case.
+    pub fn parse(name: &str) -> Option<DamageType> {
+        let name = name.trim();
+        DamageType::all().iter()
+            .copied()
+            .find(|t| t.to_string().eq_ignore_ascii_case(name))
+    }
+}
+
+impl std::fmt::Display for DamageType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Acid => write!(f, "acid"),
+            Self::Bludgeoning => write!(f, "bludgeoning"),
+            Self::Cold => write!(f, "cold"),
+            Self::Fire => write!(f, "fire"),
+            Self::Force => write!(f, "force"),
+            Self::Lightning => write!(f, "lightning"),
+            Self::Necrotic => write!(f, "necrotic"),
+            Self::Piercing => write!(f, "piercing"),
+            Self::Poison => write!(f, "poison"),
+            Self::Psychic => write!(f, "psychic"),
+            Self::Radiant => write!(f, "radiant"),
+            Self::Slashing => write!(f, "slashing"),
+            Self::Thunder => write!(f, "thunder"),
+        }
+    }
+}
+
+/// One part of a hit, so a flaming sword deals a slashing part and a fire part. Damage without a type is never
+/// adjusted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Damage {
+    pub amount: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub damage_type: Option<DamageType>
+}
+
+impl Damage {
+    pub fn new(amount: usize, damage_type: Option<DamageType>) -> Self {
+        Self { amount, damage_type }
+    }
+}
+
+impl std::fmt::Display for Damage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.damage_type {
+            Some(damage_type) => write!(f, "{} {}", self.amount, damage_type),
+            None => write!(f, "{}", self.amount),
+        }
+    }
+}
+
+/// How a creature's defenses change the damage it takes of one type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum DamageAdjustment {
+    #[default]
+    Normal,
+    /// Halved, rounding down.
+    Resisted,
+    Vulnerable,
+    /// Halved and then doubled, so only an odd point is lost.
+    ResistedAndVulnerable,
+    Immune
+}
+
+impl DamageAdjustment {
+    pub fn apply(&self, amount: usize) -> usize {
+        match self {
+            Self::Normal => amount,
+            Self::Resisted => amount / 2,
+            Self::Vulnerable => amount.saturating_mul(2),
+            Self::ResistedAndVulnerable => (amount / 2).saturating_mul(2),
+            Self::Immune => 0,
+        }
+    }
+}
+
+impl std::fmt::Display for DamageAdjustment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Normal => write!(f, "normal"),
+            Self::Resisted => write!(f, "resisted"),
+            Self::Vulnerable => write!(f, "vulnerable"),
+            Self::ResistedAndVulnerable => write!(f, "resisted and vulnerable"),
+            Self::Immune => write!(f, "immune"),
+        }
+    }
+}
+
+/// Reads damage written the way it's rolled, with its parts separated by `+` or commas, like "8 slashing + 4 fire".
+/// A part can leave out its type, so a plain number is untyped damage.
+pub fn parse_damage(text: &str) -> Result<Vec<Damage>, BackendError> {
+    let invalid = |message: String| BackendError::argument_error("damage", message);
+
+    let parts = text.split(['+', ','])
+        .map(str::trim)
+        .collect::<Vec<_>>();
+
+    if parts.iter().all(|part| part.is_empty()) {
+        return Err(invalid(String::from("No damage was given")));
+    }
+
+    parts.into_iter()
+        .map(|part| {
+            let (amount, damage_type) = part.split_once(char::is_whitespace).unwrap_or((part, ""));
+            let amount = amount.parse::<usize>()
+                .map_err(|_| invalid(format!("'{}' doesn't start with an amount of damage", part)))?;
+
+            let damage_type = match damage_type.trim() {
+                "" => None,
+                name => Some(DamageType::parse(name).ok_or_else(|| invalid(format!("'{}' is not a damage type", name)))?)
+            };
+
+            Ok(Damage::new(amount, damage_type))
+        })
+        .collect()
+}
+
+/// Reads a list of damage types separated by commas, like "fire, cold". Each type is only kept once.
+pub fn parse_damage_types(text: &str) -> Result<Vec<DamageType>, BackendError> {
+    let mut types = Vec::new();
+    for name in text.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+        let damage_type = DamageType::parse(name)
+            .ok_or_else(|| BackendError::argument_error("damage_types", format!("'{}' is not a damage type", name)))?;
+
+        if !types.contains(&damage_type) {
+            types.push(damage_type);
+        }
+    }
+
+    Ok(types)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_damage() {
+        assert_eq!(parse_damage("12").unwrap(), vec![Damage::new(12, None)]);
+        assert_eq!(
+            parse_damage(" 8 Slashing + 4 fire, 3").unwrap(),
+            vec![Damage::new(8, Some(DamageType::Slashing)), Damage::new(4, Some(DamageType::Fire)), Damage::new(3, None)]
+        );
+
+        assert!(parse_damage("").is_err());
+        assert!(parse_damage("8 slashing +").is_err());
+        assert!(parse_damage("fire").is_err());
+        assert!(parse_damage("8 sonic").is_err());
+    }
+
+    #[test]
+    fn test_parse_damage_types() {
+        assert_eq!(parse_damage_types("").unwrap(), vec![]);
+        assert_eq!(parse_damage_types(" Fire, cold,, fire ").unwrap(), vec![DamageType::Fire, DamageType::Cold]);
+
+        assert!(parse_damage_types("fire, sonic").is_err());
+        assert!(parse_damage_types("fire cold").is_err());
+    }
+
+    #[test]
+    fn test_adjustments() {
+        assert_eq!(DamageAdjustment::Normal.apply(7), 7);
+        assert_eq!(DamageAdjustment::Resisted.apply(7), 3);
+        assert_eq!(DamageAdjustment::Vulnerable.apply(7), 14);
+        assert_eq!(DamageAdjustment::ResistedAndVulnerable.apply(7), 6);
+        assert_eq!(DamageAdjustment::Immune.apply(7), 0);
+    }
+}
@@ -0,0 +1,108 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+
+/// Tracks the file the current encounter belongs to and whether it has changed since it was last saved.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct DocumentState {
+    path: Option<PathBuf>,
+    dirty: bool
+}
+
+impl DocumentState {
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    pub fn dirty(&self) -> bool {
+        self.dirty
+    }
+
+    pub fn name(&self) -> String {
+        self.path.as_ref()
+            .and_then(|path| path.file_stem())
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or(String::from("Untitled"))
+    }
+
+    pub fn title(&self, app_name: &str) -> String {
+        let marker = if self.dirty { "*" } else { "" };
+        format!("{}{} - {}", self.name(), marker, app_name)
+    }
+
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    pub fn mark_clean(&mut self) {
+        self.dirty = false;
+    }
+
+    /// Associates the encounter with the file it was just loaded from or saved to.
+    pub fn set_path(&mut self, path: impl Into<PathBuf>) {
+        self.path = Some(path.into());
+        self.dirty = false;
+    }
+
+    pub fn reset(&mut self) {
+        self.path = None;
+        self.dirty = false;
+    }
+}
+
+/// Summarises one of the open encounters for the tab strip.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncounterTab {
+    id: Uuid,
+    name: String,
+    dirty: bool,
+    in_progress: bool
+}
+
+impl EncounterTab {
+    pub fn new(id: Uuid, name: impl Into<String>, dirty: bool, in_progress: bool) -> Self {
+        Self { id, name: name.into(), dirty, in_progress }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Whether the initiative order has been finalized and turns are being taken.
+    pub fn in_progress(&self) -> bool {
+        self.in_progress
+    }
+}
+
+/// The open encounters in tab order, along with the one being shown.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncounterTabs {
+    pub tabs: Vec<EncounterTab>,
+    pub current: Uuid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_title() {
+        let mut document = DocumentState::default();
+        assert_eq!(document.title("App"), "Untitled - App");
+
+        document.mark_dirty();
+        assert_eq!(document.title("App"), "Untitled* - App");
+
+        document.set_path("/encounters/Goblin ambush.toml");
+        assert_eq!(document.title("App"), "Goblin ambush - App");
+    }
+}
@@ -0,0 +1,618 @@
+use std::ops::Deref;
+
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+
+use crate::{cards::Deck, creatures::{Creature, CreatureContainer}, initiative::InitiativeMode, BackendError};
+
+/// Why a creature has stepped out of the initiative order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WaitReason {
+    /// Waiting to act later in the round, taking a new initiative when it does (Pathfinder's Delay).
+    Delay,
+    /// Waiting for a trigger to act on, keeping its initiative (5e's Ready).
+    Ready
+}
+
+impl std::fmt::Display for WaitReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Delay => write!(f, "Delaying"),
+            Self::Ready => write!(f, "Readied"),
+        }
+    }
+}
+
+/// Where a waiting creature re-enters the order, relative to the creature whose turn it is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ResumePosition {
+    Before,
+    After
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WaitingCreature {
+    id: Uuid,
+    reason: WaitReason,
+    round: usize
+}
+
+impl WaitingCreature {
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn reason(&self) -> WaitReason {
+        self.reason
+    }
+
+    /// The round the creature started waiting in.
+    pub fn round(&self) -> usize {
+        self.round
+    }
+}
+
+/// A finalized initiative order along with whose turn it is. Only the IDs of the creatures taking part are kept, with
+/// everything else about them, including their initiatives and cards, living in the encounter's creature list.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActiveEncounter {
+    order: Vec<Uuid>,
+    current_turn: usize,
+    round: usize,
+    waiting: Vec<WaitingCreature>,
+    mode: InitiativeMode,
+    /// The creatures that have taken their turn this round, in the order they took it.
+    acted: Vec<Uuid>,
+    /// Creatures that joined the order ahead of whoever's turn it was, who wait until the next round to act.
+    #[serde(default)]
+    arrived: Vec<Uuid>,
+    /// Set at the start of a round when the mode needs everyone to roll initiative again.
+    awaiting_initiative: bool
+}
+
+impl Default for ActiveEncounter {
+    fn default() -> Self {
+        Self::new(Vec::new(), InitiativeMode::default())
+    }
+}
+
+impl ActiveEncounter {
+    /// Starts an encounter with creatures that have already been put in order by the mode's rules.
+    pub fn new(order: Vec<Uuid>, mode: InitiativeMode) -> Self {
+        Self {
+            order,
+            current_turn: 0,
+            round: 1,
+            waiting: Vec::new(),
+            mode,
+            acted: Vec::new(),
+            arrived: Vec::new(),
+            awaiting_initiative: false
+        }
+    }
+
+    /// The IDs of the creatures and initiative slots in turn order.
+    pub fn order(&self) -> &[Uuid] {
+        &self.order
+    }
+
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    pub fn current_turn(&self) -> usize {
+        self.current_turn
+    }
+
+    pub fn round(&self) -> usize {
+        self.round
+    }
+
+    pub fn active_id(&self) -> Option<Uuid> {
+        self.order.get(self.current_turn).copied()
+    }
+
+    pub fn waiting(&self) -> &[WaitingCreature] {
+        &self.waiting
+    }
+
+    pub fn mode(&self) -> InitiativeMode {
+        self.mode
+    }
+
+    /// Whether a creature has had its turn this round, or joined too late in the round to have one.
+    pub fn has_acted(&self, id: Uuid) -> bool {
+        self.acted.contains(&id) || self.arrived.contains(&id)
+    }
+
+    /// Whether the round can't start until everyone has rolled initiative again.
+    pub fn awaiting_initiative(&self) -> bool {
+        self.awaiting_initiative
+    }
+
+    pub fn index_of(&self, id: Uuid) -> Option<usize> {
+        self.order.iter().position(|&i| i == id)
+    }
+
+    /// Whether a creature is in the order or waiting to re-enter it.
+    pub fn contains(&self, id: Uuid) -> bool {
+        self.index_of(id).is_some() || self.waiting.iter().any(|w| w.id == id)
+    }
+
+    /// Moves on to the next creature, starting a new round after the last. A round that is waiting for new
+    /// initiatives can't be played until they are in.
+    pub fn next_turn(&mut self) -> Result<(), BackendError> {
+        if self.awaiting_initiative {
+            return Err(BackendError::logic_error("Cannot change turn until initiative has been rolled for the new round"));
+        }
+
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        if let Some(id) = self.active_id() {
+            self.acted.push(id);
+        }
+
+        let rules = self.mode.rules();
+        match rules.next_in_round(self) {
+            Some(index) => self.current_turn = index,
+            None => self.start_round()
+        }
+
+        Ok(())
+    }
+
+    /// Goes back to the creature that acted last, or to the end of the previous round.
+    pub fn previous_turn(&mut self) {
+        if self.is_empty() {
+            return;
+        }
+
+        self.awaiting_initiative = false;
+        while let Some(id) = self.acted.pop() {
+            if let Some(index) = self.index_of(id) {
+                self.current_turn = index;
+                return;
+            }
+        }
+
+        if self.round > 1 {
+            self.current_turn = self.len() - 1;
+            self.round -= 1;
+            self.acted = self.order[..self.current_turn].to_vec();
+        }
+    }
+
+    /// Hands the turn to a creature of the active creature's choosing, for modes where the active creature picks
+    /// who goes next. Only creatures that haven't acted yet can be picked, unless everyone has acted, in which
+    /// case the pick starts the next round.
+    pub fn pass_turn_to(&mut self, id: Uuid) -> Result<(), BackendError> {
+        if !self.mode.rules().active_creature_chooses_next() {
+            return Err(BackendError::logic_error(format!("The turn can't be passed on in {} initiative", self.mode)));
+        }
+
+        let index = self.index_of(id)
+            .ok_or_else(|| BackendError::argument_error("id", "No creature with this ID is in the initiative order"))?;
+        let active_id = self.active_id();
+        let round_over = self.order.iter().all(|&i| Some(i) == active_id || self.has_acted(i));
+
+        if !round_over && (Some(id) == active_id || self.has_acted(id)) {
+            return Err(BackendError::argument_error("id", "This creature has already acted this round"));
+        }
+
+        if round_over {
+            self.start_round();
+        } else if let Some(active_id) = active_id {
+            self.acted.push(active_id);
+        }
+
+        self.current_turn = index;
+        Ok(())
+    }
+
+    /// Sets the initiatives rolled at the start of a round and puts the order back together using them.
+    pub fn set_round_initiatives(&mut self, initiatives: &[(Uuid, isize)], creatures: &mut CreatureContainer) -> Result<(), BackendError> {
+        if let Some((id, _)) = initiatives.iter().find(|(id, _)| self.index_of(*id).is_none()) {
+            return Err(BackendError::argument_error("initiatives", format!("No creature with the ID {} is in the initiative order", id)));
+        }
+
+        for (id, initiative) in initiatives {
+            if let Some(creature) = creatures.get_mut(*id) {
+                creature.set_initiative(*initiative);
+            }
+        }
+
+        self.start_ordered_round(creatures);
+        Ok(())
+    }
+
+    /// Deals everyone in the order a new card at the start of a round and puts the order back together using them.
+    /// Waiting creatures keep the card they were dealt when they started waiting.
+    pub fn deal_round(&mut self, deck: &mut Deck, creatures: &mut CreatureContainer) {
+        let dealt = self.order.iter()
+            .filter_map(|&id| creatures.get_mut(id).and_then(|c| c.take_card()))
+            .collect::<Vec<_>>();
+        deck.start_round(dealt);
+
+        for &id in self.order.iter() {
+            let edges = creatures.state(id).map(|c| c.card_edges().to_vec()).unwrap_or_default();
+            let card = deck.deal(&edges);
+            if let Some(creature) = creatures.get_mut(id) {
+                creature.deal(card);
+            }
+        }
+
+        self.start_ordered_round(creatures);
+    }
+
+    /// Puts the order back together from the creatures' new initiatives, starting the round from the top.
+    fn start_ordered_round(&mut self, creatures: &CreatureContainer) {
+        self.order = creatures.order_by_rules(&self.order, self.mode.rules().as_ref());
+        self.current_turn = 0;
+        self.arrived.clear();
+        self.awaiting_initiative = false;
+    }
+
+    /// Moves on to the top of the next round, however the last round ended. Modes that roll again each round wait
+    /// for the new initiatives before it can be played.
+    fn start_round(&mut self) {
+        self.current_turn = 0;
+        self.round += 1;
+        self.acted.clear();
+        self.arrived.clear();
+        self.awaiting_initiative = self.mode.rules().rerolls_each_round();
+    }
+
+    /// Adds a creature to the end of the round.
+    pub fn push(&mut self, id: Uuid) {
+        self.order.push(id);
+    }
+
+    /// Adds a creature part way through the round at the given position. Creatures that arrive ahead of whoever's
+    /// turn it is don't take it from them, and wait until the next round to act.
+    pub fn insert(&mut self, index: usize, id: Uuid) {
+        let index = index.min(self.len());
+        let arrives_late = !self.is_empty() && index <= self.current_turn;
+        self.order.insert(index, id);
+
+        if arrives_late {
+            self.current_turn += 1;
+            self.arrived.push(id);
+        }
+    }
+
+    /// Removes an entry from the order or the waiting creatures, passing the turn on to the next creature if it was
+    /// their turn. Returns false if the entry isn't in the encounter.
+    pub fn remove(&mut self, id: Uuid) -> bool {
+        if let Some(index) = self.waiting.iter().position(|w| w.id == id) {
+            self.waiting.remove(index);
+            return true;
+        }
+
+        let index = match self.index_of(id) {
+            Some(index) => index,
+            None => return false
+        };
+
+        self.order.remove(index);
+        self.acted.retain(|acted_id| *acted_id != id);
+        self.arrived.retain(|arrived_id| *arrived_id != id);
+
+        if index < self.current_turn {
+            self.current_turn -= 1;
+        } else if self.current_turn >= self.len() && self.current_turn > 0 {
+            self.start_round();
+        }
+
+        true
+    }
+
+    /// Moves a creature to a new position in the order without changing whose turn it is.
+    pub fn move_creature(&mut self, move_index: usize, target_index: usize) {
+        let active_id = self.active_id();
+        let id = self.order.remove(move_index);
+        self.order.insert(target_index, id);
+
+        if let Some(index) = active_id.and_then(|id| self.index_of(id)) {
+            self.current_turn = index;
+        }
+    }
+
+    /// Takes the active creature out of the order to wait, passing the turn on to the next creature.
+    pub fn wait_active(&mut self, reason: WaitReason) -> Option<&WaitingCreature> {
+        let id = self.active_id()?;
+        let round = self.round;
+        self.remove(id);
+        self.waiting.push(WaitingCreature { id, reason, round });
+
+        self.waiting.last()
+    }
+
+    /// Puts a waiting creature back into the order next to the active creature, returning where it was put. Creatures
+    /// that resume before the active creature take the current turn. Delaying creatures take on the initiative of the
+    /// creature they resume next to.
+    pub fn resume(&mut self, id: Uuid, position: ResumePosition, creatures: &mut CreatureContainer) -> Option<usize> {
+        let index = self.waiting.iter().position(|w| w.id == id)?;
+        let WaitingCreature { reason, .. } = self.waiting.remove(index);
+
+        if reason == WaitReason::Delay {
+            let active_initiative = self.active_id().and_then(|active_id| creatures.get(active_id)).map(|c| c.initiative());
+            if let (Some(initiative), Some(creature)) = (active_initiative, creatures.get_mut(id)) {
+                creature.set_initiative(initiative);
+            }
+        }
+
+        let insert_index = match position {
+            _ if self.is_empty() => 0,
+            ResumePosition::Before => self.current_turn,
+            ResumePosition::After => self.current_turn + 1
+        };
+
+        self.order.insert(insert_index, id);
+        Some(insert_index)
+    }
+
+    /// Moves a creature to the place the initiative mode's rules give it among the rest of the order.
+    pub fn move_to_initiative(&mut self, id: Uuid, creatures: &CreatureContainer) -> Option<usize> {
+        let move_index = self.index_of(id)?;
+        let others = self.order.iter().copied().filter(|&i| i != id).collect::<Vec<_>>();
+        let target_index = creatures.initiative_position(&others, id, self.mode.rules().as_ref());
+
+        self.move_creature(move_index, target_index);
+
+        Some(target_index)
+    }
+}
+
+/// An active encounter together with the creature list its IDs refer to, which is what the app is sent to show it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct EncounterView {
+    encounter: ActiveEncounter,
+    creatures: CreatureContainer
+}
+
+impl Deref for EncounterView {
+    type Target = ActiveEncounter;
+
+    fn deref(&self) -> &Self::Target {
+        &self.encounter
+    }
+}
+
+impl EncounterView {
+    pub fn new(encounter: ActiveEncounter, creatures: CreatureContainer) -> Self {
+        Self { encounter, creatures }
+    }
+
+    pub fn get(&self, id: Uuid) -> Option<&Creature> {
+        self.creatures.get(id)
+    }
+
+    /// Gets the creature holding the hit points and other state for an entry in the order, which is the creature
+    /// an extra initiative slot belongs to, or the entry itself.
+    pub fn state(&self, id: Uuid) -> Option<&Creature> {
+        self.creatures.state(id)
+    }
+
+    pub fn get_by_index(&self, index: usize) -> Option<&Creature> {
+        self.encounter.order.get(index).and_then(|&id| self.get(id))
+    }
+
+    pub fn active_creature(&self) -> Option<&Creature> {
+        self.get_by_index(self.encounter.current_turn)
+    }
+
+    /// The creatures in turn order.
+    pub fn creatures(&self) -> impl Iterator<Item = &Creature> {
+        self.encounter.order.iter().filter_map(|&id| self.get(id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_encounter(mode: InitiativeMode) -> (ActiveEncounter, CreatureContainer) {
+        creatures_encounter(vec![
+            Creature::from("A"),
+            Creature::from("B"),
+            Creature::from("C"),
+        ], mode)
+    }
+
+    fn creatures_encounter(creatures: Vec<Creature>, mode: InitiativeMode) -> (ActiveEncounter, CreatureContainer) {
+        let ids = creatures.iter().map(|c| c.id()).collect();
+        (ActiveEncounter::new(ids, mode), CreatureContainer::from(creatures))
+    }
+
+    fn active_name(encounter: &ActiveEncounter, creatures: &CreatureContainer) -> String {
+        creatures.get(encounter.active_id().unwrap()).unwrap().name().to_string()
+    }
+
+    fn names(encounter: &ActiveEncounter, creatures: &CreatureContainer) -> Vec<String> {
+        EncounterView::new(encounter.clone(), creatures.clone()).creatures().map(|c| c.name().to_string()).collect()
+    }
+
+    #[test]
+    fn test_turn_advancement() {
+        let (mut encounter, _) = get_encounter(InitiativeMode::Individual);
+        encounter.previous_turn();
+        assert_eq!((encounter.current_turn(), encounter.round()), (0, 1));
+
+        encounter.next_turn().unwrap();
+        encounter.next_turn().unwrap();
+        encounter.next_turn().unwrap();
+        assert_eq!((encounter.current_turn(), encounter.round()), (0, 2));
+
+        encounter.previous_turn();
+        assert_eq!((encounter.current_turn(), encounter.round()), (2, 1));
+    }
+
+    #[test]
+    fn test_changes_keep_active_creature() {
+        let (mut encounter, creatures) = get_encounter(InitiativeMode::Individual);
+        encounter.next_turn().unwrap();
+
+        encounter.move_creature(1, 2);
+        assert_eq!(active_name(&encounter, &creatures), "B");
+
+        encounter.remove(encounter.order()[0]);
+        assert_eq!(active_name(&encounter, &creatures), "B");
+
+        encounter.remove(encounter.active_id().unwrap());
+        assert_eq!(active_name(&encounter, &creatures), "C");
+        assert_eq!(encounter.round(), 2);
+        assert!(!encounter.remove(Uuid::new_v4()));
+    }
+
+    #[test]
+    fn test_wait_and_resume() {
+        let (mut encounter, mut creatures) = get_encounter(InitiativeMode::Individual);
+        let ids = encounter.order().to_vec();
+        creatures.get_mut(ids[1]).unwrap().set_initiative(10);
+        encounter.next_turn().unwrap();
+        encounter.next_turn().unwrap();
+
+        // Delaying the last creature in the order starts the next round.
+        encounter.wait_active(WaitReason::Delay);
+        assert_eq!((active_name(&encounter, &creatures).as_str(), encounter.round()), ("A", 2));
+        assert!(encounter.contains(ids[2]));
+
+        encounter.next_turn().unwrap();
+        assert_eq!(encounter.resume(ids[2], ResumePosition::Before, &mut creatures), Some(1));
+        assert_eq!(active_name(&encounter, &creatures), "C");
+        assert_eq!(creatures.get(ids[2]).unwrap().initiative(), 10);
+
+        encounter.next_turn().unwrap();
+        assert_eq!(active_name(&encounter, &creatures), "B");
+
+        encounter.wait_active(WaitReason::Ready);
+        assert_eq!((active_name(&encounter, &creatures).as_str(), encounter.round()), ("A", 3));
+        encounter.resume(ids[1], ResumePosition::After, &mut creatures);
+        assert_eq!(names(&encounter, &creatures), vec!["A", "B", "C"]);
+        assert!(encounter.waiting().is_empty());
+        assert_eq!(creatures.get(ids[1]).unwrap().initiative(), 10);
+    }
+
+    #[test]
+    fn test_move_to_initiative() {
+        let (mut encounter, mut creatures) = get_encounter(InitiativeMode::Individual);
+        let ids = encounter.order().to_vec();
+        creatures.get_mut(ids[0]).unwrap().set_initiative(15);
+        creatures.get_mut(ids[1]).unwrap().set_initiative(10);
+        creatures.get_mut(ids[2]).unwrap().set_initiative(12);
+        creatures.get_mut(ids[2]).unwrap().set_hidden(true);
+        encounter.next_turn().unwrap();
+
+        assert_eq!(encounter.move_to_initiative(ids[2], &creatures), Some(1));
+        assert_eq!(active_name(&encounter, &creatures), "B");
+    }
+
+    #[test]
+    fn test_slots() {
+        let mut boss = Creature::from("Boss");
+        boss.set_initiative(20);
+        boss.set_hidden(true);
+        let slot = boss.new_slot(5);
+        let mut goblin = Creature::from("Goblin");
+        goblin.set_initiative(10);
+        let slot_id = slot.id();
+
+        let (mut encounter, creatures) = creatures_encounter(vec![boss, goblin, slot], InitiativeMode::Individual);
+        let view = EncounterView::new(encounter.clone(), creatures.clone());
+        assert!(view.state(slot_id).unwrap().hidden());
+        assert_eq!(view.state(slot_id).unwrap().name(), "Boss");
+
+        encounter.next_turn().unwrap();
+        encounter.next_turn().unwrap();
+        encounter.wait_active(WaitReason::Ready);
+        assert_eq!(encounter.waiting()[0].id(), slot_id);
+        assert_eq!(encounter.len(), 2);
+    }
+
+    #[test]
+    fn test_late_arrivals() {
+        let (mut encounter, _) = get_encounter(InitiativeMode::Popcorn);
+        let ids = encounter.order().to_vec();
+        encounter.pass_turn_to(ids[1]).unwrap();
+
+        // Arriving ahead of the active creature leaves them with the turn, and sits out the rest of the round.
+        let early = Uuid::new_v4();
+        encounter.insert(0, early);
+        assert_eq!(encounter.active_id(), Some(ids[1]));
+        assert!(encounter.has_acted(early));
+        assert!(encounter.pass_turn_to(early).is_err());
+
+        let late = Uuid::new_v4();
+        encounter.insert(3, late);
+        assert!(!encounter.has_acted(late));
+
+        encounter.pass_turn_to(late).unwrap();
+        encounter.next_turn().unwrap();
+        encounter.next_turn().unwrap();
+        assert_eq!(encounter.round(), 2);
+        assert!(!encounter.has_acted(early));
+    }
+
+    #[test]
+    fn test_popcorn_turns() {
+        let (mut encounter, creatures) = get_encounter(InitiativeMode::Popcorn);
+        let ids = encounter.order().to_vec();
+
+        encounter.pass_turn_to(ids[2]).unwrap();
+        assert_eq!(active_name(&encounter, &creatures), "C");
+        assert!(encounter.pass_turn_to(ids[0]).is_err());
+
+        encounter.next_turn().unwrap();
+        assert_eq!(active_name(&encounter, &creatures), "B");
+        encounter.previous_turn();
+        assert_eq!(active_name(&encounter, &creatures), "C");
+        encounter.next_turn().unwrap();
+
+        // Everyone has acted, so the last creature can pick anyone to start the next round.
+        encounter.pass_turn_to(ids[1]).unwrap();
+        assert_eq!((active_name(&encounter, &creatures).as_str(), encounter.round()), ("B", 2));
+    }
+
+    #[test]
+    fn test_deal_round() {
+        let (mut encounter, mut creatures) = get_encounter(InitiativeMode::Cards);
+        let mut deck = Deck::default();
+        encounter.next_turn().unwrap();
+        encounter.next_turn().unwrap();
+        encounter.next_turn().unwrap();
+        assert!(encounter.awaiting_initiative());
+
+        encounter.deal_round(&mut deck, &mut creatures);
+        assert!(!encounter.awaiting_initiative());
+        assert_eq!(deck.remaining(), 51);
+
+        let cards = encounter.order().iter().map(|&id| creatures.get(id).unwrap().card().unwrap()).collect::<Vec<_>>();
+        assert!(cards.windows(2).all(|pair| pair[0] > pair[1]));
+        assert!(creatures.iter().all(|c| Some(c.initiative()) == c.card().map(|card| card.initiative())));
+    }
+
+    #[test]
+    fn test_reroll_each_round() {
+        let (mut encounter, mut creatures) = get_encounter(InitiativeMode::RerollEachRound);
+        let ids = encounter.order().to_vec();
+        assert!(encounter.pass_turn_to(ids[1]).is_err());
+
+        encounter.next_turn().unwrap();
+        encounter.next_turn().unwrap();
+        assert!(!encounter.awaiting_initiative());
+        encounter.next_turn().unwrap();
+        assert!(encounter.awaiting_initiative());
+
+        encounter.set_round_initiatives(&[(ids[0], 3), (ids[1], 7), (ids[2], 5)], &mut creatures).unwrap();
+        assert_eq!(names(&encounter, &creatures), vec!["B", "C", "A"]);
+        assert_eq!((active_name(&encounter, &creatures).as_str(), encounter.round()), ("B", 2));
+        assert_eq!(creatures.get(ids[1]).unwrap().initiative(), 7);
+        assert!(!encounter.awaiting_initiative());
+    }
+}
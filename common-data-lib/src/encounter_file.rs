@@ -0,0 +1,158 @@
+use std::{fs, path::Path};
+
+use serde::Deserialize;
+
+use crate::{BackendError, ToBackendResult, creatures::{CreatureData, EncounterData}};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncounterFormat {
+    Json,
+    Toml
+}
+
+impl EncounterFormat {
+    /// Files ending in `.toml` are TOML; everything else, including the original `.enc` and `.encounter` files, is JSON.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("toml") => Self::Toml,
+            _ => Self::Json
+        }
+    }
+}
+
+/// Encounters saved before the TOML format was added are a bare list of creatures.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum JsonEncounter {
+    Encounter(EncounterData),
+    Legacy(Vec<CreatureData>)
+}
+
+impl From<JsonEncounter> for EncounterData {
+    fn from(value: JsonEncounter) -> Self {
+        match value {
+            JsonEncounter::Encounter(data) => data,
+            JsonEncounter::Legacy(creatures) => EncounterData::from(creatures)
+        }
+    }
+}
+
+pub fn parse_encounter(content: &str, format: EncounterFormat) -> Result<EncounterData, BackendError> {
+    let data: EncounterData = match format {
+        EncounterFormat::Json => serde_json::from_str::<JsonEncounter>(content).to_backend_result()?.into(),
+        EncounterFormat::Toml => toml::from_str(content).to_backend_result()?
+    };
+
+    Ok(data)
+}
+
+pub fn write_encounter(data: &EncounterData, format: EncounterFormat) -> Result<String, BackendError> {
+    match format {
+        EncounterFormat::Json => serde_json::to_string_pretty(data).to_backend_result(),
+        EncounterFormat::Toml => toml::to_string_pretty(data).to_backend_result()
+    }
+}
+
+pub fn load_encounter(path: &Path) -> Result<EncounterData, BackendError> {
+    let content = fs::read_to_string(path).to_backend_result()?;
+    parse_encounter(&content, EncounterFormat::from_path(path))
+}
+
+pub fn save_encounter(path: &Path, data: &EncounterData) -> Result<(), BackendError> {
+    let content = write_encounter(data, EncounterFormat::from_path(path))?;
+    fs::write(path, content).to_backend_result()
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::{cards::{Card, CardEdge, Suit}, creatures::{Creature, CreatureContainer, HitPoints, Side}, initiative::InitiativeMode, stat_block::StatBlock};
+
+    use super::*;
+
+    fn temp_path(extension: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("{}.{}", Uuid::new_v4(), extension))
+    }
+
+    /// An encounter that uses every optional field a creature can save.
+    fn encounter() -> EncounterData {
+        let mut boss = Creature::from("Boss");
+        boss.set_initiative(17);
+        boss.set_initiative_modifier(3);
+        let mut hit_points = HitPoints::new(60);
+        hit_points.damage(12);
+        boss.set_hit_points(Some(hit_points));
+        boss.set_hidden(true);
+        boss.set_side(Side::Players);
+        boss.deal(Card::Standard { rank: 12, suit: Suit::Hearts });
+        boss.set_card_edges(vec![CardEdge::LevelHeaded, CardEdge::Quick]);
+        boss.set_notes("## Tactics\nFlees below *half* HP.");
+        boss.set_stat_block(Some(StatBlock { armor_class: 17, hit_dice: String::from("8d10 + 16"), ..StatBlock::default() }));
+        let slot = boss.new_slot(7);
+
+        let mut joker = Creature::from("Goblin");
+        joker.deal(Card::Joker { red: true });
+
+        let creatures = CreatureContainer::from(vec![boss, slot, joker, Creature::from("Orc")]);
+        EncounterData::new(&creatures, InitiativeMode::Side { players: 14, monsters: 9 })
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(EncounterFormat::from_path(Path::new("ambush.toml")), EncounterFormat::Toml);
+        assert_eq!(EncounterFormat::from_path(Path::new("AMBUSH.TOML")), EncounterFormat::Toml);
+        assert_eq!(EncounterFormat::from_path(Path::new("ambush.enc")), EncounterFormat::Json);
+        assert_eq!(EncounterFormat::from_path(Path::new("ambush.json")), EncounterFormat::Json);
+        assert_eq!(EncounterFormat::from_path(Path::new("ambush")), EncounterFormat::Json);
+    }
+
+    #[test]
+    fn test_toml_round_trip() {
+        let path = temp_path("toml");
+        let data = encounter();
+
+        save_encounter(&path, &data).unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        let loaded = load_encounter(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert!(content.contains("players = 14"));
+        assert!(serde_json::from_str::<serde_json::Value>(&content).is_err());
+        assert_eq!(loaded, data);
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let path = temp_path("enc");
+        let data = encounter();
+
+        save_encounter(&path, &data).unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        let loaded = load_encounter(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert!(serde_json::from_str::<serde_json::Value>(&content).is_ok());
+        assert_eq!(loaded, data);
+    }
+
+    #[test]
+    fn test_legacy_json() {
+        let content = r#"[
+            { "id": "0b6c1b5e-2d1c-4f4e-9a59-6f3f8a1c2d3e", "name": "Goblin", "initiative": 12 },
+            { "id": "5f0a3b7c-8e2d-4c1a-b6f9-1d2e3f4a5b6c", "name": "Orc", "initiative": -1 }
+        ]"#;
+
+        let data = parse_encounter(content, EncounterFormat::Json).unwrap();
+        assert_eq!(data.initiative_mode(), InitiativeMode::Individual);
+
+        let creatures = CreatureContainer::from(data);
+        let goblin = creatures.iter().find(|c| c.name() == "Goblin").unwrap();
+        assert_eq!(goblin.initiative(), 12);
+        assert!(goblin.hit_points().is_none() && goblin.notes().is_empty() && !goblin.hidden());
+        assert_eq!(creatures.iter().find(|c| c.name() == "Orc").unwrap().initiative(), -1);
+
+        // A legacy list isn't TOML, so the extension decides how the same text is read.
+        assert!(parse_encounter(content, EncounterFormat::Toml).is_err());
+    }
+}
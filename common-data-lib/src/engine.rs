@@ -0,0 +1,687 @@
+use std::path::Path;
+
+use rand::Rng;
+use uuid::Uuid;
+
+use crate::{cards::{CardEdge, Deck}, damage::{Damage, DamageAdjustment}, creatures::{ConflictGroup, Creature, CreatureContainer, CreaturePatch, EncounterData, HitPoints, OrderMode, Side}, document::{DocumentState, EncounterTab}, encounter::{ActiveEncounter, EncounterView, ResumePosition, WaitReason}, encounter_file, initiative::InitiativeMode, stat_block::StatBlock, BackendError};
+
+/// Everything belonging to one open encounter, along with the changes that can be made to it. The app keeps one of
+/// these for each tab, with its commands doing little more than passing their arguments along.
+#[derive(Debug, Clone, Default)]
+pub struct EncounterEngine {
+    name: Option<String>,
+    document: DocumentState,
+    creatures: CreatureContainer,
+    conflicts: Option<Vec<ConflictGroup>>,
+    encounter: Option<ActiveEncounter>,
+    initiative_mode: InitiativeMode,
+    deck: Deck
+}
+
+/// Splits a line into a creature's name and the initiative after its last comma, if it has one.
+fn parse_arrival(line: &str) -> Option<(&str, Option<isize>)> {
+    let line = line.trim();
+    let (name, initiative) = match line.rsplit_once(',') {
+        Some((name, initiative)) => match initiative.trim().parse::<isize>() {
+            Ok(initiative) => (name.trim(), Some(initiative)),
+            Err(_) if initiative.trim().is_empty() => (name.trim(), None),
+            Err(_) => (line, None)
+        },
+        None => (line, None)
+    };
+
+    (!name.is_empty()).then_some((name, initiative))
+}
+
+fn no_creature(id: Uuid) -> BackendError {
+    BackendError::argument_error("id", format!("No creature with id '{}' exists", id))
+}
+
+fn active<'a>(encounter: &'a mut Option<ActiveEncounter>, action: &str) -> Result<&'a mut ActiveEncounter, BackendError> {
+    encounter.as_mut()
+        .ok_or_else(|| BackendError::logic_error(format!("Cannot {} because the initiative order has not been finalized", action)))
+}
+
+impl EncounterEngine {
+    pub fn document(&self) -> &DocumentState {
+        &self.document
+    }
+
+    pub fn creatures(&self) -> &CreatureContainer {
+        &self.creatures
+    }
+
+    pub fn initiative_mode(&self) -> InitiativeMode {
+        self.initiative_mode
+    }
+
+    pub fn deck(&self) -> &Deck {
+        &self.deck
+    }
+
+    /// The name shown on the encounter's tab, which is the file name unless it has been renamed.
+    pub fn name(&self) -> String {
+        self.name.clone().unwrap_or_else(|| self.document.name())
+    }
+
+    pub fn tab(&self, id: Uuid) -> EncounterTab {
+        EncounterTab::new(id, self.name(), self.document.dirty(), self.encounter.is_some())
+    }
+
+    /// Renames the encounter. A blank name goes back to using the file name.
+    pub fn rename(&mut self, name: &str) {
+        let trimmed = name.trim();
+        self.name = (!trimmed.is_empty()).then(|| trimmed.to_string());
+
+        log::info!("Renamed encounter to {}", trimmed);
+    }
+
+    /// A copy of the encounter that isn't tied to the original's file, so saving it asks where to put it.
+    pub fn duplicate(&self) -> EncounterEngine {
+        let mut document = DocumentState::default();
+        document.mark_dirty();
+
+        EncounterEngine {
+            name: Some(format!("{} (copy)", self.name())),
+            document,
+            ..self.clone()
+        }
+    }
+
+    /// Applies a change to a copy of the encounter, keeping it only if the whole change succeeds. Changes that fail
+    /// part way through leave the encounter as it was.
+    pub fn transaction<T>(&mut self, update: impl FnOnce(&mut EncounterEngine) -> Result<T, BackendError>) -> Result<T, BackendError> {
+        let mut working = self.clone();
+        let result = update(&mut working)?;
+        *self = working;
+
+        Ok(result)
+    }
+
+    fn mark_dirty(&mut self) {
+        self.document.mark_dirty();
+    }
+
+    pub fn discard_changes(&mut self) {
+        self.document.mark_clean();
+    }
+
+    pub fn reset(&mut self) {
+        *self = EncounterEngine::default();
+
+        log::info!("Beginning a new encounter");
+    }
+
+    pub fn load(&mut self, path: &Path) -> Result<(), BackendError> {
+        let data = encounter_file::load_encounter(path)?;
+        self.initiative_mode = data.initiative_mode();
+        self.creatures = data.into();
+        self.deck = Deck::without(self.creatures.iter().filter_map(|c| c.card()));
+        self.name = None;
+        self.document.set_path(path);
+
+        log::info!("Loaded encounter from: '{}'", path.to_string_lossy());
+
+        Ok(())
+    }
+
+    pub fn save(&mut self, path: &Path) -> Result<(), BackendError> {
+        encounter_file::save_encounter(path, &EncounterData::new(&self.creatures, self.initiative_mode))?;
+        self.document.set_path(path);
+
+        log::info!("Saved encounter to: '{}'", path.to_string_lossy());
+
+        Ok(())
+    }
+
+    pub fn set_order_mode(&mut self, mode: OrderMode) {
+        self.creatures.set_order_mode(mode);
+    }
+
+    pub fn add_creatures(&mut self, names: &str) {
+        for name in names.lines().filter(|l| !l.is_empty()) {
+            let creature = Creature::from(name);
+            log::info!("Adding new creature: {}", creature);
+            self.creatures.push_and_sort(creature);
+            self.creatures.sort();
+        }
+
+        self.mark_dirty();
+    }
+
+    pub fn import_creatures(&mut self, creatures: Vec<Creature>) {
+        for creature in creatures {
+            self.creatures.push(creature);
+        }
+
+        self.creatures.sort();
+        self.mark_dirty();
+    }
+
+    pub fn remove_creature(&mut self, id: Uuid) -> Result<Creature, BackendError> {
+        let slot_ids = self.creatures.slots_of(id).map(|c| c.id()).collect::<Vec<_>>();
+        let creature = self.creatures.remove_with_slots(id).ok_or_else(|| no_creature(id))?;
+
+        if let Some(encounter) = &mut self.encounter {
+            for id in slot_ids.into_iter().chain([id]) {
+                encounter.remove(id);
+            }
+        }
+
+        self.mark_dirty();
+
+        log::info!("Removed creature: {}", creature);
+
+        Ok(creature)
+    }
+
+    /// Adds another of a creature with its own state. Rerolling gives the copy a new initiative, or deals it a card
+    /// in card based initiative, and rolls its hit points from its stat block's hit dice when it has them. Copies of
+    /// creatures in the active encounter join it where their initiative puts them.
+    pub fn duplicate_creature(&mut self, id: Uuid, reroll: bool) -> Result<Creature, BackendError> {
+        let original = self.creatures.state(id).ok_or_else(|| no_creature(id))?;
+        let initiative = reroll.then(|| original.roll_initiative());
+        let copy = self.creatures.duplicate(id, initiative)?;
+        let ids = std::iter::once(copy.id())
+            .chain(self.creatures.slots_of(copy.id()).map(|c| c.id()))
+            .collect::<Vec<_>>();
+
+        if reroll && self.initiative_mode == InitiativeMode::Cards {
+            for &id in ids.iter() {
+                let card = self.deck.deal(copy.card_edges());
+                if let Some(creature) = self.creatures.get_mut(id) {
+                    creature.deal(card);
+                }
+            }
+        }
+
+        if let Some(maximum) = copy.stat_block().filter(|_| reroll).and_then(|block| block.roll_hit_points()) {
+            if let Some(creature) = self.creatures.get_mut(copy.id()) {
+                creature.set_hit_points(Some(HitPoints::new(maximum)));
+            }
+        }
+
+        let original_id = self.creatures.state(id).map(|c| c.id()).unwrap_or(id);
+        if self.encounter.as_ref().is_some_and(|encounter| encounter.contains(original_id)) {
+            for &id in ids.iter() {
+                self.join_active_encounter(id);
+            }
+        }
+
+        log::info!("Duplicated creature {} as {}", self.creatures.get(original_id).map(|c| c.name()).unwrap_or_default(), copy);
+
+        self.creatures.sort();
+        self.mark_dirty();
+
+        self.creatures.get(copy.id()).cloned().ok_or_else(|| no_creature(copy.id()))
+    }
+
+    pub fn set_creature_selected(&mut self, id: Uuid, selected: bool) -> Result<(), BackendError> {
+        let creature = self.creatures.get_mut(id).ok_or_else(|| no_creature(id))?;
+        creature.set_selected(selected);
+
+        log::info!("Set creature {} selected state to {}", creature, selected);
+
+        Ok(())
+    }
+
+    pub fn set_all_creatures_selected(&mut self, selected: bool) {
+        for creature in self.creatures.iter_mut() {
+            creature.set_selected(selected);
+        }
+
+        log::info!("Set all creatures selected state to {}", selected);
+    }
+
+    pub fn set_creature_initiative(&mut self, id: Uuid, initiative: isize) -> Result<(), BackendError> {
+        let creature = self.creatures.get_mut(id).ok_or_else(|| no_creature(id))?;
+        creature.set_initiative(initiative);
+
+        log::info!("Set creature {} initiative to {}", creature, initiative);
+
+        self.mark_dirty();
+
+        Ok(())
+    }
+
+    /// Applies the changes in a patch to a creature, returning it as it is afterwards. The name, initiative modifier,
+    /// side, hidden state and notes of a slot belong to the creature it was made for, so changing them through a slot
+    /// changes that creature.
+    pub fn update_creature(&mut self, id: Uuid, patch: CreaturePatch) -> Result<Creature, BackendError> {
+        if self.creatures.get(id).is_none() {
+            return Err(no_creature(id));
+        }
+
+        if let Some(name) = &patch.name {
+            self.creatures.rename(id, name)?;
+            self.creatures.sort();
+        }
+
+        let creature = self.creatures.get_mut(id).ok_or_else(|| no_creature(id))?;
+        if let Some(initiative) = patch.initiative {
+            creature.set_initiative(initiative);
+        }
+
+        if let Some(selected) = patch.selected {
+            creature.set_selected(selected);
+        }
+
+        let state = self.creatures.state_mut(id).ok_or_else(|| no_creature(id))?;
+        if let Some(modifier) = patch.initiative_modifier {
+            state.set_initiative_modifier(modifier);
+        }
+
+        if let Some(side) = patch.side {
+            state.set_side(side);
+        }
+
+        if let Some(notes) = &patch.notes {
+            state.set_notes(notes.as_str());
+        }
+
+        if patch.damage_vulnerabilities.is_some() || patch.damage_resistances.is_some() || patch.damage_immunities.is_some() {
+            let mut stat_block = state.stat_block().cloned().unwrap_or_default();
+            if let Some(types) = &patch.damage_vulnerabilities {
+                stat_block.damage_vulnerabilities = types.clone();
+            }
+
+            if let Some(types) = &patch.damage_resistances {
+                stat_block.damage_resistances = types.clone();
+            }
+
+            if let Some(types) = &patch.damage_immunities {
+                stat_block.damage_immunities = types.clone();
+            }
+
+            // A stat block started for damage types goes away again once they're all cleared.
+            state.set_stat_block(Some(stat_block).filter(|block| *block != StatBlock::default()));
+        }
+
+        log::info!("Updated creature {} with {:?}", state, patch);
+
+        if let Some(hidden) = patch.hidden {
+            self.set_creature_hidden(id, hidden, false)?;
+        }
+
+        self.mark_dirty();
+
+        self.creatures.get(id).cloned().ok_or_else(|| no_creature(id))
+    }
+
+    pub fn reset_all_initiatives(&mut self) {
+        for creature in self.creatures.iter_mut() {
+            creature.set_initiative(0);
+            creature.set_sub_order(0);
+        }
+
+        self.mark_dirty();
+
+        log::info!("Reset initiative order");
+    }
+
+    /// Gives a creature another turn each round at the given initiative. The slot joins the order when it is next finalized.
+    pub fn add_initiative_slot(&mut self, id: Uuid, initiative: isize) -> Result<Creature, BackendError> {
+        let creature = self.creatures.state(id).ok_or_else(|| no_creature(id))?;
+        let slot = creature.new_slot(initiative);
+
+        log::info!("Added an initiative slot at {} for creature {}", initiative, creature);
+
+        self.creatures.push_and_sort(slot.clone());
+        self.mark_dirty();
+
+        Ok(slot)
+    }
+
+    pub fn set_creature_side(&mut self, id: Uuid, side: Side) -> Result<(), BackendError> {
+        let creature = self.creatures.state_mut(id).ok_or_else(|| no_creature(id))?;
+        creature.set_side(side);
+
+        log::info!("Put creature {} on the side of the {}", creature, side.to_string().to_lowercase());
+
+        self.mark_dirty();
+
+        Ok(())
+    }
+
+    pub fn set_creature_card_edges(&mut self, id: Uuid, edges: Vec<CardEdge>) -> Result<(), BackendError> {
+        let creature = self.creatures.state_mut(id).ok_or_else(|| no_creature(id))?;
+        creature.set_card_edges(edges);
+
+        log::info!("Set creature {} edges to {:?}", creature, creature.card_edges());
+
+        self.mark_dirty();
+
+        Ok(())
+    }
+
+    pub fn set_creature_hidden(&mut self, id: Uuid, hidden: bool, move_to_initiative: bool) -> Result<(), BackendError> {
+        let creature = self.creatures.state_mut(id).ok_or_else(|| no_creature(id))?;
+        creature.set_hidden(hidden);
+
+        if hidden {
+            log::info!("Hid creature {}", creature);
+        } else {
+            log::info!("Revealed creature {}", creature);
+        }
+
+        if let Some(encounter) = self.encounter.as_mut().filter(|_| !hidden && move_to_initiative) {
+            if let Some(index) = encounter.move_to_initiative(id, &self.creatures) {
+                log::info!("Moved revealed creature to position {} in the initiative order", index + 1);
+            }
+        }
+
+        self.mark_dirty();
+
+        Ok(())
+    }
+
+    /// Sets how the initiative order is put together and how turns pass. Takes effect when the order is next finalized.
+    pub fn set_initiative_mode(&mut self, mode: InitiativeMode) {
+        if self.initiative_mode == mode {
+            return;
+        }
+
+        self.initiative_mode = mode;
+        log::info!("Changed the initiative mode to {}", mode.to_string().to_lowercase());
+
+        self.mark_dirty();
+    }
+
+    pub fn deal_initiative_cards(&mut self) {
+        self.deck = Deck::default();
+        self.creatures.deal_cards(&mut self.deck);
+
+        log::info!("Dealt initiative cards, leaving {} in the deck", self.deck.remaining());
+
+        self.mark_dirty();
+    }
+
+    pub fn initiative_conflicts(&mut self, regenerate: bool) -> Result<Vec<ConflictGroup>, BackendError> {
+        if regenerate {
+            self.conflicts = Some(self.creatures.get_conflicts());
+        }
+
+        self.conflicts.clone()
+            .ok_or_else(|| BackendError::logic_error("Could not get conflicts because they have not been generated"))
+    }
+
+    pub fn move_initiative_conflict(&mut self, group_index: usize, move_index: usize, target_index: usize) -> Result<(), BackendError> {
+        let conflicts = self.conflicts.as_mut()
+            .ok_or_else(|| BackendError::logic_error("Could not move conflict because they have not been generated"))?;
+
+        let group = conflicts.get_mut(group_index)
+            .ok_or_else(|| BackendError::argument_error("group_index", format!("Group index {} is out of bounds", group_index)))?;
+
+        let creatures = group.creatures_mut();
+        if move_index >= creatures.len() {
+            return Err(BackendError::argument_error("move_index", format!("Creature index {} is out of bounds", move_index)));
+        }
+
+        if target_index >= creatures.len() {
+            return Err(BackendError::argument_error("target_index", format!("Creature index {} is out of bounds", target_index)));
+        }
+
+        if move_index != target_index {
+            let move_creature = creatures.remove(move_index);
+            creatures.insert(target_index, move_creature);
+        }
+
+        Ok(())
+    }
+
+    pub fn finalize_initiative_order(&mut self) {
+        if let Some(conflicts) = &self.conflicts {
+            for group in conflicts {
+                group.finalize(&mut self.creatures);
+            }
+        }
+
+        let order = self.creatures.finalize(self.initiative_mode.rules().as_ref()).iter().map(|c| c.id()).collect();
+        self.encounter = Some(ActiveEncounter::new(order, self.initiative_mode));
+
+        log::info!("Finalized the initiative order using {} initiative", self.initiative_mode.to_string().to_lowercase());
+    }
+
+    pub fn active_encounter(&self) -> Result<&ActiveEncounter, BackendError> {
+        self.encounter.as_ref()
+            .ok_or_else(|| BackendError::logic_error("Cannot get encounter because the initiative order has not been finalized"))
+    }
+
+    pub fn encounter_view(&self) -> Result<EncounterView, BackendError> {
+        Ok(EncounterView::new(self.active_encounter()?.clone(), self.creatures.clone()))
+    }
+
+    pub fn active_encounter_creatures(&self) -> Result<Vec<Creature>, BackendError> {
+        Ok(self.encounter_view()?.creatures().cloned().collect())
+    }
+
+    pub fn next_turn(&mut self) -> Result<(), BackendError> {
+        let encounter = active(&mut self.encounter, "change turn")?;
+        encounter.next_turn()?;
+
+        log::info!("Advanced to turn {} of round {}", encounter.current_turn() + 1, encounter.round());
+
+        Ok(())
+    }
+
+    pub fn previous_turn(&mut self) -> Result<(), BackendError> {
+        let encounter = active(&mut self.encounter, "change turn")?;
+        encounter.previous_turn();
+
+        log::info!("Returned to turn {} of round {}", encounter.current_turn() + 1, encounter.round());
+
+        Ok(())
+    }
+
+    pub fn pass_turn_to(&mut self, id: Uuid) -> Result<(), BackendError> {
+        let encounter = active(&mut self.encounter, "change turn")?;
+        encounter.pass_turn_to(id)?;
+
+        if let Some(creature) = self.creatures.get(id) {
+            log::info!("Passed the turn to {} in round {}", creature, encounter.round());
+        }
+
+        Ok(())
+    }
+
+    pub fn set_round_initiatives(&mut self, initiatives: &[(Uuid, isize)]) -> Result<(), BackendError> {
+        let encounter = active(&mut self.encounter, "set initiatives")?;
+        encounter.set_round_initiatives(initiatives, &mut self.creatures)?;
+
+        log::info!("Set the initiative order for round {}", encounter.round());
+
+        self.mark_dirty();
+
+        Ok(())
+    }
+
+    pub fn deal_round_cards(&mut self) -> Result<(), BackendError> {
+        let encounter = active(&mut self.encounter, "deal cards")?;
+        encounter.deal_round(&mut self.deck, &mut self.creatures);
+
+        log::info!("Dealt cards for round {}, leaving {} in the deck", encounter.round(), self.deck.remaining());
+
+        self.mark_dirty();
+
+        Ok(())
+    }
+
+    pub fn wait_active_creature(&mut self, reason: WaitReason) -> Result<(), BackendError> {
+        let encounter = active(&mut self.encounter, "change turn")?;
+        let waiting = encounter.wait_active(reason)
+            .ok_or_else(|| BackendError::logic_error("There is no creature whose turn it is"))?;
+
+        if let Some(creature) = self.creatures.get(waiting.id()) {
+            log::info!("{} is {} in round {}", creature, waiting.reason().to_string().to_lowercase(), waiting.round());
+        }
+
+        Ok(())
+    }
+
+    pub fn resume_creature(&mut self, id: Uuid, position: ResumePosition) -> Result<(), BackendError> {
+        let encounter = active(&mut self.encounter, "change turn")?;
+        let initiative = self.creatures.get(id).map(|c| c.initiative());
+        encounter.resume(id, position, &mut self.creatures)
+            .ok_or_else(|| BackendError::argument_error("id", format!("No waiting creature with id '{}' exists", id)))?;
+
+        let creature = self.creatures.get(id).ok_or_else(|| no_creature(id))?;
+        log::info!("{} resumed with initiative {}", creature, creature.initiative());
+
+        // Delaying changes a creature's initiative for the rest of the encounter.
+        if initiative != Some(creature.initiative()) {
+            self.mark_dirty();
+        }
+
+        Ok(())
+    }
+
+    pub fn set_creature_hit_points(&mut self, id: Uuid, maximum: Option<usize>) -> Result<(), BackendError> {
+        self.update_hit_points(id, |hit_points| {
+            *hit_points = match (*hit_points, maximum) {
+                (Some(mut hit_points), Some(maximum)) => {
+                    hit_points.set_maximum(maximum);
+                    Some(hit_points)
+                },
+                (None, Some(maximum)) => Some(HitPoints::new(maximum)),
+                (_, None) => None
+            };
+        })
+    }
+
+    /// Applies each part of a hit after the creature's immunities, resistances and vulnerabilities to its type,
+    /// returning the total damage taken.
+    pub fn damage_creature(&mut self, id: Uuid, damage: &[Damage]) -> Result<usize, BackendError> {
+        let creature = self.creatures.state(id).ok_or_else(|| no_creature(id))?;
+        if creature.hit_points().is_none() {
+            return Err(BackendError::argument_error("id", format!("Creature {} has no hit points to damage", creature.name())));
+        }
+
+        let mut total = 0;
+        for part in damage {
+            let adjustment = creature.damage_adjustment(part.damage_type);
+            let taken = adjustment.apply(part.amount);
+            if adjustment != DamageAdjustment::Normal {
+                log::info!("Creature {} took {} of {} damage ({})", creature, taken, part, adjustment);
+            }
+
+            total += taken;
+        }
+
+        self.update_hit_points(id, |hit_points| {
+            if let Some(hit_points) = hit_points {
+                hit_points.damage(total);
+            }
+        })?;
+
+        Ok(total)
+    }
+
+    pub fn heal_creature(&mut self, id: Uuid, amount: usize) -> Result<(), BackendError> {
+        self.update_hit_points(id, |hit_points| {
+            if let Some(hit_points) = hit_points {
+                hit_points.heal(amount);
+            }
+        })
+    }
+
+    /// Applies a change to a creature's hit points. Changes made through an extra initiative slot apply to the
+    /// creature it belongs to.
+    fn update_hit_points(&mut self, id: Uuid, update: impl Fn(&mut Option<HitPoints>)) -> Result<(), BackendError> {
+        let creature = self.creatures.state_mut(id).ok_or_else(|| no_creature(id))?;
+        let mut hit_points = creature.hit_points();
+        update(&mut hit_points);
+        creature.set_hit_points(hit_points);
+
+        log::info!("Set creature {} hit points to {}", creature, hit_points.map(|hp| hp.to_string()).unwrap_or_else(|| "none".to_string()));
+
+        self.mark_dirty();
+
+        Ok(())
+    }
+
+    /// Adds a creature to the active encounter for each non-empty line, written as a name that can be followed by a
+    /// comma and the initiative it rolled. Creatures without one are rolled a d20, or dealt a card in card based
+    /// initiative. Each arrival joins the order where its initiative puts it, after any creatures it ties with.
+    pub fn add_creatures_to_active_encounter(&mut self, lines: &str) -> Result<(), BackendError> {
+        active(&mut self.encounter, "get encounter")?;
+
+        for (name, initiative) in lines.lines().filter_map(parse_arrival) {
+            let mut creature = Creature::from(name);
+            creature.set_selected(true);
+            match initiative {
+                Some(initiative) => creature.set_initiative(initiative),
+                None if self.initiative_mode == InitiativeMode::Cards => creature.deal(self.deck.deal(&[])),
+                None => creature.set_initiative(rand::thread_rng().gen_range(1..=20))
+            }
+
+            log::info!("Adding {} to the encounter with initiative {}", creature, creature.initiative());
+            let id = creature.id();
+            self.creatures.push(creature);
+            self.join_active_encounter(id);
+        }
+
+        self.creatures.sort();
+        self.mark_dirty();
+
+        Ok(())
+    }
+
+    /// Puts a creature that was just added to the creature list into the active encounter at its initiative, using
+    /// the initiative mode's rules. Creatures that were already in the order win ties with it.
+    fn join_active_encounter(&mut self, id: Uuid) {
+        let initiative = match self.creatures.get(id) {
+            Some(creature) => creature.initiative(),
+            None => return
+        };
+
+        let sub_order = self.creatures.iter()
+            .filter(|c| c.id() != id && c.initiative() == initiative)
+            .map(|c| c.sub_order() + 1)
+            .max()
+            .unwrap_or_default();
+
+        if let Some(creature) = self.creatures.get_mut(id) {
+            creature.set_sub_order(sub_order);
+        }
+
+        if let Some(encounter) = self.encounter.as_mut() {
+            let index = self.creatures.initiative_position(encounter.order(), id, self.initiative_mode.rules().as_ref());
+            encounter.insert(index, id);
+        }
+    }
+
+    pub fn change_active_encounter_order(&mut self, move_index: usize, target_index: usize) -> Result<(), BackendError> {
+        let encounter = active(&mut self.encounter, "get encounter")?;
+
+        if move_index >= encounter.len() {
+            return Err(BackendError::argument_error("move_index", format!("Creature index {} is out of bounds", move_index)));
+        }
+
+        if target_index >= encounter.len() {
+            return Err(BackendError::argument_error("target_index", format!("Creature index {} is out of bounds", target_index)));
+        }
+
+        encounter.move_creature(move_index, target_index);
+
+        Ok(())
+    }
+
+    /// Takes a creature and its extra initiative slots out of the encounter, leaving them in the creature list but
+    /// no longer selected.
+    pub fn remove_from_active_encounter(&mut self, id: Uuid) -> Result<(), BackendError> {
+        let encounter = active(&mut self.encounter, "get encounter")?;
+        if !encounter.contains(id) {
+            return Err(BackendError::argument_error("id", format!("No creature with id '{}' is in the encounter", id)));
+        }
+
+        let creature = self.creatures.get_mut(id).ok_or_else(|| no_creature(id))?;
+        creature.set_selected(false);
+
+        let slot_ids = self.creatures.slots_of(id).map(|c| c.id()).collect::<Vec<_>>();
+        for id in slot_ids.into_iter().chain([id]) {
+            encounter.remove(id);
+        }
+
+        Ok(())
+    }
+}
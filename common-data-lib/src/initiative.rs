@@ -0,0 +1,200 @@
+use std::cmp::Ordering;
+
+use serde::{Serialize, Deserialize};
+
+use crate::{creatures::{Creature, Side, initiative_order}, encounter::ActiveEncounter};
+
+/// The rules a game system uses to decide who acts when.
+pub trait InitiativeRules {
+    /// Sorts the creatures taking part into the order they act in.
+    fn order(&self, creatures: &mut [Creature]);
+
+    /// The index of the creature that acts after the active creature, or `None` if the round is over.
+    fn next_in_round(&self, encounter: &ActiveEncounter) -> Option<usize> {
+        let next = encounter.current_turn() + 1;
+        (next < encounter.len()).then_some(next)
+    }
+
+    /// Whether the creature whose turn it is picks who goes next.
+    fn active_creature_chooses_next(&self) -> bool {
+        false
+    }
+
+    /// Whether initiative is rolled again at the start of every round.
+    fn rerolls_each_round(&self) -> bool {
+        false
+    }
+}
+
+/// Each creature rolls its own initiative once, highest first.
+pub struct IndividualInitiative;
+
+impl InitiativeRules for IndividualInitiative {
+    fn order(&self, creatures: &mut [Creature]) {
+        creatures.sort_by(|a, b| initiative_order(b, a));
+    }
+}
+
+/// The players and the monsters each roll once, and the whole side acts together.
+pub struct SideInitiative {
+    pub players: isize,
+    pub monsters: isize
+}
+
+impl SideInitiative {
+    fn side_rank(&self, side: Side) -> isize {
+        match side {
+            Side::Players => self.players,
+            Side::Monsters => self.monsters
+        }
+    }
+}
+
+impl InitiativeRules for SideInitiative {
+    fn order(&self, creatures: &mut [Creature]) {
+        // The players win ties between the sides.
+        creatures.sort_by(|a, b| {
+            match self.side_rank(b.side()).cmp(&self.side_rank(a.side())) {
+                Ordering::Equal => {},
+                ord => return ord
+            };
+
+            match (a.side(), b.side()) {
+                (Side::Players, Side::Monsters) => Ordering::Less,
+                (Side::Monsters, Side::Players) => Ordering::Greater,
+                _ => initiative_order(b, a)
+            }
+        });
+    }
+}
+
+/// The first creature is picked by initiative, then each creature chooses who acts next from those yet to act.
+/// Once everyone has acted, the last creature picks anyone to start the next round.
+pub struct PopcornInitiative;
+
+impl InitiativeRules for PopcornInitiative {
+    fn order(&self, creatures: &mut [Creature]) {
+        IndividualInitiative.order(creatures);
+    }
+
+    fn next_in_round(&self, encounter: &ActiveEncounter) -> Option<usize> {
+        let active_id = encounter.active_id();
+        encounter.order().iter()
+            .position(|&id| Some(id) != active_id && !encounter.has_acted(id))
+    }
+
+    fn active_creature_chooses_next(&self) -> bool {
+        true
+    }
+}
+
+/// Individual initiative, but everyone rolls again at the start of each round.
+pub struct RerollEachRound;
+
+impl InitiativeRules for RerollEachRound {
+    fn order(&self, creatures: &mut [Creature]) {
+        IndividualInitiative.order(creatures);
+    }
+
+    fn rerolls_each_round(&self) -> bool {
+        true
+    }
+}
+
+/// Savage Worlds style initiative, where everyone is dealt a card from the action deck each round. Cards give
+/// creatures their initiative, so the order is the same as individual initiative.
+pub struct CardInitiative;
+
+impl InitiativeRules for CardInitiative {
+    fn order(&self, creatures: &mut [Creature]) {
+        IndividualInitiative.order(creatures);
+    }
+
+    fn rerolls_each_round(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum InitiativeMode {
+    #[default]
+    Individual,
+    Side { players: isize, monsters: isize },
+    Popcorn,
+    RerollEachRound,
+    Cards
+}
+
+impl InitiativeMode {
+    /// Every mode, using zero for the side rolls.
+    pub fn all() -> &'static [InitiativeMode] {
+        &[
+            InitiativeMode::Individual,
+            InitiativeMode::Side { players: 0, monsters: 0 },
+            InitiativeMode::Popcorn,
+            InitiativeMode::RerollEachRound,
+            InitiativeMode::Cards,
+        ]
+    }
+
+    pub fn rules(&self) -> Box<dyn InitiativeRules> {
+        match *self {
+            Self::Individual => Box::new(IndividualInitiative),
+            Self::Side { players, monsters } => Box::new(SideInitiative { players, monsters }),
+            Self::Popcorn => Box::new(PopcornInitiative),
+            Self::RerollEachRound => Box::new(RerollEachRound),
+            Self::Cards => Box::new(CardInitiative),
+        }
+    }
+
+    /// Whether two modes use the same rules, ignoring any rolls they hold.
+    pub fn same_kind(&self, other: &InitiativeMode) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
+impl std::fmt::Display for InitiativeMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Individual => write!(f, "Individual"),
+            Self::Side { .. } => write!(f, "Side"),
+            Self::Popcorn => write!(f, "Popcorn"),
+            Self::RerollEachRound => write!(f, "Reroll each round"),
+            Self::Cards => write!(f, "Cards"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn creature(name: &str, initiative: isize, side: Side) -> Creature {
+        let mut creature = Creature::from(name);
+        creature.set_initiative(initiative);
+        creature.set_side(side);
+        creature
+    }
+
+    fn names(creatures: &[Creature]) -> Vec<&str> {
+        creatures.iter().map(|c| c.name()).collect()
+    }
+
+    #[test]
+    fn test_side_order() {
+        let mut creatures = vec![
+            creature("Goblin", 20, Side::Monsters),
+            creature("Fighter", 5, Side::Players),
+            creature("Wizard", 12, Side::Players),
+        ];
+
+        InitiativeMode::Side { players: 10, monsters: 8 }.rules().order(&mut creatures);
+        assert_eq!(names(&creatures), vec!["Wizard", "Fighter", "Goblin"]);
+
+        InitiativeMode::Side { players: 8, monsters: 8 }.rules().order(&mut creatures);
+        assert_eq!(names(&creatures), vec!["Wizard", "Fighter", "Goblin"]);
+
+        InitiativeMode::Side { players: 3, monsters: 8 }.rules().order(&mut creatures);
+        assert_eq!(names(&creatures), vec!["Goblin", "Wizard", "Fighter"]);
+    }
+}
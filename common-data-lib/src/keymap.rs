@@ -0,0 +1,214 @@
+use serde::{Serialize, Deserialize};
+
+use crate::BackendError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum KeyAction {
+    NextTurn,
+    PreviousTurn,
+    Damage,
+    Heal,
+    SelectPrevious,
+    SelectNext,
+    MoveUp,
+    MoveDown,
+    CheatSheet
+}
+
+impl KeyAction {
+    pub fn all() -> &'static [KeyAction] {
+        &[
+            KeyAction::NextTurn,
+            KeyAction::PreviousTurn,
+            KeyAction::Damage,
+            KeyAction::Heal,
+            KeyAction::SelectPrevious,
+            KeyAction::SelectNext,
+            KeyAction::MoveUp,
+            KeyAction::MoveDown,
+            KeyAction::CheatSheet,
+        ]
+    }
+}
+
+impl std::fmt::Display for KeyAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NextTurn => write!(f, "Next turn"),
+            Self::PreviousTurn => write!(f, "Previous turn"),
+            Self::Damage => write!(f, "Damage selected creature"),
+            Self::Heal => write!(f, "Heal selected creature"),
+            Self::SelectPrevious => write!(f, "Select previous creature"),
+            Self::SelectNext => write!(f, "Select next creature"),
+            Self::MoveUp => write!(f, "Move selected creature up"),
+            Self::MoveDown => write!(f, "Move selected creature down"),
+            Self::CheatSheet => write!(f, "Show keyboard shortcuts"),
+        }
+    }
+}
+
+/// A key press bound to an action. Keys use the names from `KeyboardEvent.key` and are matched ignoring case,
+/// so shift is not treated as a modifier.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyBinding {
+    action: KeyAction,
+    key: String,
+    #[serde(default)]
+    alt: bool,
+    #[serde(default)]
+    ctrl: bool
+}
+
+impl KeyBinding {
+    pub fn new(action: KeyAction, key: impl Into<String>) -> Self {
+        Self {
+            action,
+            key: key.into(),
+            alt: false,
+            ctrl: false
+        }
+    }
+
+    pub fn with_alt(mut self) -> Self {
+        self.alt = true;
+        self
+    }
+
+    pub fn with_ctrl(mut self) -> Self {
+        self.ctrl = true;
+        self
+    }
+
+    pub fn action(&self) -> KeyAction {
+        self.action
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn alt(&self) -> bool {
+        self.alt
+    }
+
+    pub fn ctrl(&self) -> bool {
+        self.ctrl
+    }
+
+    pub fn matches(&self, key: &str, alt: bool, ctrl: bool) -> bool {
+        self.alt == alt && self.ctrl == ctrl && self.key.eq_ignore_ascii_case(key)
+    }
+
+    fn same_keys(&self, other: &KeyBinding) -> bool {
+        other.matches(&self.key, self.alt, self.ctrl)
+    }
+}
+
+impl std::fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+
+        if self.alt {
+            write!(f, "Alt+")?;
+        }
+
+        match self.key.as_str() {
+            " " => write!(f, "Space"),
+            key if key.chars().count() == 1 => write!(f, "{}", key.to_uppercase()),
+            key => write!(f, "{}", key)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Keymap {
+    bindings: Vec<KeyBinding>
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self {
+            bindings: vec![
+                KeyBinding::new(KeyAction::NextTurn, " "),
+                KeyBinding::new(KeyAction::NextTurn, "n"),
+                KeyBinding::new(KeyAction::PreviousTurn, "p"),
+                KeyBinding::new(KeyAction::Damage, "d"),
+                KeyBinding::new(KeyAction::Heal, "h"),
+                KeyBinding::new(KeyAction::SelectPrevious, "ArrowUp"),
+                KeyBinding::new(KeyAction::SelectNext, "ArrowDown"),
+                KeyBinding::new(KeyAction::MoveUp, "ArrowUp").with_alt(),
+                KeyBinding::new(KeyAction::MoveDown, "ArrowDown").with_alt(),
+                KeyBinding::new(KeyAction::CheatSheet, "?"),
+            ]
+        }
+    }
+}
+
+impl Keymap {
+    pub fn bindings(&self) -> &[KeyBinding] {
+        &self.bindings
+    }
+
+    pub fn bindings_for(&self, action: KeyAction) -> impl Iterator<Item = &KeyBinding> {
+        self.bindings.iter().filter(move |b| b.action == action)
+    }
+
+    pub fn action_for(&self, key: &str, alt: bool, ctrl: bool) -> Option<KeyAction> {
+        self.bindings.iter()
+            .find(|b| b.matches(key, alt, ctrl))
+            .map(|b| b.action)
+    }
+
+    /// Adds a binding, replacing any existing binding for the same keys.
+    pub fn bind(&mut self, binding: KeyBinding) {
+        self.bindings.retain(|b| !b.same_keys(&binding));
+        self.bindings.push(binding);
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        if index < self.bindings.len() {
+            self.bindings.remove(index);
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), BackendError> {
+        for (idx, binding) in self.bindings.iter().enumerate() {
+            if binding.key.is_empty() {
+                return Err(BackendError::argument_error("keymap", format!("No key is set for '{}'", binding.action)));
+            }
+
+            if let Some(other) = self.bindings[..idx].iter().find(|b| b.same_keys(binding)) {
+                return Err(BackendError::argument_error("keymap", format!("{} is bound to both '{}' and '{}'", binding, other.action, binding.action)));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_actions() {
+        let keymap = Keymap::default();
+        assert!(keymap.validate().is_ok());
+        assert_eq!(keymap.action_for("N", false, false), Some(KeyAction::NextTurn));
+        assert_eq!(keymap.action_for("ArrowUp", false, false), Some(KeyAction::SelectPrevious));
+        assert_eq!(keymap.action_for("ArrowUp", true, false), Some(KeyAction::MoveUp));
+        assert_eq!(keymap.action_for("n", false, true), None);
+    }
+
+    #[test]
+    fn test_bind_replaces_duplicates() {
+        let mut keymap = Keymap::default();
+        keymap.bind(KeyBinding::new(KeyAction::Heal, "D"));
+
+        assert_eq!(keymap.action_for("d", false, false), Some(KeyAction::Heal));
+        assert_eq!(keymap.bindings_for(KeyAction::Damage).count(), 0);
+        assert!(keymap.validate().is_ok());
+    }
+}
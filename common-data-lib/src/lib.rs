@@ -1,6 +1,19 @@
 use serde::{Deserialize, Serialize};
 
+pub mod campaign;
+pub mod cards;
 pub mod creatures;
+pub mod csv_files;
+pub mod damage;
+pub mod document;
+pub mod encounter;
+pub mod engine;
+pub mod encounter_file;
+pub mod initiative;
+pub mod keymap;
+pub mod recent_files;
+pub mod settings;
+pub mod stat_block;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum BackendError {
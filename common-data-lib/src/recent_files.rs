@@ -0,0 +1,120 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Serialize, Deserialize};
+
+pub const MAX_RECENT_FILES: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecentFile {
+    path: PathBuf,
+    name: String,
+    /// Seconds since the unix epoch that the file was last modified.
+    last_modified: Option<u64>
+}
+
+impl RecentFile {
+    pub fn new(path: impl Into<PathBuf>, last_modified: Option<u64>) -> Self {
+        let path = path.into();
+        let name = path.file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string_lossy().to_string());
+
+        Self { path, name, last_modified }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn last_modified(&self) -> Option<u64> {
+        self.last_modified
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct RecentFiles {
+    files: Vec<RecentFile>,
+    last_directory: Option<PathBuf>
+}
+
+impl RecentFiles {
+    pub fn files(&self) -> &[RecentFile] {
+        &self.files
+    }
+
+    pub fn last_directory(&self) -> Option<&Path> {
+        self.last_directory.as_deref()
+    }
+
+    /// Moves the file to the top of the list, adding it if it is not already there.
+    pub fn add(&mut self, file: RecentFile) {
+        self.files.retain(|f| f.path != file.path);
+        self.last_directory = file.path.parent().map(|p| p.to_path_buf());
+        self.files.insert(0, file);
+        self.files.truncate(MAX_RECENT_FILES);
+    }
+
+    /// Removes the files that no longer exist, returning the removed files.
+    pub fn remove_missing(&mut self, exists: impl Fn(&Path) -> bool) -> Vec<RecentFile> {
+        let (files, missing) = self.files.drain(..).partition(|f| exists(&f.path));
+        self.files = files;
+
+        missing
+    }
+
+    pub fn update_last_modified(&mut self, last_modified: impl Fn(&Path) -> Option<u64>) {
+        for file in self.files.iter_mut() {
+            file.last_modified = last_modified(&file.path);
+        }
+    }
+}
+
+/// The recent files list along with any files that were removed because they could not be found.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct RecentFilesList {
+    pub files: Vec<RecentFile>,
+    pub missing: Vec<RecentFile>
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_moves_to_front() {
+        let mut recent = RecentFiles::default();
+        recent.add(RecentFile::new("/a/one.enc", None));
+        recent.add(RecentFile::new("/b/two.toml", None));
+        recent.add(RecentFile::new("/a/one.enc", Some(5)));
+
+        let names = recent.files().iter().map(|f| f.name()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["one", "two"]);
+        assert_eq!(recent.files()[0].last_modified(), Some(5));
+        assert_eq!(recent.last_directory(), Some(Path::new("/a")));
+
+        for idx in 0..(MAX_RECENT_FILES * 2) {
+            recent.add(RecentFile::new(format!("/c/{}.enc", idx), None));
+        }
+
+        assert_eq!(recent.files().len(), MAX_RECENT_FILES);
+    }
+
+    #[test]
+    fn test_remove_missing() {
+        let mut recent = RecentFiles::default();
+        recent.add(RecentFile::new("/a/one.enc", None));
+        recent.add(RecentFile::new("/a/two.enc", None));
+
+        let missing = recent.remove_missing(|path| path.ends_with("two.enc"));
+
+        assert_eq!(missing.len(), 1);
+        assert_eq!(missing[0].name(), "one");
+        assert_eq!(recent.files().len(), 1);
+        assert_eq!(recent.files()[0].name(), "two");
+    }
+}
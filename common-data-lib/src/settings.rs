@@ -0,0 +1,130 @@
+use serde::{Serialize, Deserialize};
+
+use crate::{BackendError, creatures::OrderMode, keymap::Keymap};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum LogLevel {
+    Off,
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace
+}
+
+impl LogLevel {
+    pub fn all() -> &'static [LogLevel] {
+        &[LogLevel::Off, LogLevel::Error, LogLevel::Warn, LogLevel::Info, LogLevel::Debug, LogLevel::Trace]
+    }
+}
+
+impl std::fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Off => write!(f, "Off"),
+            Self::Error => write!(f, "Error"),
+            Self::Warn => write!(f, "Warn"),
+            Self::Info => write!(f, "Info"),
+            Self::Debug => write!(f, "Debug"),
+            Self::Trace => write!(f, "Trace"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    max_log_count: usize,
+    console_log_level: LogLevel,
+    default_order_mode: OrderMode,
+    window_width: u32,
+    window_height: u32,
+    keymap: Keymap
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            max_log_count: 10,
+            console_log_level: LogLevel::Info,
+            default_order_mode: OrderMode::Alphabetical,
+            window_width: 800,
+            window_height: 600,
+            keymap: Keymap::default()
+        }
+    }
+}
+
+impl Settings {
+    pub const MIN_WINDOW_WIDTH: u32 = 400;
+    pub const MIN_WINDOW_HEIGHT: u32 = 300;
+
+    pub fn max_log_count(&self) -> usize {
+        self.max_log_count
+    }
+
+    pub fn set_max_log_count(&mut self, value: usize) {
+        self.max_log_count = value;
+    }
+
+    pub fn console_log_level(&self) -> LogLevel {
+        self.console_log_level
+    }
+
+    pub fn set_console_log_level(&mut self, value: LogLevel) {
+        self.console_log_level = value;
+    }
+
+    pub fn default_order_mode(&self) -> OrderMode {
+        self.default_order_mode
+    }
+
+    pub fn set_default_order_mode(&mut self, value: OrderMode) {
+        self.default_order_mode = value;
+    }
+
+    pub fn window_width(&self) -> u32 {
+        self.window_width
+    }
+
+    pub fn set_window_width(&mut self, value: u32) {
+        self.window_width = value;
+    }
+
+    pub fn window_height(&self) -> u32 {
+        self.window_height
+    }
+
+    pub fn set_window_height(&mut self, value: u32) {
+        self.window_height = value;
+    }
+
+    pub fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    pub fn keymap_mut(&mut self) -> &mut Keymap {
+        &mut self.keymap
+    }
+
+    pub fn set_keymap(&mut self, value: Keymap) {
+        self.keymap = value;
+    }
+
+    pub fn validate(&self) -> Result<(), BackendError> {
+        if self.max_log_count == 0 {
+            return Err(BackendError::argument_error("max_log_count", "At least one log file must be kept"));
+        }
+
+        if self.window_width < Self::MIN_WINDOW_WIDTH {
+            return Err(BackendError::argument_error("window_width", format!("The window must be at least {} pixels wide", Self::MIN_WINDOW_WIDTH)));
+        }
+
+        if self.window_height < Self::MIN_WINDOW_HEIGHT {
+            return Err(BackendError::argument_error("window_height", format!("The window must be at least {} pixels high", Self::MIN_WINDOW_HEIGHT)));
+        }
+
+        self.keymap.validate()
+    }
+}
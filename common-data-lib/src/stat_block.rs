@@ -0,0 +1,302 @@
+use std::collections::BTreeMap;
+
+use rand::Rng;
+use serde::{Serialize, Deserialize};
+
+use crate::damage::{DamageAdjustment, DamageType};
+
+/// The six ability scores, in the order stat blocks list them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Ability {
+    Strength,
+    Dexterity,
+    Constitution,
+    Intelligence,
+    Wisdom,
+    Charisma
+}
+
+impl Ability {
+    pub fn all() -> &'static [Ability] {
+        &[Ability::Strength, Ability::Dexterity, Ability::Constitution, Ability::Intelligence, Ability::Wisdom, Ability::Charisma]
+    }
+
+    pub fn abbreviation(&self) -> &'static str {
+        match self {
+            Self::Strength => "STR",
+            Self::Dexterity => "DEX",
+            Self::Constitution => "CON",
+            Self::Intelligence => "INT",
+            Self::Wisdom => "WIS",
+            Self::Charisma => "CHA",
+        }
+    }
+}
+
+impl std::fmt::Display for Ability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Strength => write!(f, "Strength"),
+            Self::Dexterity => write!(f, "Dexterity"),
+            Self::Constitution => write!(f, "Constitution"),
+            Self::Intelligence => write!(f, "Intelligence"),
+            Self::Wisdom => write!(f, "Wisdom"),
+            Self::Charisma => write!(f, "Charisma"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AbilityScores {
+    pub strength: u8,
+    pub dexterity: u8,
+    pub constitution: u8,
+    pub intelligence: u8,
+    pub wisdom: u8,
+    pub charisma: u8
+}
+
+impl Default for AbilityScores {
+    fn default() -> Self {
+        Self {
+            strength: 10,
+            dexterity: 10,
+            constitution: 10,
+            intelligence: 10,
+            wisdom: 10,
+            charisma: 10
+        }
+    }
+}
+
+impl AbilityScores {
+    pub fn score(&self, ability: Ability) -> u8 {
+        match ability {
+            Ability::Strength => self.strength,
+            Ability::Dexterity => self.dexterity,
+            Ability::Constitution => self.constitution,
+            Ability::Intelligence => self.intelligence,
+            Ability::Wisdom => self.wisdom,
+            Ability::Charisma => self.charisma,
+        }
+    }
+
+    /// The modifier for an ability, rounding down so 9 gives -1 and 11 gives +0.
+    pub fn modifier(&self, ability: Ability) -> isize {
+        (self.score(ability) as isize - 10).div_euclid(2)
+    }
+}
+
+/// A named entry in one of a stat block's lists, such as a trait, an action or a legendary action.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct StatBlockEntry {
+    pub name: String,
+    /// Markdown, like creature notes.
+    #[serde(default)]
+    pub description: String
+}
+
+/// Everything a GM needs to run a creature's turn. Text fields are kept as they're written in the source books, so
+/// "30 ft., fly 60 ft." is a speed and "darkvision 60 ft." a sense.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct StatBlock {
+    /// Size, type and alignment, like "Small humanoid (goblinoid), neutral evil".
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    pub armor_class: usize,
+    /// Where the armor class comes from, like "leather armor, shield".
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub armor_source: String,
+    /// The dice hit points are rolled with, like "2d6".
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub hit_dice: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub speed: String,
+    pub abilities: AbilityScores,
+    /// Only the saving throws the creature is proficient in; the rest use the ability modifier.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub saving_throws: BTreeMap<Ability, isize>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub skills: BTreeMap<String, isize>,
+    /// The damage types the backend adjusts damage for when the creature is hit.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub damage_vulnerabilities: Vec<DamageType>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub damage_resistances: Vec<DamageType>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub damage_immunities: Vec<DamageType>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub condition_immunities: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub senses: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub languages: Vec<String>,
+    /// Challenge rating, kept as text so fractions like "1/4" read naturally.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub challenge: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub traits: Vec<StatBlockEntry>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub actions: Vec<StatBlockEntry>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub reactions: Vec<StatBlockEntry>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub legendary_actions: Vec<StatBlockEntry>
+}
+
+impl StatBlock {
+    /// The bonus to a saving throw, which is the ability modifier unless the creature is proficient in it.
+    pub fn saving_throw(&self, ability: Ability) -> isize {
+        self.saving_throws.get(&ability).copied().unwrap_or_else(|| self.abilities.modifier(ability))
+    }
+
+    /// Whether there's more to the stat block than damage types, which can be given to creatures that have no stat
+    /// block of their own.
+    pub fn has_details(&self) -> bool {
+        let damage_types = StatBlock {
+            damage_vulnerabilities: self.damage_vulnerabilities.clone(),
+            damage_resistances: self.damage_resistances.clone(),
+            damage_immunities: self.damage_immunities.clone(),
+            ..StatBlock::default()
+        };
+
+        *self != damage_types
+    }
+
+    /// How damage of the given type is changed by the creature's immunities, resistances and vulnerabilities.
+    pub fn damage_adjustment(&self, damage_type: DamageType) -> DamageAdjustment {
+        if self.damage_immunities.contains(&damage_type) {
+            return DamageAdjustment::Immune;
+        }
+
+        match (self.damage_resistances.contains(&damage_type), self.damage_vulnerabilities.contains(&damage_type)) {
+            (true, true) => DamageAdjustment::ResistedAndVulnerable,
+            (true, false) => DamageAdjustment::Resisted,
+            (false, true) => DamageAdjustment::Vulnerable,
+            (false, false) => DamageAdjustment::Normal,
+        }
+    }
+
+    /// Rolls new hit points from the hit dice, never giving less than 1. Returns `None` when the hit dice aren't
+    /// written as dice with an optional bonus, like "2d6" or "9d10 + 18".
+    pub fn roll_hit_points(&self) -> Option<usize> {
+        let (count, sides, bonus) = parse_dice(&self.hit_dice)?;
+        let mut rng = rand::thread_rng();
+        let total = (0..count).map(|_| rng.gen_range(1..=sides) as isize).sum::<isize>() + bonus;
+
+        Some(total.max(1) as usize)
+    }
+}
+
+/// Splits dice like "9d10 + 18" into the number of dice, their sides and the bonus.
+fn parse_dice(text: &str) -> Option<(usize, usize, isize)> {
+    let text = text.replace(' ', "").to_lowercase();
+    let (dice, bonus) = match text.find(['+', '-']) {
+        Some(index) => (&text[..index], text[index..].trim_start_matches('+').parse().ok()?),
+        None => (text.as_str(), 0)
+    };
+
+    let (count, sides) = dice.split_once('d')?;
+    let (count, sides) = (count.parse().ok()?, sides.parse().ok()?);
+
+    (count > 0 && sides > 0).then_some((count, sides, bonus))
+}
+
+/// Formats a bonus the way stat blocks do, always with a sign.
+pub fn format_bonus(value: isize) -> String {
+    format!("{:+}", value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn goblin() -> StatBlock {
+        StatBlock {
+            description: String::from("Small humanoid (goblinoid), neutral evil"),
+            armor_class: 15,
+            armor_source: String::from("leather armor, shield"),
+            hit_dice: String::from("2d6"),
+            speed: String::from("30 ft."),
+            abilities: AbilityScores { strength: 8, dexterity: 14, constitution: 10, intelligence: 10, wisdom: 8, charisma: 8 },
+            saving_throws: BTreeMap::from([(Ability::Dexterity, 4)]),
+            skills: BTreeMap::from([(String::from("Stealth"), 6)]),
+            senses: vec![String::from("darkvision 60 ft."), String::from("passive Perception 9")],
+            languages: vec![String::from("Common"), String::from("Goblin")],
+            damage_resistances: vec![DamageType::Fire, DamageType::Cold],
+            damage_vulnerabilities: vec![DamageType::Cold, DamageType::Radiant],
+            damage_immunities: vec![DamageType::Poison],
+            challenge: String::from("1/4"),
+            traits: vec![StatBlockEntry { name: String::from("Nimble Escape"), description: String::from("Disengage or Hide as a bonus action.") }],
+            actions: vec![StatBlockEntry { name: String::from("Scimitar"), description: String::from("*Melee Weapon Attack:* +4 to hit.") }],
+            ..StatBlock::default()
+        }
+    }
+
+    #[test]
+    fn test_modifiers() {
+        let block = goblin();
+        assert_eq!(block.abilities.modifier(Ability::Strength), -1);
+        assert_eq!(block.abilities.modifier(Ability::Dexterity), 2);
+        assert_eq!(block.abilities.modifier(Ability::Constitution), 0);
+        assert_eq!(AbilityScores { charisma: 1, ..AbilityScores::default() }.modifier(Ability::Charisma), -5);
+
+        assert_eq!(block.saving_throw(Ability::Dexterity), 4);
+        assert_eq!(block.saving_throw(Ability::Wisdom), -1);
+        assert_eq!(format_bonus(0), "+0");
+        assert_eq!(format_bonus(-1), "-1");
+    }
+
+    #[test]
+    fn test_damage_adjustment() {
+        let block = goblin();
+        assert_eq!(block.damage_adjustment(DamageType::Fire), DamageAdjustment::Resisted);
+        assert_eq!(block.damage_adjustment(DamageType::Radiant), DamageAdjustment::Vulnerable);
+        assert_eq!(block.damage_adjustment(DamageType::Cold), DamageAdjustment::ResistedAndVulnerable);
+        assert_eq!(block.damage_adjustment(DamageType::Poison), DamageAdjustment::Immune);
+        assert_eq!(block.damage_adjustment(DamageType::Slashing), DamageAdjustment::Normal);
+
+        let fire_only = StatBlock { damage_resistances: vec![DamageType::Fire], ..StatBlock::default() };
+        assert!(block.has_details());
+        assert!(!fire_only.has_details());
+    }
+
+    #[test]
+    fn test_roll_hit_points() {
+        assert_eq!(parse_dice("2d6"), Some((2, 6, 0)));
+        assert_eq!(parse_dice("9D10 + 18"), Some((9, 10, 18)));
+        assert_eq!(parse_dice("1d4-3"), Some((1, 4, -3)));
+        assert_eq!(parse_dice("15"), None);
+        assert_eq!(parse_dice("0d6"), None);
+
+        let mut block = goblin();
+        for _ in 0..20 {
+            assert!((2..=12).contains(&block.roll_hit_points().unwrap()));
+        }
+
+        block.hit_dice = String::from("1d4 - 3");
+        assert_eq!(block.roll_hit_points(), Some(1));
+        block.hit_dice = String::new();
+        assert_eq!(block.roll_hit_points(), None);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let block = goblin();
+
+        let json = serde_json::to_string(&block).unwrap();
+        assert_eq!(serde_json::from_str::<StatBlock>(&json).unwrap(), block);
+
+        let toml = toml::to_string_pretty(&block).unwrap();
+        assert_eq!(toml::from_str::<StatBlock>(&toml).unwrap(), block);
+
+        // Anything left out of a hand-written stat block gets its usual value.
+        let partial: StatBlock = serde_json::from_str(r#"{ "armor_class": 12, "abilities": { "dexterity": 15 }, "damage_resistances": ["fire"] }"#).unwrap();
+        assert_eq!(partial.armor_class, 12);
+        assert_eq!(partial.damage_resistances, vec![DamageType::Fire]);
+        assert_eq!(partial.abilities.modifier(Ability::Strength), 0);
+        assert_eq!(partial.abilities.modifier(Ability::Dexterity), 2);
+    }
+}
@@ -0,0 +1,596 @@
+//! Whole encounters played through the engine, the same way the app's commands drive it.
+
+use common_data_lib::{creatures::{Creature, CreatureContainer, CreaturePatch, EncounterData, HitPoints, Side}, damage::{parse_damage, Damage, DamageType}, encounter::{ResumePosition, WaitReason}, encounter_file, engine::EncounterEngine, initiative::InitiativeMode, stat_block::StatBlock, BackendError};
+use uuid::Uuid;
+
+fn id_of(engine: &EncounterEngine, name: &str) -> Uuid {
+    engine.creatures().iter()
+        .find(|c| c.name() == name)
+        .unwrap_or_else(|| panic!("No creature named {}", name))
+        .id()
+}
+
+fn order(engine: &EncounterEngine) -> Vec<String> {
+    engine.encounter_view().unwrap().creatures()
+        .map(|c| c.name().to_string())
+        .collect()
+}
+
+fn active_name(engine: &EncounterEngine) -> String {
+    engine.encounter_view().unwrap().active_creature().unwrap().name().to_string()
+}
+
+/// An encounter with the given creatures and initiatives, all selected to take part.
+fn prepared(creatures: &[(&str, isize)]) -> EncounterEngine {
+    let mut engine = EncounterEngine::default();
+    let names = creatures.iter().map(|(name, _)| *name).collect::<Vec<_>>().join("\n");
+    engine.add_creatures(&names);
+
+    for (name, initiative) in creatures {
+        let id = id_of(&engine, name);
+        engine.set_creature_initiative(id, *initiative).unwrap();
+    }
+
+    engine.set_all_creatures_selected(true);
+    engine
+}
+
+#[test]
+fn test_roll_resolve_and_finalize() {
+    let mut engine = prepared(&[("Goblin", 12), ("Fighter", 18), ("Wizard", 12), ("Orc", 5)]);
+    assert!(engine.document().dirty());
+
+    let conflicts = engine.initiative_conflicts(true).unwrap();
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].initiative(), 12);
+    let names = conflicts[0].creatures().iter().map(|c| c.name()).collect::<Vec<_>>();
+    assert_eq!(names, vec!["Goblin", "Wizard"]);
+
+    // The wizard wins the tie.
+    engine.move_initiative_conflict(0, 1, 0).unwrap();
+    engine.finalize_initiative_order();
+
+    assert_eq!(order(&engine), vec!["Fighter", "Wizard", "Goblin", "Orc"]);
+    assert_eq!(active_name(&engine), "Fighter");
+
+    for _ in 0..4 {
+        engine.next_turn().unwrap();
+    }
+
+    let encounter = engine.active_encounter().unwrap();
+    assert_eq!(encounter.round(), 2);
+    assert_eq!(active_name(&engine), "Fighter");
+
+    engine.previous_turn().unwrap();
+    assert_eq!(engine.active_encounter().unwrap().round(), 1);
+    assert_eq!(active_name(&engine), "Orc");
+}
+
+#[test]
+fn test_unselected_creatures_sit_out() {
+    let mut engine = prepared(&[("Goblin", 12), ("Fighter", 18)]);
+    let goblin = id_of(&engine, "Goblin");
+    engine.set_creature_selected(goblin, false).unwrap();
+
+    assert!(engine.initiative_conflicts(true).unwrap().is_empty());
+    engine.finalize_initiative_order();
+    assert_eq!(order(&engine), vec!["Fighter"]);
+}
+
+#[test]
+fn test_mid_combat_changes() {
+    let mut engine = prepared(&[("Fighter", 18), ("Wizard", 14), ("Goblin", 10)]);
+    engine.finalize_initiative_order();
+    engine.next_turn().unwrap();
+    assert_eq!(active_name(&engine), "Wizard");
+
+    // Arrivals join the order at their initiative, losing ties with the creatures already there. Those ahead of
+    // the wizard don't take the turn from it.
+    engine.add_creatures_to_active_encounter("Ogre, 16\n\n  \nTroll,20\nImp, 10").unwrap();
+    assert_eq!(order(&engine), vec!["Troll", "Fighter", "Ogre", "Wizard", "Goblin", "Imp"]);
+    assert_eq!(active_name(&engine), "Wizard");
+    assert_eq!(engine.creatures().len(), 6);
+    assert!(engine.creatures().get(id_of(&engine, "Ogre")).unwrap().selected());
+
+    // Arrivals without an initiative have one rolled.
+    engine.add_creatures_to_active_encounter("Bat").unwrap();
+    let bat = engine.creatures().get(id_of(&engine, "Bat")).unwrap().initiative();
+    assert!((1..=20).contains(&bat));
+    engine.remove_creature(id_of(&engine, "Bat")).unwrap();
+
+    // Moving creatures around doesn't change whose turn it is.
+    engine.change_active_encounter_order(4, 0).unwrap();
+    assert_eq!(order(&engine), vec!["Goblin", "Troll", "Fighter", "Ogre", "Wizard", "Imp"]);
+    assert_eq!(active_name(&engine), "Wizard");
+
+    // Removing the active creature passes the turn on, and leaves it in the creature list unselected.
+    let wizard = id_of(&engine, "Wizard");
+    engine.remove_from_active_encounter(wizard).unwrap();
+    assert_eq!(order(&engine), vec!["Goblin", "Troll", "Fighter", "Ogre", "Imp"]);
+    assert_eq!(active_name(&engine), "Imp");
+    assert!(!engine.creatures().get(wizard).unwrap().selected());
+
+    assert!(engine.change_active_encounter_order(5, 0).is_err());
+    engine.next_turn().unwrap();
+    assert_eq!(active_name(&engine), "Goblin");
+}
+
+#[test]
+fn test_encounter_shares_the_creature_list() {
+    let mut engine = prepared(&[("Fighter", 18), ("Wizard", 14), ("Goblin", 10)]);
+    engine.finalize_initiative_order();
+    let goblin = id_of(&engine, "Goblin");
+
+    // Changes made to the creature list show up in the encounter straight away.
+    engine.set_creature_initiative(goblin, 20).unwrap();
+    engine.set_creature_hidden(goblin, true, false).unwrap();
+    let view = engine.encounter_view().unwrap();
+    assert_eq!(view.get(goblin).unwrap().initiative(), 20);
+    assert!(view.state(goblin).unwrap().hidden());
+
+    // Removing a creature from the list takes it out of the encounter, and removing it from the encounter leaves
+    // it in the list.
+    let wizard = id_of(&engine, "Wizard");
+    engine.remove_creature(wizard).unwrap();
+    assert_eq!(order(&engine), vec!["Fighter", "Goblin"]);
+    assert!(engine.remove_from_active_encounter(wizard).is_err());
+
+    engine.remove_from_active_encounter(goblin).unwrap();
+    assert_eq!(order(&engine), vec!["Fighter"]);
+    assert!(engine.creatures().get(goblin).is_some());
+}
+
+#[test]
+fn test_update_creature() {
+    let mut engine = prepared(&[("Goblin", 12), ("Fighter", 18)]);
+    let goblin = id_of(&engine, "Goblin");
+    let slot = engine.add_initiative_slot(goblin, 2).unwrap();
+    engine.finalize_initiative_order();
+    engine.discard_changes();
+
+    // Fixing a typo keeps the creature's ID and everything else about it.
+    let patch = CreaturePatch { name: Some(String::from("Hobgoblin")), side: Some(Side::Players), ..CreaturePatch::default() };
+    let updated = engine.update_creature(slot.id(), patch).unwrap();
+    assert_eq!((updated.name(), updated.initiative()), ("Hobgoblin", 2));
+    assert_eq!(engine.creatures().get(goblin).unwrap().side(), Side::Players);
+    assert_eq!(order(&engine), vec!["Fighter", "Hobgoblin", "Hobgoblin"]);
+    assert!(engine.document().dirty());
+
+    let patch = CreaturePatch { initiative: Some(20), initiative_modifier: Some(2), ..CreaturePatch::default() };
+    let updated = engine.update_creature(goblin, patch).unwrap();
+    assert_eq!((updated.initiative(), updated.initiative_modifier()), (20, 2));
+
+    for name in ["", "  ", "fighter"] {
+        let patch = CreaturePatch { name: Some(name.to_string()), ..CreaturePatch::default() };
+        assert!(matches!(engine.update_creature(goblin, patch), Err(BackendError::ArgumentError { .. })));
+    }
+
+    // Creatures added with the same name can keep it, but can't be renamed to another creature's name.
+    engine.add_creatures("Orc\nOrc");
+    let orcs = engine.creatures().iter().filter(|c| c.name() == "Orc").map(|c| c.id()).collect::<Vec<_>>();
+    let patch = CreaturePatch { name: Some(String::from("orc ")), ..CreaturePatch::default() };
+    assert_eq!(engine.update_creature(orcs[0], patch).unwrap().name(), "orc");
+    let patch = CreaturePatch { name: Some(String::from("Fighter")), ..CreaturePatch::default() };
+    assert!(engine.update_creature(orcs[1], patch).is_err());
+
+    assert!(engine.update_creature(Uuid::new_v4(), CreaturePatch::default()).is_err());
+}
+
+#[test]
+fn test_duplicate_creature() {
+    let mut engine = prepared(&[("Fighter", 18), ("Orc", 12), ("Wizard", 8)]);
+    let orc = id_of(&engine, "Orc");
+    engine.set_creature_hit_points(orc, Some(15)).unwrap();
+    engine.finalize_initiative_order();
+    engine.next_turn().unwrap();
+
+    // The copy joins the encounter at its initiative without taking the turn from the orc.
+    let copy = engine.duplicate_creature(orc, false).unwrap();
+    assert_eq!(copy.name(), "Orc 2");
+    assert_eq!(order(&engine), vec!["Fighter", "Orc", "Orc 2", "Wizard"]);
+    assert_eq!(active_name(&engine), "Orc");
+
+    engine.damage_creature(copy.id(), &[Damage::new(10, None)]).unwrap();
+    assert_eq!(engine.creatures().get(orc).unwrap().hit_points().unwrap().current(), 15);
+
+    let rolled = engine.duplicate_creature(copy.id(), true).unwrap();
+    assert_eq!(rolled.name(), "Orc 3");
+    assert!((1..=20).contains(&rolled.initiative()));
+    assert_eq!(engine.encounter_view().unwrap().creatures().count(), 5);
+
+    // Creatures sitting out the encounter are copied into the creature list only.
+    engine.remove_from_active_encounter(orc).unwrap();
+    engine.duplicate_creature(orc, false).unwrap();
+    assert_eq!(engine.active_encounter().unwrap().len(), 4);
+    assert_eq!(engine.creatures().len(), 6);
+}
+
+#[test]
+fn test_duplicate_creature_follows_the_mode() {
+    let mut engine = prepared(&[("Fighter", 18), ("Wizard", 8)]);
+    let mut creature = Creature::from("Goblin");
+    creature.set_initiative(12);
+    creature.set_initiative_modifier(30);
+    creature.set_selected(true);
+    creature.set_hit_points(Some(HitPoints::new(40)));
+    creature.set_stat_block(Some(StatBlock { hit_dice: String::from("2d6"), ..StatBlock::default() }));
+    let goblin = creature.id();
+    engine.import_creatures(vec![creature]);
+    engine.set_initiative_mode(InitiativeMode::Popcorn);
+    engine.finalize_initiative_order();
+
+    // The copy lands before the wizard whose turn it is, so it has arrived too late to be picked this round.
+    let wizard = id_of(&engine, "Wizard");
+    engine.pass_turn_to(wizard).unwrap();
+    let copy = engine.duplicate_creature(goblin, false).unwrap();
+    assert_eq!(order(&engine), vec!["Fighter", "Goblin", "Goblin 2", "Wizard"]);
+    assert_eq!(active_name(&engine), "Wizard");
+    assert_eq!(copy.hit_points().unwrap().maximum(), 40);
+    assert!(engine.pass_turn_to(copy.id()).is_err());
+    engine.pass_turn_to(goblin).unwrap();
+
+    // Rerolled copies get hit points from their hit dice rather than the original's maximum, and add the original's
+    // modifier to their initiative roll.
+    let rolled = engine.duplicate_creature(goblin, true).unwrap();
+    assert!((2..=12).contains(&rolled.hit_points().unwrap().maximum()));
+    assert!((31..=50).contains(&rolled.initiative()));
+}
+
+#[test]
+fn test_duplicate_creature_with_side_initiative() {
+    let mut engine = prepared(&[("Fighter", 5), ("Goblin", 20), ("Wizard", 10)]);
+    let fighter = id_of(&engine, "Fighter");
+    let wizard = id_of(&engine, "Wizard");
+    engine.set_creature_side(fighter, Side::Players).unwrap();
+    engine.set_creature_side(wizard, Side::Players).unwrap();
+    engine.set_initiative_mode(InitiativeMode::Side { players: 15, monsters: 8 });
+    engine.finalize_initiative_order();
+
+    // The copy acts with the monsters even though its own initiative is higher than everyone's.
+    engine.duplicate_creature(id_of(&engine, "Goblin"), false).unwrap();
+    assert_eq!(order(&engine), vec!["Wizard", "Fighter", "Goblin", "Goblin 2"]);
+}
+
+#[test]
+fn test_wait_and_resume() {
+    let mut engine = prepared(&[("Fighter", 18), ("Wizard", 14), ("Goblin", 10)]);
+    engine.finalize_initiative_order();
+    engine.discard_changes();
+
+    engine.wait_active_creature(WaitReason::Delay).unwrap();
+    assert_eq!(active_name(&engine), "Wizard");
+
+    engine.next_turn().unwrap();
+    let fighter = id_of(&engine, "Fighter");
+    engine.resume_creature(fighter, ResumePosition::After).unwrap();
+
+    // Delaying takes on the initiative of the creature resumed next to, which is kept.
+    assert_eq!(order(&engine), vec!["Wizard", "Goblin", "Fighter"]);
+    assert_eq!(engine.creatures().get(fighter).unwrap().initiative(), 10);
+    assert!(engine.document().dirty());
+
+    assert!(matches!(engine.resume_creature(fighter, ResumePosition::After), Err(BackendError::ArgumentError { .. })));
+}
+
+#[test]
+fn test_hit_points_through_slots() {
+    let mut engine = prepared(&[("Dragon", 20), ("Fighter", 15)]);
+    let dragon = id_of(&engine, "Dragon");
+    let slot = engine.add_initiative_slot(dragon, 10).unwrap();
+    engine.set_creature_hit_points(dragon, Some(100)).unwrap();
+    engine.finalize_initiative_order();
+
+    assert_eq!(order(&engine), vec!["Dragon", "Fighter", "Dragon"]);
+
+    // Damage dealt to the slot is taken by the dragon, in both the creature list and the encounter.
+    engine.damage_creature(slot.id(), &[Damage::new(30, None)]).unwrap();
+    engine.heal_creature(dragon, 5).unwrap();
+
+    assert_eq!(engine.creatures().get(dragon).unwrap().hit_points().unwrap().current(), 75);
+    let view = engine.encounter_view().unwrap();
+    assert_eq!(view.state(slot.id()).unwrap().hit_points().unwrap().current(), 75);
+
+    // Removing the dragon takes its slot with it, from the creature list and the encounter alike.
+    engine.remove_creature(dragon).unwrap();
+    assert_eq!(engine.creatures().len(), 1);
+    assert_eq!(order(&engine), vec!["Fighter"]);
+}
+
+#[test]
+fn test_typed_damage() {
+    let mut skeleton = Creature::from("Skeleton");
+    skeleton.set_hit_points(Some(HitPoints::new(50)));
+    skeleton.set_stat_block(Some(StatBlock {
+        damage_vulnerabilities: vec![DamageType::Bludgeoning],
+        damage_resistances: vec![DamageType::Fire],
+        damage_immunities: vec![DamageType::Poison],
+        ..StatBlock::default()
+    }));
+    let slot = skeleton.new_slot(5);
+    let slot_id = slot.id();
+
+    // Defenses come from the stat block saved with the encounter.
+    let path = std::env::temp_dir().join(format!("engine-scenario-{}.enc", Uuid::new_v4()));
+    let creatures = CreatureContainer::from(vec![skeleton, slot, Creature::from("Fighter")]);
+    encounter_file::save_encounter(&path, &EncounterData::from(&creatures)).unwrap();
+
+    let mut engine = EncounterEngine::default();
+    engine.load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let skeleton = id_of(&engine, "Skeleton");
+    let current = |engine: &EncounterEngine| engine.creatures().get(skeleton).unwrap().hit_points().unwrap().current();
+
+    // 8 slashing is taken as it is and 5 fire is halved, rounding down.
+    assert_eq!(engine.damage_creature(skeleton, &parse_damage("8 slashing + 5 fire").unwrap()).unwrap(), 10);
+    assert_eq!(current(&engine), 40);
+
+    // Hits on an extra initiative slot use the stat block of the creature it belongs to.
+    assert_eq!(engine.damage_creature(slot_id, &parse_damage("6 bludgeoning, 20 poison").unwrap()).unwrap(), 12);
+    assert_eq!(current(&engine), 28);
+
+    // Creatures without hit points can't be damaged, and the failed hit leaves nothing to save.
+    let fighter = id_of(&engine, "Fighter");
+    engine.discard_changes();
+    assert!(matches!(engine.damage_creature(fighter, &parse_damage("5").unwrap()), Err(BackendError::ArgumentError { .. })));
+    assert!(!engine.document().dirty());
+
+    // Creatures without a stat block take every type of damage in full.
+    engine.set_creature_hit_points(fighter, Some(30)).unwrap();
+    assert_eq!(engine.damage_creature(fighter, &[Damage::new(20, Some(DamageType::Poison))]).unwrap(), 20);
+
+    // Damage types can be given to them by hand, which starts a stat block that goes again once they're cleared.
+    let resist_poison = |types: Vec<DamageType>| CreaturePatch { damage_resistances: Some(types), ..CreaturePatch::default() };
+    engine.update_creature(fighter, resist_poison(vec![DamageType::Poison])).unwrap();
+    assert_eq!(engine.damage_creature(fighter, &[Damage::new(6, Some(DamageType::Poison))]).unwrap(), 3);
+
+    engine.update_creature(fighter, resist_poison(vec![])).unwrap();
+    assert!(engine.creatures().get(fighter).unwrap().stat_block().is_none());
+
+    // Changing one list leaves the rest of an existing stat block alone.
+    engine.update_creature(skeleton, CreaturePatch { damage_immunities: Some(vec![]), ..CreaturePatch::default() }).unwrap();
+    let stat_block = engine.creatures().get(skeleton).unwrap().stat_block().unwrap().clone();
+    assert!(stat_block.damage_immunities.is_empty());
+    assert_eq!(stat_block.damage_resistances, vec![DamageType::Fire]);
+}
+
+#[test]
+fn test_side_initiative() {
+    let mut engine = prepared(&[("Fighter", 5), ("Goblin", 20), ("Wizard", 10)]);
+    let fighter = id_of(&engine, "Fighter");
+    let wizard = id_of(&engine, "Wizard");
+    engine.set_creature_side(fighter, Side::Players).unwrap();
+    engine.set_creature_side(wizard, Side::Players).unwrap();
+    engine.set_initiative_mode(InitiativeMode::Side { players: 15, monsters: 8 });
+    engine.finalize_initiative_order();
+
+    assert_eq!(order(&engine), vec!["Wizard", "Fighter", "Goblin"]);
+}
+
+#[test]
+fn test_reveal_with_side_initiative() {
+    let mut engine = prepared(&[("Fighter", 5), ("Goblin", 20), ("Wizard", 10)]);
+    let goblin = id_of(&engine, "Goblin");
+    for name in ["Fighter", "Wizard"] {
+        engine.set_creature_side(id_of(&engine, name), Side::Players).unwrap();
+    }
+
+    engine.set_creature_hidden(goblin, true, false).unwrap();
+    engine.set_initiative_mode(InitiativeMode::Side { players: 15, monsters: 8 });
+    engine.finalize_initiative_order();
+    engine.change_active_encounter_order(2, 0).unwrap();
+
+    // The ambusher goes back to acting with the monsters, however high its own roll was.
+    engine.set_creature_hidden(goblin, false, true).unwrap();
+    assert_eq!(order(&engine), vec!["Wizard", "Fighter", "Goblin"]);
+}
+
+#[test]
+fn test_popcorn_initiative() {
+    let mut engine = prepared(&[("Fighter", 18), ("Wizard", 14), ("Goblin", 10)]);
+    engine.set_initiative_mode(InitiativeMode::Popcorn);
+    engine.finalize_initiative_order();
+
+    let goblin = id_of(&engine, "Goblin");
+    engine.pass_turn_to(goblin).unwrap();
+    assert_eq!(active_name(&engine), "Goblin");
+
+    let fighter = id_of(&engine, "Fighter");
+    assert!(engine.pass_turn_to(fighter).is_err());
+}
+
+#[test]
+fn test_card_initiative() {
+    let mut engine = prepared(&[("Fighter", 0), ("Wizard", 0), ("Goblin", 0)]);
+    engine.set_initiative_mode(InitiativeMode::Cards);
+    engine.deal_initiative_cards();
+
+    assert!(engine.creatures().iter().all(|c| c.card().is_some()));
+    assert_eq!(engine.deck().remaining(), 54 - 3);
+
+    engine.finalize_initiative_order();
+    for _ in 0..3 {
+        engine.next_turn().unwrap();
+    }
+
+    assert!(engine.active_encounter().unwrap().awaiting_initiative());
+    engine.deal_round_cards().unwrap();
+
+    // The new cards are dealt to the creature list, so they are saved with the encounter.
+    assert!(!engine.active_encounter().unwrap().awaiting_initiative());
+    for creature in engine.encounter_view().unwrap().creatures() {
+        assert!(creature.card().is_some());
+        assert_eq!(creature.initiative(), creature.card().unwrap().initiative());
+    }
+}
+
+/// Plays through whole rounds of card based initiative, checking that no two creatures ever hold the same card.
+fn play_card_rounds(engine: &mut EncounterEngine, rounds: usize) {
+    for _ in 0..rounds {
+        while !engine.active_encounter().unwrap().awaiting_initiative() {
+            engine.next_turn().unwrap();
+        }
+
+        engine.deal_round_cards().unwrap();
+
+        let mut cards = engine.creatures().iter().filter_map(|c| c.card()).collect::<Vec<_>>();
+        let dealt = cards.len();
+        cards.sort();
+        cards.dedup();
+        assert_eq!(cards.len(), dealt, "A card was dealt to more than one creature");
+    }
+}
+
+#[test]
+fn test_dealt_cards_stay_unique() {
+    let names = (1..=12).map(|n| format!("Goblin {}", n)).collect::<Vec<_>>();
+    let mut engine = prepared(&names.iter().map(|name| (name.as_str(), 0)).collect::<Vec<_>>());
+    engine.set_initiative_mode(InitiativeMode::Cards);
+
+    // Dealing again starts a fresh deck, which must not take back the cards it replaces.
+    engine.deal_initiative_cards();
+    engine.deal_initiative_cards();
+    engine.finalize_initiative_order();
+    play_card_rounds(&mut engine, 30);
+
+    // The deck isn't saved, so a loaded encounter has to leave out the cards its creatures already hold.
+    let path = std::env::temp_dir().join(format!("engine-scenario-{}.enc", Uuid::new_v4()));
+    engine.save(&path).unwrap();
+    let mut loaded = EncounterEngine::default();
+    loaded.load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.deck().remaining(), 54 - 12);
+    loaded.set_all_creatures_selected(true);
+    loaded.finalize_initiative_order();
+    play_card_rounds(&mut loaded, 30);
+}
+
+#[test]
+fn test_turns_wait_for_new_initiatives() {
+    for mode in [InitiativeMode::RerollEachRound, InitiativeMode::Cards] {
+        let mut engine = prepared(&[("Fighter", 18), ("Goblin", 10)]);
+        engine.set_initiative_mode(mode);
+        engine.finalize_initiative_order();
+        engine.next_turn().unwrap();
+        engine.next_turn().unwrap();
+
+        // The old order can't be played through while the new round is waiting for its initiatives.
+        assert!(engine.active_encounter().unwrap().awaiting_initiative());
+        assert!(matches!(engine.next_turn(), Err(BackendError::LogicError(_))));
+
+        match mode {
+            InitiativeMode::Cards => engine.deal_round_cards().unwrap(),
+            _ => {
+                let initiatives = [(id_of(&engine, "Fighter"), 3), (id_of(&engine, "Goblin"), 15)];
+                engine.set_round_initiatives(&initiatives).unwrap();
+                assert_eq!(order(&engine), vec!["Goblin", "Fighter"]);
+            }
+        }
+
+        engine.next_turn().unwrap();
+        assert_eq!(engine.active_encounter().unwrap().round(), 2);
+    }
+}
+
+#[test]
+fn test_rounds_ended_by_waiting_or_removal_wait_for_new_initiatives() {
+    for mode in [InitiativeMode::RerollEachRound, InitiativeMode::Cards] {
+        for remove in [false, true] {
+            let mut engine = prepared(&[("Fighter", 18), ("Wizard", 14), ("Goblin", 10)]);
+            engine.set_initiative_mode(mode);
+            engine.deal_initiative_cards();
+            engine.finalize_initiative_order();
+            engine.next_turn().unwrap();
+            engine.next_turn().unwrap();
+
+            // Taking the last creature out of the order ends the round just like its turn ending would.
+            let last = engine.active_encounter().unwrap().active_id().unwrap();
+            match remove {
+                true => engine.remove_from_active_encounter(last).unwrap(),
+                false => engine.wait_active_creature(WaitReason::Delay).unwrap()
+            }
+
+            let encounter = engine.active_encounter().unwrap();
+            assert_eq!(encounter.round(), 2);
+            assert!(encounter.awaiting_initiative());
+            assert!(matches!(engine.next_turn(), Err(BackendError::LogicError(_))));
+
+            match mode {
+                InitiativeMode::Cards => engine.deal_round_cards().unwrap(),
+                _ => {
+                    let initiatives = engine.active_encounter().unwrap().order().iter().map(|&id| (id, 10)).collect::<Vec<_>>();
+                    engine.set_round_initiatives(&initiatives).unwrap();
+                }
+            }
+
+            assert!(!engine.active_encounter().unwrap().awaiting_initiative());
+            engine.next_turn().unwrap();
+        }
+    }
+}
+
+#[test]
+fn test_actions_need_a_finalized_order() {
+    let mut engine = prepared(&[("Fighter", 18)]);
+    let fighter = id_of(&engine, "Fighter");
+
+    assert!(matches!(engine.active_encounter(), Err(BackendError::LogicError(_))));
+    assert!(matches!(engine.next_turn(), Err(BackendError::LogicError(_))));
+    assert!(matches!(engine.remove_from_active_encounter(fighter), Err(BackendError::LogicError(_))));
+    assert!(matches!(engine.initiative_conflicts(false), Err(BackendError::LogicError(_))));
+    assert!(matches!(engine.set_creature_initiative(Uuid::new_v4(), 5), Err(BackendError::ArgumentError { .. })));
+}
+
+#[test]
+fn test_failed_changes_are_rolled_back() {
+    let mut engine = prepared(&[("Fighter", 18), ("Goblin", 10)]);
+    engine.finalize_initiative_order();
+    let fighter = id_of(&engine, "Fighter");
+
+    // The second step fails, so the fighter stays in the encounter.
+    let result = engine.transaction(|engine| {
+        engine.remove_from_active_encounter(fighter)?;
+        engine.set_creature_initiative(Uuid::new_v4(), 5)
+    });
+    assert!(result.is_err());
+    assert_eq!(order(&engine), vec!["Fighter", "Goblin"]);
+    assert!(engine.creatures().get(fighter).unwrap().selected());
+
+    engine.transaction(|engine| engine.remove_from_active_encounter(fighter)).unwrap();
+    assert_eq!(order(&engine), vec!["Goblin"]);
+}
+
+#[test]
+fn test_save_and_load() {
+    let path = std::env::temp_dir().join(format!("engine-scenario-{}.enc", Uuid::new_v4()));
+
+    let mut engine = prepared(&[("Fighter", 18), ("Goblin", 10)]);
+    let patch = CreaturePatch { notes: Some(String::from("## Tactics\nFlees below *half* HP.")), ..CreaturePatch::default() };
+    engine.update_creature(id_of(&engine, "Goblin"), patch).unwrap();
+    engine.set_initiative_mode(InitiativeMode::RerollEachRound);
+    engine.rename("Ambush");
+    engine.save(&path).unwrap();
+    assert!(!engine.document().dirty());
+    assert_eq!(engine.name(), "Ambush");
+
+    let copy = engine.duplicate();
+    assert!(copy.document().dirty());
+    assert_eq!(copy.document().path(), None);
+    assert_eq!(copy.name(), "Ambush (copy)");
+
+    let mut loaded = EncounterEngine::default();
+    loaded.load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.initiative_mode(), InitiativeMode::RerollEachRound);
+    assert_eq!(loaded.creatures().iter().map(|c| (c.name(), c.initiative())).collect::<Vec<_>>(), vec![("Fighter", 18), ("Goblin", 10)]);
+    assert_eq!(loaded.creatures().get(id_of(&loaded, "Goblin")).unwrap().notes(), "## Tactics\nFlees below *half* HP.");
+    assert!(loaded.creatures().get(id_of(&loaded, "Goblin")).unwrap().matches_search("FLEES"));
+    assert!(!loaded.creatures().get(id_of(&loaded, "Fighter")).unwrap().matches_search("flees"));
+    assert_eq!(loaded.document().path(), Some(path.as_path()));
+    assert!(!loaded.document().dirty());
+
+    loaded.reset();
+    assert_eq!(loaded.creatures().len(), 0);
+    assert_eq!(loaded.document().path(), None);
+}
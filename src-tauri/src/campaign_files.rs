@@ -0,0 +1,24 @@
+use std::{fs, path::Path};
+
+use common_data_lib::campaign::Campaign;
+
+use crate::error::Error;
+
+/// The file in a campaign directory that holds everything but the encounters.
+pub const CAMPAIGN_FILE: &str = "campaign.json";
+
+pub fn is_campaign(directory: &Path) -> bool {
+    directory.join(CAMPAIGN_FILE).is_file()
+}
+
+pub fn load_campaign(directory: &Path) -> Result<Campaign, Error> {
+    let content = fs::read_to_string(directory.join(CAMPAIGN_FILE))?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+pub fn save_campaign(directory: &Path, campaign: &Campaign) -> Result<(), Error> {
+    fs::create_dir_all(directory.join(Campaign::ENCOUNTERS_DIRECTORY))?;
+    fs::write(directory.join(CAMPAIGN_FILE), serde_json::to_string_pretty(campaign)?)?;
+
+    Ok(())
+}
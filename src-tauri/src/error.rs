@@ -1,23 +1,50 @@
-use std::sync::LockResult;
-
 use log::Level;
 
+#[derive(Debug)]
 pub enum Error {
     IoError(std::io::Error),
+    JsonError(serde_json::Error),
     TomlDeserializeError(toml::de::Error),
     TomlSerializeError(toml::ser::Error)
 }
 
-pub fn log_lock_error<T>(result: LockResult<T>, msg: impl Into<String>) -> LockResult<T> {
-    match result {
-        Ok(res) => Ok(res),
-        Err(err) => {
-            log::warn!("{}: {}", msg.into(), err.to_string());
-            Err(err)
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::IoError(e) => write!(f, "IO error: {}", e),
+            Self::JsonError(e) => write!(f, "JSON error: {}", e),
+            Self::TomlDeserializeError(e) => write!(f, "TOML deserialize error: {}", e),
+            Self::TomlSerializeError(e) => write!(f, "TOML serialize error: {}", e),
         }
     }
 }
 
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(value: std::io::Error) -> Self {
+        Self::IoError(value)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(value: serde_json::Error) -> Self {
+        Self::JsonError(value)
+    }
+}
+
+impl From<toml::de::Error> for Error {
+    fn from(value: toml::de::Error) -> Self {
+        Self::TomlDeserializeError(value)
+    }
+}
+
+impl From<toml::ser::Error> for Error {
+    fn from(value: toml::ser::Error) -> Self {
+        Self::TomlSerializeError(value)
+    }
+}
+
 pub fn log<T: std::fmt::Display>(error: T, level: Level) -> T {
     match level {
         Level::Error => log::error!("{}", error),
@@ -1,284 +1,725 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+pub mod campaign_files;
 pub mod error;
+pub mod recent_files;
+pub mod session;
+pub mod settings;
 
-use std::{sync::Mutex, path::PathBuf, fs::{self, DirEntry}, cmp::Ordering};
+use std::{sync::{Mutex, MutexGuard}, path::{Path, PathBuf}, fs::{self, DirEntry}, cmp::Ordering, time::{SystemTime, UNIX_EPOCH}};
 
 use chrono::Local;
-use common_data_lib::{creatures::{CreatureContainer, Creature, OrderMode, ConflictGroup}, BackendError, ToBackendResult};
-use error::{log_lock_error, log};
+use common_data_lib::{encounter_file, campaign::{Campaign, CampaignEncounter, CampaignWorkspace, EncounterStatus, Roster, RosterCreature}, cards::CardEdge, damage::Damage, creatures::{CreatureContainer, Creature, CreaturePatch, ConflictGroup, EncounterData, Side}, csv_files::{self, CsvColumnMapping}, document::{DocumentState, EncounterTabs}, encounter::{EncounterView, ResumePosition, WaitReason}, engine::EncounterEngine, initiative::InitiativeMode, recent_files::{RecentFiles, RecentFilesList}, settings::{Settings, LogLevel}, BackendError, ToBackendResult};
+use error::log;
+use session::Sessions;
 use log::{SetLoggerError, LevelFilter, Level};
-use log4rs::{append::{console::{ConsoleAppender, Target}, file::FileAppender}, encode::pattern::PatternEncoder, Config, config::{Appender, Root}, filter::threshold::ThresholdFilter};
-use tauri::{State, Manager};
+use log4rs::{append::{console::{ConsoleAppender, Target}, file::FileAppender}, encode::pattern::PatternEncoder, Config, config::{Appender, Root}, filter::threshold::ThresholdFilter, Handle};
+use tauri::{State, Manager, LogicalSize, Window, WindowEvent};
 use uuid::Uuid;
 
-const MAX_LOG_COUNT: usize = 10;
+const APP_NAME: &str = "Initiative app";
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Default)]
+struct AppData {
+    settings: Settings,
+    recent_files: RecentFiles,
+    sessions: Sessions,
+    campaign: Option<CampaignWorkspace>
+}
+
+impl AppData {
+    fn engine(&self, encounter_id: Uuid) -> Result<&EncounterEngine, BackendError> {
+        self.sessions.get(encounter_id).ok_or_else(|| no_encounter(encounter_id))
+    }
+
+    fn engine_mut(&mut self, encounter_id: Uuid) -> Result<&mut EncounterEngine, BackendError> {
+        self.sessions.get_mut(encounter_id).ok_or_else(|| no_encounter(encounter_id))
+    }
+}
+
+fn no_encounter(encounter_id: Uuid) -> BackendError {
+    BackendError::argument_error("encounter_id", format!("No encounter with id '{}' is open", encounter_id))
+}
+
+#[derive(Debug, Default)]
 struct AppState {
-    creatures: Mutex<CreatureContainer>,
-    conflicts: Mutex<Option<Vec<ConflictGroup>>>,
-    encounter: Mutex<Option<CreatureContainer>>
+    data: Mutex<AppData>
 }
 
-impl Default for AppState {
-    fn default() -> Self {
-        Self {
-            creatures: Mutex::new(CreatureContainer::default()),
-            conflicts: Mutex::new(None),
-            encounter: Mutex::new(None)
-        }
+impl AppState {
+    /// Changes are only stored once they have succeeded, so a command that panicked while holding the lock can't
+    /// have left anything half changed. The lock is recovered rather than failing every command after it.
+    fn lock(&self) -> MutexGuard<AppData> {
+        self.data.lock().unwrap_or_else(|poisoned| {
+            log::warn!("Recovering app state after a command panicked: {}", poisoned);
+            self.data.clear_poison();
+            poisoned.into_inner()
+        })
+    }
+
+    /// Applies a change to a copy of the app's data, storing it only if the whole change succeeds.
+    fn transaction<T>(&self, update: impl FnOnce(&mut AppData) -> Result<T, BackendError>) -> Result<T, BackendError> {
+        let mut data_guard = self.lock();
+        let mut data = data_guard.clone();
+        let result = update(&mut data)?;
+        *data_guard = data;
+
+        Ok(result)
     }
 }
 
-#[tauri::command]
-fn get_creatures(state: State<AppState>) -> Result<Vec<Creature>, BackendError> {
-    let mut creatures_guard = log_lock_error(state.creatures.lock(), "Unable to lock creatures state").to_backend_result()?;
-    creatures_guard.set_order_mode(OrderMode::Alphabetical);
+struct LoggerState {
+    handle: Handle,
+    path: PathBuf
+}
 
-    Ok(creatures_guard.cloned())
+impl LoggerState {
+    fn set_level(&self, level: LevelFilter) {
+        self.handle.set_config(build_logger_config(level, &self.path));
+    }
+}
+
+#[tauri::command]
+fn get_settings(state: State<AppState>) -> Result<Settings, BackendError> {
+    Ok(state.lock().settings.clone())
 }
 
 #[tauri::command]
-fn add_creatures(state: State<AppState>, creatures: String) -> Result<(), BackendError> {
-    let mut creatures_guard = log_lock_error(state.creatures.lock(), "Unable to lock creatures state").to_backend_result()?;
-    for name in creatures.lines().filter(|l| !l.is_empty()) {
-        let creature = Creature::from(name);
-        log::info!("Adding new creature: {}", creature);
-        creatures_guard.push_and_sort(creature);
-        creatures_guard.sort();
+fn update_settings(state: State<AppState>, logger: State<LoggerState>, settings: Settings) -> Result<(), BackendError> {
+    settings.validate().map_err(|e| log(e, Level::Warn))?;
+
+    let mut data_guard = state.lock();
+    settings::save_settings(&settings).to_backend_result()?;
+
+    if settings.console_log_level() != data_guard.settings.console_log_level() {
+        logger.set_level(to_level_filter(settings.console_log_level()));
+        log::info!("Set console log level to {}", settings.console_log_level());
     }
 
+    data_guard.settings = settings;
+
+    log::info!("Saved settings to: '{}'", settings::get_settings_path().to_string_lossy());
+
     Ok(())
 }
 
 #[tauri::command]
-fn remove_creature(state: State<AppState>, id: Uuid) -> Result<Creature, BackendError> {
-    let mut creatures_guard = log_lock_error(state.creatures.lock(), "Unable to lock creatures state").to_backend_result()?;
-    let creature = creatures_guard.remove(id).ok_or(BackendError::argument_error("id", format!("No creature with id '{}' exists", id)))?;
+fn get_recent_files(state: State<AppState>) -> Result<RecentFilesList, BackendError> {
+    state.transaction(|data| {
+        let missing = data.recent_files.remove_missing(|path| path.exists());
+        data.recent_files.update_last_modified(recent_files::get_last_modified);
+
+        if !missing.is_empty() {
+            for file in missing.iter() {
+                log::warn!("Removed missing recent file: '{}'", file.path().to_string_lossy());
+            }
 
-    log::info!("Removed creature: {}", creature);
+            recent_files::save_recent_files(&data.recent_files).to_backend_result()?;
+        }
 
-    Ok(creature)
+        Ok(RecentFilesList {
+            files: data.recent_files.files().to_vec(),
+            missing
+        })
+    })
 }
 
 #[tauri::command]
-fn set_creature_selected(state: State<AppState>, id: Uuid, selected: bool) -> Result<(), BackendError> {
-    let mut creatures_guard = log_lock_error(state.creatures.lock(), "Unable to lock creatures state").to_backend_result()?;
-    let creature = creatures_guard.get_mut(id).ok_or(BackendError::argument_error("id", format!("No creature with id '{}' exists", id)))?;
-    creature.set_selected(selected);
+fn get_last_directory(state: State<AppState>) -> Result<Option<PathBuf>, BackendError> {
+    Ok(state.lock().recent_files.last_directory().map(|p| p.to_path_buf()))
+}
 
-    log::info!("Set creature {} selected state to {}", creature, selected);
+fn add_recent_file(data: &mut AppData, path: &Path) {
+    data.recent_files.add(recent_files::get_recent_file(path));
 
-    Ok(())
+    if let Err(err) = recent_files::save_recent_files(&data.recent_files) {
+        log::warn!("Unable to save recent files: {}", err);
+    }
+}
+
+#[tauri::command]
+fn get_creatures(state: State<AppState>, encounter_id: Uuid) -> Result<Vec<Creature>, BackendError> {
+    let order_mode = state.lock().settings.default_order_mode();
+    with_engine(&state, encounter_id, |engine| {
+        engine.set_order_mode(order_mode);
+        Ok(engine.creatures().cloned())
+    })
 }
 
 #[tauri::command]
-fn set_creature_initiative(state: State<AppState>, id: Uuid, initiative: isize) -> Result<(), BackendError> {
-    let mut creatures_guard = log_lock_error(state.creatures.lock(), "Unable to lock creatures state").to_backend_result()?;
-    let creature = creatures_guard.get_mut(id).ok_or(BackendError::argument_error("id", format!("No creature with id '{}' exists", id)))?;
-    creature.set_initiative(initiative);
+fn add_creatures(state: State<AppState>, encounter_id: Uuid, window: Window, creatures: String) -> Result<(), BackendError> {
+    update_engine(&state, encounter_id, &window, |engine| {
+        engine.add_creatures(&creatures);
+        Ok(())
+    })
+}
 
-    log::info!("Set creature {} initiative to {}", creature, initiative);
+#[tauri::command]
+fn remove_creature(state: State<AppState>, encounter_id: Uuid, window: Window, id: Uuid) -> Result<Creature, BackendError> {
+    update_engine(&state, encounter_id, &window, |engine| engine.remove_creature(id))
+}
 
-    Ok(())
+#[tauri::command]
+fn set_creature_selected(state: State<AppState>, encounter_id: Uuid, id: Uuid, selected: bool) -> Result<(), BackendError> {
+    with_engine(&state, encounter_id, |engine| engine.set_creature_selected(id, selected))
 }
 
 #[tauri::command]
-fn set_all_creatures_selected(state: State<AppState>, selected: bool) -> Result<(), BackendError> {
-    let mut creatures_guard = log_lock_error(state.creatures.lock(), "Unable to lock creatures state").to_backend_result()?;
-    for creature in creatures_guard.iter_mut() {
-        creature.set_selected(selected);
-    }
+fn set_creature_initiative(state: State<AppState>, encounter_id: Uuid, window: Window, id: Uuid, initiative: isize) -> Result<(), BackendError> {
+    update_engine(&state, encounter_id, &window, |engine| engine.set_creature_initiative(id, initiative))
+}
 
-    log::info!("Set all creatures selected state to {}", selected);
+#[tauri::command]
+fn duplicate_creature(state: State<AppState>, encounter_id: Uuid, window: Window, id: Uuid, reroll: bool) -> Result<Creature, BackendError> {
+    update_engine(&state, encounter_id, &window, |engine| engine.duplicate_creature(id, reroll))
+}
 
-    Ok(())
+#[tauri::command]
+fn update_creature(state: State<AppState>, encounter_id: Uuid, window: Window, id: Uuid, patch: CreaturePatch) -> Result<Creature, BackendError> {
+    update_engine(&state, encounter_id, &window, |engine| engine.update_creature(id, patch))
 }
 
 #[tauri::command]
-fn reset_all_initiatives(state: State<AppState>) -> Result<(), BackendError> {
-    let mut creatures_guard = log_lock_error(state.creatures.lock(), "Unable to lock creatures state").to_backend_result()?;
-    for creature in creatures_guard.iter_mut() {
-        creature.set_initiative(0);
-        creature.set_sub_order(0);
-    }
+fn add_initiative_slot(state: State<AppState>, encounter_id: Uuid, window: Window, id: Uuid, initiative: isize) -> Result<Creature, BackendError> {
+    update_engine(&state, encounter_id, &window, |engine| engine.add_initiative_slot(id, initiative))
+}
+
+#[tauri::command]
+fn set_creature_side(state: State<AppState>, encounter_id: Uuid, window: Window, id: Uuid, side: Side) -> Result<(), BackendError> {
+    update_engine(&state, encounter_id, &window, |engine| engine.set_creature_side(id, side))
+}
+
+#[tauri::command]
+fn set_creature_card_edges(state: State<AppState>, encounter_id: Uuid, window: Window, id: Uuid, edges: Vec<CardEdge>) -> Result<(), BackendError> {
+    update_engine(&state, encounter_id, &window, |engine| engine.set_creature_card_edges(id, edges))
+}
+
+#[tauri::command]
+fn deal_initiative_cards(state: State<AppState>, encounter_id: Uuid, window: Window) -> Result<(), BackendError> {
+    update_engine(&state, encounter_id, &window, |engine| {
+        engine.deal_initiative_cards();
+        Ok(())
+    })
+}
+
+#[tauri::command]
+fn get_initiative_mode(state: State<AppState>, encounter_id: Uuid) -> Result<InitiativeMode, BackendError> {
+    with_engine(&state, encounter_id, |engine| Ok(engine.initiative_mode()))
+}
+
+#[tauri::command]
+fn set_initiative_mode(state: State<AppState>, encounter_id: Uuid, window: Window, mode: InitiativeMode) -> Result<(), BackendError> {
+    update_engine(&state, encounter_id, &window, |engine| {
+        engine.set_initiative_mode(mode);
+        Ok(())
+    })
+}
 
-    log::info!("Reset initiative order");
+#[tauri::command]
+fn set_creature_hidden(state: State<AppState>, encounter_id: Uuid, window: Window, id: Uuid, hidden: bool, move_to_initiative: bool) -> Result<(), BackendError> {
+    update_engine(&state, encounter_id, &window, |engine| engine.set_creature_hidden(id, hidden, move_to_initiative))
+}
+
+#[tauri::command]
+fn set_all_creatures_selected(state: State<AppState>, encounter_id: Uuid, selected: bool) -> Result<(), BackendError> {
+    with_engine(&state, encounter_id, |engine| {
+        engine.set_all_creatures_selected(selected);
+        Ok(())
+    })
+}
+
+#[tauri::command]
+fn reset_all_initiatives(state: State<AppState>, encounter_id: Uuid, window: Window) -> Result<(), BackendError> {
+    update_engine(&state, encounter_id, &window, |engine| {
+        engine.reset_all_initiatives();
+        Ok(())
+    })
+}
+
+#[tauri::command]
+fn save_encounter(state: State<AppState>, encounter_id: Uuid, window: Window, path: PathBuf) -> Result<(), BackendError> {
+    update_engine(&state, encounter_id, &window, |engine| engine.save(&path))?;
+    add_recent_file(&mut state.lock(), &path);
 
     Ok(())
 }
 
+/// Saves the encounter to the file it was loaded from or last saved to. Returns false if there is no such file.
 #[tauri::command]
-fn save_encounter(state: State<AppState>, path: PathBuf) -> Result<(), BackendError> {
-    let creatures_guard = log_lock_error(state.creatures.lock(), "Unable to lock creatures state").to_backend_result()?;
-    creatures_guard.save_to(&path)?;
+fn save_current_encounter(state: State<AppState>, encounter_id: Uuid, window: Window) -> Result<bool, BackendError> {
+    let path = with_engine(&state, encounter_id, |engine| Ok(engine.document().path().map(|p| p.to_path_buf())))?;
+
+    match path {
+        Some(path) => save_encounter(state, encounter_id, window, path).map(|_| true),
+        None => Ok(false)
+    }
+}
 
-    log::info!("Saved encounter to: '{}'", path.to_string_lossy());
+#[tauri::command]
+fn load_encounter(state: State<AppState>, encounter_id: Uuid, window: Window, path: PathBuf) -> Result<(), BackendError> {
+    update_engine(&state, encounter_id, &window, |engine| engine.load(&path))?;
+    add_recent_file(&mut state.lock(), &path);
 
     Ok(())
 }
 
 #[tauri::command]
-fn load_encounter(state: State<AppState>, path: PathBuf) -> Result<(), BackendError> {
-    let mut creatures_guard = log_lock_error(state.creatures.lock(), "Unable to lock creatures state").to_backend_result()?;
-    let new_creatures = CreatureContainer::load_from(&path)?;
-    *creatures_guard = new_creatures;
+fn export_creatures_csv(state: State<AppState>, encounter_id: Uuid, path: PathBuf, for_gm: bool) -> Result<(), BackendError> {
+    with_engine(&state, encounter_id, |engine| csv_files::write_csv_creatures(&path, engine.creatures(), for_gm))?;
 
-    log::info!("Loaded encounter from: '{}'", path.to_string_lossy());
+    log::info!("Exported creatures to: '{}'", path.to_string_lossy());
 
     Ok(())
 }
 
 #[tauri::command]
-fn new_encounter(state: State<AppState>) -> Result<(), BackendError> {
-    let mut creatures_guard = log_lock_error(state.creatures.lock(), "Unable to lock creatures state").to_backend_result()?;
-    *creatures_guard = CreatureContainer::default();
+fn get_csv_headers(path: PathBuf) -> Result<Vec<String>, BackendError> {
+    csv_files::read_csv_headers(&path)
+}
+
+#[tauri::command]
+fn import_creatures_csv(state: State<AppState>, encounter_id: Uuid, window: Window, path: PathBuf, mapping: CsvColumnMapping) -> Result<(), BackendError> {
+    let new_creatures = csv_files::read_csv_creatures(&path, &mapping)
+        .map_err(|e| log(e, Level::Warn))?;
 
-    log::info!("Beginning a new encounter");
+    let count = new_creatures.len();
+    update_engine(&state, encounter_id, &window, |engine| {
+        engine.import_creatures(new_creatures);
+        Ok(())
+    })?;
+
+    log::info!("Imported {} creatures from: '{}'", count, path.to_string_lossy());
 
     Ok(())
 }
 
 #[tauri::command]
-fn get_initiative_conflicts(state: State<AppState>, set_conflicts: bool) -> Result<Vec<ConflictGroup>, BackendError> {
-    let creatures_guard = log_lock_error(state.creatures.lock(), "Unable to lock creatures state").to_backend_result()?;
-    let mut conflicts_guard = log_lock_error(state.conflicts.lock(), "Unable to lock conflicts state").to_backend_result()?;
-    
-    if set_conflicts {
-        *conflicts_guard = Some(creatures_guard.get_conflicts());
-    }
+fn new_encounter(state: State<AppState>, encounter_id: Uuid, window: Window) -> Result<(), BackendError> {
+    update_engine(&state, encounter_id, &window, |engine| {
+        engine.reset();
+        Ok(())
+    })
+}
 
-    match &*conflicts_guard {
-        None => return Err(log(BackendError::logic_error("Could not get conflicts because they have not been generated"), Level::Error)),
-        Some(conflicts) => Ok(conflicts.clone())
-    }
+#[tauri::command]
+fn get_document_state(state: State<AppState>, encounter_id: Uuid) -> Result<DocumentState, BackendError> {
+    with_engine(&state, encounter_id, |engine| Ok(engine.document().clone()))
 }
 
+/// Closes the window, optionally throwing away unsaved changes in every open encounter so the close is not
+/// intercepted again.
 #[tauri::command]
-fn move_initiative_conflict(state: State<AppState>, group_index: usize, move_index: usize, target_index: usize) -> Result<(), BackendError> {
-    let mut conflicts_guard = log_lock_error(state.conflicts.lock(), "Unable to lock conflicts state").to_backend_result()?;
-    let conflicts = match &mut *conflicts_guard {
-        Some(conflicts) => conflicts,
-        None => return Err(log(BackendError::logic_error("Could not move conflict because they have not been generated"), Level::Error))
-    };
-    
-    if group_index >= conflicts.len() {
-        return Err(log(BackendError::argument_error("group_index", format!("Group index {} is out of bounds", move_index)), Level::Error));
+fn close_window(state: State<AppState>, window: Window, discard_changes: bool) -> Result<(), BackendError> {
+    if discard_changes {
+        for (_, engine) in state.lock().sessions.iter_mut() {
+            engine.discard_changes();
+        }
+
+        log::info!("Discarding unsaved changes");
     }
 
-    let group = &mut conflicts[group_index];
+    window.close().to_backend_result()
+}
 
-    let creatures = group.creatures_mut();
-    if move_index >= creatures.len() {
-        return Err(log(BackendError::argument_error("move_index", format!("Creature index {} is out of bounds", move_index)), Level::Error));
-    }
+fn with_engine<T>(state: &AppState, encounter_id: Uuid, action: impl FnOnce(&mut EncounterEngine) -> Result<T, BackendError>) -> Result<T, BackendError> {
+    state.lock()
+        .engine_mut(encounter_id)
+        .and_then(|engine| engine.transaction(action))
+        .map_err(|e| log(e, Level::Error))
+}
 
-    if target_index >= creatures.len() {
-        return Err(log(BackendError::argument_error("target_index", format!("Creature index {} is out of bounds", target_index)), Level::Error));
-    }
+/// Runs an action that may change an encounter's file or saved state, keeping the window title and tabs up to date.
+fn update_engine<T>(state: &AppState, encounter_id: Uuid, window: &Window, action: impl FnOnce(&mut EncounterEngine) -> Result<T, BackendError>) -> Result<T, BackendError> {
+    let (result, document) = with_engine(state, encounter_id, |engine| {
+        let document = engine.document().clone();
+        let result = action(engine)?;
 
-    if move_index != target_index {
-        let move_creature = creatures.remove(move_index);
-        creatures.insert(target_index, move_creature);
+        Ok((result, (*engine.document() != document).then(|| engine.document().clone())))
+    })?;
+
+    if let Some(document) = document {
+        update_window_title(window, &document);
+        notify_encounters_changed(window);
     }
 
-    Ok(())
+    Ok(result)
 }
 
 #[tauri::command]
-fn finalize_initiative_order(state: State<AppState>) -> Result<(), BackendError> {
-    let mut creatures_guard = log_lock_error(state.creatures.lock(), "Unable to lock creatures state").to_backend_result()?;
-    let mut conflicts_guard = log_lock_error(state.conflicts.lock(), "Unable to lock conflicts state").to_backend_result()?;
-    let mut encounter_guard = log_lock_error(state.encounter.lock(), "Unable to lock encounter state").to_backend_result()?;
-    if let Some(conflicts) = &mut *conflicts_guard {
-        for group in conflicts {
-            group.finalize(&mut creatures_guard);
-        }
+fn list_encounters(state: State<AppState>) -> Result<EncounterTabs, BackendError> {
+    let data_guard = state.lock();
+    let tabs = data_guard.sessions.iter()
+        .map(|(id, engine)| engine.tab(id))
+        .collect();
+
+    Ok(EncounterTabs { tabs, current: data_guard.sessions.current() })
+}
+
+#[tauri::command]
+fn create_encounter(state: State<AppState>, window: Window) -> Result<Uuid, BackendError> {
+    let mut data_guard = state.lock();
+    let id = data_guard.sessions.add(EncounterEngine::default());
+    data_guard.sessions.set_current(id);
+    update_window_title(&window, &DocumentState::default());
+
+    log::info!("Opened a new encounter");
+
+    Ok(id)
+}
+
+#[tauri::command]
+fn duplicate_encounter(state: State<AppState>, window: Window, encounter_id: Uuid) -> Result<Uuid, BackendError> {
+    let mut data_guard = state.lock();
+    let copy = data_guard.engine(encounter_id).map_err(|e| log(e, Level::Error))?.duplicate();
+    let document = copy.document().clone();
+
+    log::info!("Duplicated encounter as {}", copy.name());
+    let id = data_guard.sessions.add(copy);
+    data_guard.sessions.set_current(id);
+    update_window_title(&window, &document);
+
+    Ok(id)
+}
+
+#[tauri::command]
+fn rename_encounter(state: State<AppState>, encounter_id: Uuid, name: String) -> Result<(), BackendError> {
+    with_engine(&state, encounter_id, |engine| {
+        engine.rename(&name);
+        Ok(())
+    })
+}
+
+/// Closes an encounter's tab, throwing away any unsaved changes. The frontend asks about those first.
+#[tauri::command]
+fn close_encounter(state: State<AppState>, window: Window, encounter_id: Uuid) -> Result<(), BackendError> {
+    let mut data_guard = state.lock();
+    data_guard.sessions.remove(encounter_id)
+        .ok_or_else(|| log(BackendError::logic_error("Cannot close the only open encounter"), Level::Warn))?;
+
+    if let Some(engine) = data_guard.sessions.get(data_guard.sessions.current()) {
+        update_window_title(&window, engine.document());
     }
 
-    *encounter_guard = Some(creatures_guard.finalize());
+    log::info!("Closed encounter {}", encounter_id);
 
     Ok(())
 }
 
 #[tauri::command]
-fn get_active_encounter_creatures(state: State<AppState>) -> Result<Vec<Creature>, BackendError> {
-    let encounter_guard = log_lock_error(state.encounter.lock(), "Unable to lock encounter state").to_backend_result()?;
+fn switch_encounter(state: State<AppState>, window: Window, encounter_id: Uuid) -> Result<(), BackendError> {
+    let mut data_guard = state.lock();
+    let document = data_guard.engine(encounter_id).map_err(|e| log(e, Level::Error))?.document().clone();
+    data_guard.sessions.set_current(encounter_id);
+    update_window_title(&window, &document);
 
-    match &*encounter_guard {
-        Some(encounter) => Ok(encounter.cloned()),
-        None => Err(log(BackendError::logic_error("Cannot get encounter because the initiative order has not been finalized"), Level::Error)) 
+    Ok(())
+}
+
+fn update_window_title(window: &Window, document: &DocumentState) {
+    if let Err(err) = window.set_title(&document.title(APP_NAME)) {
+        log::warn!("Unable to set window title: {}", err);
+    }
+}
+
+fn notify_encounters_changed(window: &Window) {
+    if let Err(err) = window.emit("encounters-changed", ()) {
+        log::warn!("Unable to emit encounters changed: {}", err);
     }
 }
 
 #[tauri::command]
-fn add_creatures_to_active_encounter(state: State<AppState>, creatures: String) -> Result<(), BackendError> {
-    let mut creatures_guard = log_lock_error(state.creatures.lock(), "Unable to lock creatures state").to_backend_result()?;
-    let mut encounter_guard = log_lock_error(state.encounter.lock(), "Unable to lock encounter state").to_backend_result()?;
+fn get_initiative_conflicts(state: State<AppState>, encounter_id: Uuid, set_conflicts: bool) -> Result<Vec<ConflictGroup>, BackendError> {
+    with_engine(&state, encounter_id, |engine| engine.initiative_conflicts(set_conflicts))
+}
 
-    let encounter = match &mut *encounter_guard {
-        Some(encounter) => encounter,
-        None => return Err(log(BackendError::logic_error("Cannot get encounter because the initiative order has not been finalized"), Level::Error))
-    };
-    
-    for name in creatures.lines() {
-        let trimmed = name.trim();
-        if !trimmed.is_empty() {
-            let mut creature = Creature::from(name);
-            creature.set_selected(true);
-            creatures_guard.push_and_sort(creature.clone());
-            encounter.push(creature);
+#[tauri::command]
+fn move_initiative_conflict(state: State<AppState>, encounter_id: Uuid, group_index: usize, move_index: usize, target_index: usize) -> Result<(), BackendError> {
+    with_engine(&state, encounter_id, |engine| engine.move_initiative_conflict(group_index, move_index, target_index))
+}
+
+#[tauri::command]
+fn finalize_initiative_order(state: State<AppState>, encounter_id: Uuid) -> Result<(), BackendError> {
+    state.transaction(|data| {
+        let engine = data.engine_mut(encounter_id)?;
+        engine.finalize_initiative_order();
+
+        if let Some(path) = engine.document().path().map(|p| p.to_path_buf()) {
+            set_campaign_status_for_file(data, &path, EncounterStatus::InProgress)?;
         }
-    }
 
-    creatures_guard.sort();
+        Ok(())
+    }).map_err(|e| log(e, Level::Error))
+}
 
-    Ok(())
+#[tauri::command]
+fn get_active_encounter_creatures(state: State<AppState>, encounter_id: Uuid) -> Result<Vec<Creature>, BackendError> {
+    with_engine(&state, encounter_id, |engine| engine.active_encounter_creatures())
 }
 
 #[tauri::command]
-fn change_active_encounter_order(state: State<AppState>, move_index: usize, target_index: usize) -> Result<(), BackendError> {
-    let mut encounter_guard = log_lock_error(state.encounter.lock(), "Unable to lock encounter state").to_backend_result()?;
-    let encounter = match &mut *encounter_guard {
-        Some(encounter) => encounter,
-        None => return Err(log(BackendError::logic_error("Cannot get encounter because the initiative order has not been finalized"), Level::Error))
-    };
+fn get_active_encounter(state: State<AppState>, encounter_id: Uuid) -> Result<EncounterView, BackendError> {
+    with_engine(&state, encounter_id, |engine| engine.encounter_view())
+}
+
+#[tauri::command]
+fn next_turn(state: State<AppState>, encounter_id: Uuid) -> Result<(), BackendError> {
+    with_engine(&state, encounter_id, |engine| engine.next_turn())
+}
+
+#[tauri::command]
+fn previous_turn(state: State<AppState>, encounter_id: Uuid) -> Result<(), BackendError> {
+    with_engine(&state, encounter_id, |engine| engine.previous_turn())
+}
+
+#[tauri::command]
+fn pass_turn_to(state: State<AppState>, encounter_id: Uuid, id: Uuid) -> Result<(), BackendError> {
+    with_engine(&state, encounter_id, |engine| engine.pass_turn_to(id))
+}
+
+#[tauri::command]
+fn set_round_initiatives(state: State<AppState>, encounter_id: Uuid, initiatives: Vec<(Uuid, isize)>) -> Result<(), BackendError> {
+    with_engine(&state, encounter_id, |engine| engine.set_round_initiatives(&initiatives))
+}
+
+#[tauri::command]
+fn deal_round_cards(state: State<AppState>, encounter_id: Uuid, window: Window) -> Result<(), BackendError> {
+    update_engine(&state, encounter_id, &window, |engine| engine.deal_round_cards())
+}
+
+#[tauri::command]
+fn wait_active_creature(state: State<AppState>, encounter_id: Uuid, reason: WaitReason) -> Result<(), BackendError> {
+    with_engine(&state, encounter_id, |engine| engine.wait_active_creature(reason))
+}
+
+#[tauri::command]
+fn resume_creature(state: State<AppState>, encounter_id: Uuid, window: Window, id: Uuid, position: ResumePosition) -> Result<(), BackendError> {
+    update_engine(&state, encounter_id, &window, |engine| engine.resume_creature(id, position))
+}
 
-    if move_index >= encounter.len() {
-        return Err(log(BackendError::argument_error("move_index", format!("Creature index {} is out of bounds", move_index)), Level::Error));
+#[tauri::command]
+fn set_creature_hit_points(state: State<AppState>, encounter_id: Uuid, window: Window, id: Uuid, maximum: Option<usize>) -> Result<(), BackendError> {
+    update_engine(&state, encounter_id, &window, |engine| engine.set_creature_hit_points(id, maximum))
+}
+
+#[tauri::command]
+fn damage_creature(state: State<AppState>, encounter_id: Uuid, window: Window, id: Uuid, damage: Vec<Damage>) -> Result<usize, BackendError> {
+    update_engine(&state, encounter_id, &window, |engine| engine.damage_creature(id, &damage))
+}
+
+#[tauri::command]
+fn heal_creature(state: State<AppState>, encounter_id: Uuid, window: Window, id: Uuid, amount: usize) -> Result<(), BackendError> {
+    update_engine(&state, encounter_id, &window, |engine| engine.heal_creature(id, amount))
+}
+
+#[tauri::command]
+fn add_creatures_to_active_encounter(state: State<AppState>, encounter_id: Uuid, window: Window, creatures: String) -> Result<(), BackendError> {
+    update_engine(&state, encounter_id, &window, |engine| engine.add_creatures_to_active_encounter(&creatures))
+}
+
+#[tauri::command]
+fn change_active_encounter_order(state: State<AppState>, encounter_id: Uuid, move_index: usize, target_index: usize) -> Result<(), BackendError> {
+    with_engine(&state, encounter_id, |engine| engine.change_active_encounter_order(move_index, target_index))
+}
+
+#[tauri::command]
+fn remove_from_active_encounter(state: State<AppState>, encounter_id: Uuid, id: Uuid) -> Result<(), BackendError> {
+    with_engine(&state, encounter_id, |engine| engine.remove_from_active_encounter(id))
+}
+
+#[tauri::command]
+fn get_campaign(state: State<AppState>) -> Result<Option<CampaignWorkspace>, BackendError> {
+    Ok(state.lock().campaign.clone())
+}
+
+#[tauri::command]
+fn create_campaign(state: State<AppState>, directory: PathBuf) -> Result<CampaignWorkspace, BackendError> {
+    if campaign_files::is_campaign(&directory) {
+        return Err(log(BackendError::argument_error("directory", format!("'{}' already holds a campaign", directory.to_string_lossy())), Level::Warn));
     }
 
-    if target_index >= encounter.len() {
-        return Err(log(BackendError::argument_error("target_index", format!("Creature index {} is out of bounds", target_index)), Level::Error));
+    let name = directory.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or(String::from("Campaign"));
+    let campaign = Campaign::new(name);
+    campaign_files::save_campaign(&directory, &campaign).to_backend_result()?;
+
+    let workspace = CampaignWorkspace { directory, campaign };
+    state.lock().campaign = Some(workspace.clone());
+
+    log::info!("Created campaign {} in: '{}'", workspace.campaign.name(), workspace.directory.to_string_lossy());
+
+    Ok(workspace)
+}
+
+#[tauri::command]
+fn open_campaign(state: State<AppState>, directory: PathBuf) -> Result<CampaignWorkspace, BackendError> {
+    if !campaign_files::is_campaign(&directory) {
+        return Err(log(BackendError::argument_error("directory", format!("'{}' does not hold a campaign", directory.to_string_lossy())), Level::Warn));
     }
 
-    let move_creature = encounter.remove_by_index(move_index);
-    encounter.insert(target_index, move_creature);
+    let campaign = campaign_files::load_campaign(&directory).to_backend_result()?;
+    let workspace = CampaignWorkspace { directory, campaign };
+    state.lock().campaign = Some(workspace.clone());
+
+    log::info!("Opened campaign {} from: '{}'", workspace.campaign.name(), workspace.directory.to_string_lossy());
+
+    Ok(workspace)
+}
+
+#[tauri::command]
+fn close_campaign(state: State<AppState>) -> Result<(), BackendError> {
+    state.lock().campaign = None;
+
+    log::info!("Closed campaign");
+
+    Ok(())
+}
+
+/// Applies a change to the open campaign and saves it, keeping the change only if it is saved.
+fn update_campaign<T>(state: &AppState, update: impl FnOnce(&mut CampaignWorkspace) -> Result<T, BackendError>) -> Result<T, BackendError> {
+    state.transaction(|data| {
+        let workspace = data.campaign.as_mut()
+            .ok_or_else(|| log(BackendError::logic_error("Cannot change the campaign because none is open"), Level::Error))?;
+
+        let result = update(workspace)?;
+        campaign_files::save_campaign(&workspace.directory, &workspace.campaign).to_backend_result()?;
+
+        Ok(result)
+    })
+}
+
+#[tauri::command]
+fn add_campaign_encounter(state: State<AppState>, name: String) -> Result<CampaignEncounter, BackendError> {
+    let encounter = update_campaign(&state, |workspace| {
+        let encounter = workspace.campaign.add_encounter(name.trim()).clone();
+        encounter_file::save_encounter(&workspace.encounter_path(&encounter), &EncounterData::new(&CreatureContainer::default(), InitiativeMode::default()))?;
+
+        Ok(encounter)
+    })?;
+
+    log::info!("Prepared campaign encounter {}", encounter.name());
+
+    Ok(encounter)
+}
+
+/// Takes an encounter off the campaign's list. Its file is left where it is.
+#[tauri::command]
+fn remove_campaign_encounter(state: State<AppState>, id: Uuid) -> Result<(), BackendError> {
+    let encounter = update_campaign(&state, |workspace| {
+        workspace.campaign.remove_encounter(id)
+            .ok_or_else(|| log(BackendError::argument_error("id", format!("No encounter with id '{}' is in the campaign", id)), Level::Error))
+    })?;
+
+    log::info!("Removed campaign encounter {}", encounter.name());
 
     Ok(())
 }
 
 #[tauri::command]
-fn remove_from_active_encounter(state: State<AppState>, id: Uuid) -> Result<(), BackendError> {
-    let mut creatures_guard = log_lock_error(state.creatures.lock(), "Unable to lock creatures state").to_backend_result()?;
-    let mut encounter_guard = log_lock_error(state.encounter.lock(), "Unable to lock encounter state").to_backend_result()?;
-    let encounter = match &mut *encounter_guard {
-        Some(encounter) => encounter,
-        None => return Err(log(BackendError::logic_error("Cannot get encounter because the initiative order has not been finalized"), Level::Error))
+fn set_campaign_encounter_status(state: State<AppState>, id: Uuid, status: EncounterStatus) -> Result<(), BackendError> {
+    update_campaign(&state, |workspace| workspace.campaign.set_status(id, status, get_unix_timestamp()))?;
+
+    log::info!("Set campaign encounter {} to {}", id, status.to_string().to_lowercase());
+
+    Ok(())
+}
+
+fn set_campaign_status_for_file(data: &mut AppData, path: &Path, status: EncounterStatus) -> Result<(), BackendError> {
+    let workspace = match &mut data.campaign {
+        Some(workspace) => workspace,
+        None => return Ok(())
     };
 
-    encounter.remove(id);
-    creatures_guard.get_mut(id)
-        .ok_or(log(BackendError::argument_error("id", format!("No creature found with id '{}'", id)), Level::Error))?
-        .set_selected(false);
+    let id = match path.strip_prefix(&workspace.directory).ok().and_then(|file| workspace.campaign.encounter_for_file(file)) {
+        Some(encounter) if encounter.status() != status => encounter.id(),
+        _ => return Ok(())
+    };
+
+    workspace.campaign.set_status(id, status, get_unix_timestamp())?;
+    campaign_files::save_campaign(&workspace.directory, &workspace.campaign).to_backend_result()?;
+
+    log::info!("Set campaign encounter {} to {}", id, status.to_string().to_lowercase());
+
+    Ok(())
+}
+
+#[tauri::command]
+fn add_selected_to_campaign_roster(state: State<AppState>, encounter_id: Uuid, roster: Roster) -> Result<(), BackendError> {
+    let creatures = state.lock().engine(encounter_id)
+        .map_err(|e| log(e, Level::Error))?
+        .creatures()
+        .iter()
+        .filter(|creature| creature.selected() && !creature.is_slot())
+        .map(RosterCreature::from)
+        .collect::<Vec<_>>();
+
+    let count = creatures.len();
+    update_campaign(&state, |workspace| {
+        for creature in creatures {
+            workspace.campaign.add_to_roster(roster, creature);
+        }
+
+        Ok(())
+    })?;
+
+    log::info!("Added {} creatures to the campaign {}", count, roster.to_string().to_lowercase());
 
     Ok(())
-} 
+}
 
-fn get_default_state() -> AppState {
-    AppState::default()
+#[tauri::command]
+fn remove_from_campaign_roster(state: State<AppState>, roster: Roster, id: Uuid) -> Result<(), BackendError> {
+    let creature = update_campaign(&state, |workspace| {
+        workspace.campaign.remove_from_roster(roster, id)
+            .ok_or_else(|| log(BackendError::argument_error("id", format!("No creature with id '{}' is in the campaign {}", id, roster.to_string().to_lowercase())), Level::Error))
+    })?;
+
+    log::info!("Removed {} from the campaign {}", creature.name(), roster.to_string().to_lowercase());
+
+    Ok(())
+}
+
+#[tauri::command]
+fn add_campaign_roster_to_encounter(state: State<AppState>, encounter_id: Uuid, window: Window, roster: Roster, ids: Vec<Uuid>) -> Result<(), BackendError> {
+    let creatures = state.lock().campaign
+        .as_ref()
+        .map(|workspace| workspace.campaign.roster(roster).iter()
+            .filter(|creature| ids.contains(&creature.id()))
+            .map(RosterCreature::to_creature)
+            .collect::<Vec<_>>())
+        .ok_or_else(|| log(BackendError::logic_error("Cannot add creatures from the campaign because none is open"), Level::Error))?;
+
+    let count = creatures.len();
+    update_engine(&state, encounter_id, &window, |engine| {
+        engine.import_creatures(creatures.into_iter()
+            .map(|mut creature| {
+                creature.set_selected(true);
+                creature
+            })
+            .collect());
+        Ok(())
+    })?;
+
+    log::info!("Added {} creatures from the campaign {}", count, roster.to_string().to_lowercase());
+
+    Ok(())
+}
+
+fn get_unix_timestamp() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+fn get_default_state(settings: Settings, recent_files: RecentFiles) -> AppState {
+    AppState {
+        data: Mutex::new(AppData {
+            settings,
+            recent_files,
+            ..AppData::default()
+        })
+    }
 
     // Mutex::new(AppState {
     //     creatures: CreatureContainer::from(vec! [
@@ -292,33 +733,103 @@ fn get_default_state() -> AppState {
 }
 
 fn main() -> Result<(), SetLoggerError> {
-    configure_logger()?;
-    cleanup_logs();
+    let (settings, settings_error) = match settings::load_settings() {
+        Ok(settings) => (settings, None),
+        Err(err) => (Settings::default(), Some(err))
+    };
+
+    let logger = configure_logger(to_level_filter(settings.console_log_level()))?;
+    if let Some(err) = settings_error {
+        log::warn!("Unable to load settings, using defaults instead: {}", err);
+    }
+
+    cleanup_logs(settings.max_log_count());
+
+    let recent_files = recent_files::load_recent_files().unwrap_or_else(|err| {
+        log::warn!("Unable to load recent files: {}", err);
+        RecentFiles::default()
+    });
 
     log::info!("Starting app");
 
+    let window_size = LogicalSize::new(settings.window_width() as f64, settings.window_height() as f64);
+
     tauri::Builder::default()
-        .manage(get_default_state())
+        .manage(get_default_state(settings, recent_files))
+        .manage(logger)
         .invoke_handler(tauri::generate_handler![
+            get_settings,
+            update_settings,
+            get_recent_files,
+            get_last_directory,
             get_creatures,
             add_creatures,
             remove_creature,
             set_creature_selected,
             set_creature_initiative,
+            update_creature,
+            duplicate_creature,
+            set_creature_hidden,
+            set_creature_side,
+            add_initiative_slot,
+            set_creature_card_edges,
+            deal_initiative_cards,
+            get_initiative_mode,
+            set_initiative_mode,
             set_all_creatures_selected,
             reset_all_initiatives,
             get_initiative_conflicts,
             move_initiative_conflict,
             finalize_initiative_order,
             get_active_encounter_creatures,
+            get_active_encounter,
+            next_turn,
+            previous_turn,
+            pass_turn_to,
+            set_round_initiatives,
+            deal_round_cards,
+            wait_active_creature,
+            resume_creature,
+            set_creature_hit_points,
+            damage_creature,
+            heal_creature,
             add_creatures_to_active_encounter,
             change_active_encounter_order,
             remove_from_active_encounter,
             save_encounter,
+            save_current_encounter,
             load_encounter,
-            new_encounter
+            new_encounter,
+            get_document_state,
+            close_window,
+            list_encounters,
+            create_encounter,
+            duplicate_encounter,
+            rename_encounter,
+            close_encounter,
+            switch_encounter,
+            get_campaign,
+            create_campaign,
+            open_campaign,
+            close_campaign,
+            add_campaign_encounter,
+            remove_campaign_encounter,
+            set_campaign_encounter_status,
+            add_selected_to_campaign_roster,
+            remove_from_campaign_roster,
+            add_campaign_roster_to_encounter,
+            export_creatures_csv,
+            get_csv_headers,
+            import_creatures_csv
         ])
-        .setup(|app| {
+        .setup(move |app| {
+            let window = app.get_window("main").unwrap();
+            if let Err(err) = window.set_size(window_size) {
+                log::warn!("Unable to set window size: {}", err);
+            }
+
+            update_window_title(&window, &DocumentState::default());
+
             #[cfg(debug_assertions)] // only include this code on debug builds
             {
                 let window = app.get_window("main").unwrap();
@@ -327,6 +838,22 @@ fn main() -> Result<(), SetLoggerError> {
             }
             Ok(())
         })
+        .on_window_event(|event| {
+            if let WindowEvent::CloseRequested { api, .. } = event.event() {
+                let state = event.window().state::<AppState>();
+                let dirty_encounter = state.lock().sessions.iter()
+                    .find(|(_, engine)| engine.document().dirty())
+                    .map(|(id, _)| id);
+
+                // Let the frontend ask whether to save or discard the changes before closing, one encounter at a time.
+                if let Some(id) = dirty_encounter {
+                    api.prevent_close();
+                    if let Err(err) = event.window().emit("close-requested", id) {
+                        log::warn!("Unable to emit close request: {}", err);
+                    }
+                }
+            }
+        })
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 
@@ -337,7 +864,7 @@ fn get_current_timestamp() -> String {
     Local::now().format("%d-%m-%y %H-%M-%S").to_string()
 }
 
-fn cleanup_logs() {
+fn cleanup_logs(max_log_count: usize) {
     match fs::read_dir(get_backend_log_dir()) {
         Err(err) => log::warn!("Unable to read log directory: {}", err),
         Ok(files) => {
@@ -351,11 +878,11 @@ fn cleanup_logs() {
                 })
                 .collect::<Vec<_>>();
 
-            if files.len() > MAX_LOG_COUNT {
+            if files.len() > max_log_count {
                 files.sort_by(cmp_files);
             }
 
-            while files.len() > MAX_LOG_COUNT {
+            while files.len() > max_log_count {
                 let file = files.pop().unwrap();
                 match fs::remove_file(file.path()) {
                     Ok(_) => log::info!("Removed log file: {}", file.path().to_string_lossy()),
@@ -402,11 +929,16 @@ fn cmp_files(a: &DirEntry, b: &DirEntry) -> Ordering {
     b_accessed.cmp(&a_accessed)
 }
 
-fn configure_logger() -> Result<(), SetLoggerError> {
-    let level = log::LevelFilter::Info;
+fn configure_logger(level: LevelFilter) -> Result<LoggerState, SetLoggerError> {
     let path = get_backend_log_dir()
         .join(format!("{}.log", get_current_timestamp()));
 
+    let handle = log4rs::init_config(build_logger_config(level, &path))?;
+
+    Ok(LoggerState { handle, path })
+}
+
+fn build_logger_config(level: LevelFilter, path: &Path) -> Config {
     let stderr = ConsoleAppender::builder().target(Target::Stderr).build();
 
     let logfile = FileAppender::builder()
@@ -415,7 +947,7 @@ fn configure_logger() -> Result<(), SetLoggerError> {
         .build(path)
         .unwrap();
 
-    let config = Config::builder()
+    Config::builder()
         .appender(Appender::builder().build("logfile", Box::new(logfile)))
         .appender(
             Appender::builder()
@@ -428,11 +960,18 @@ fn configure_logger() -> Result<(), SetLoggerError> {
                 .appender("stderr")
                 .build(LevelFilter::Trace),
         )
-        .unwrap();
-    
-    let _handle = log4rs::init_config(config)?;
+        .unwrap()
+}
 
-    Ok(())
+fn to_level_filter(level: LogLevel) -> LevelFilter {
+    match level {
+        LogLevel::Off => LevelFilter::Off,
+        LogLevel::Error => LevelFilter::Error,
+        LogLevel::Warn => LevelFilter::Warn,
+        LogLevel::Info => LevelFilter::Info,
+        LogLevel::Debug => LevelFilter::Debug,
+        LogLevel::Trace => LevelFilter::Trace,
+    }
 }
 
 fn get_backend_log_dir() -> PathBuf {
@@ -0,0 +1,42 @@
+use std::{fs, path::{Path, PathBuf}, time::UNIX_EPOCH};
+
+use common_data_lib::recent_files::{RecentFiles, RecentFile};
+
+use crate::error::Error;
+
+pub fn get_recent_files_path() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or(PathBuf::from("/home"))
+        .join("InitiativeApp")
+        .join("recent_files.toml")
+}
+
+pub fn load_recent_files() -> Result<RecentFiles, Error> {
+    let path = get_recent_files_path();
+    if !path.exists() {
+        return Ok(RecentFiles::default());
+    }
+
+    let content = fs::read_to_string(&path)?;
+    Ok(toml::from_str(&content)?)
+}
+
+pub fn save_recent_files(recent_files: &RecentFiles) -> Result<(), Error> {
+    let path = get_recent_files_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::write(&path, toml::to_string_pretty(recent_files)?)?;
+
+    Ok(())
+}
+
+pub fn get_last_modified(path: &Path) -> Option<u64> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    Some(modified.duration_since(UNIX_EPOCH).ok()?.as_secs())
+}
+
+pub fn get_recent_file(path: &Path) -> RecentFile {
+    RecentFile::new(path, get_last_modified(path))
+}
@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+
+use common_data_lib::engine::EncounterEngine;
+use uuid::Uuid;
+
+/// The open encounters, kept in the order their tabs are shown. Each encounter has its own creatures, turn order
+/// and file, so several can be worked on side by side.
+#[derive(Debug, Clone)]
+pub struct Sessions {
+    sessions: HashMap<Uuid, EncounterEngine>,
+    order: Vec<Uuid>,
+    current: Uuid
+}
+
+impl Default for Sessions {
+    fn default() -> Self {
+        let id = Uuid::new_v4();
+
+        Self {
+            sessions: HashMap::from([(id, EncounterEngine::default())]),
+            order: vec![id],
+            current: id
+        }
+    }
+}
+
+impl Sessions {
+    pub fn get(&self, id: Uuid) -> Option<&EncounterEngine> {
+        self.sessions.get(&id)
+    }
+
+    pub fn get_mut(&mut self, id: Uuid) -> Option<&mut EncounterEngine> {
+        self.sessions.get_mut(&id)
+    }
+
+    pub fn current(&self) -> Uuid {
+        self.current
+    }
+
+    /// Shows the encounter with the given ID. Returns false if there is no such encounter.
+    pub fn set_current(&mut self, id: Uuid) -> bool {
+        if self.sessions.contains_key(&id) {
+            self.current = id;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Opens an encounter in a new tab after the current one and returns its ID.
+    pub fn add(&mut self, engine: EncounterEngine) -> Uuid {
+        let id = Uuid::new_v4();
+        let index = self.order.iter()
+            .position(|&i| i == self.current)
+            .map(|i| i + 1)
+            .unwrap_or(self.order.len());
+
+        self.sessions.insert(id, engine);
+        self.order.insert(index, id);
+
+        id
+    }
+
+    /// Closes the session with the given ID, showing its neighbour if it was current. The last session can't be
+    /// closed, so there is always an encounter to show.
+    pub fn remove(&mut self, id: Uuid) -> Option<EncounterEngine> {
+        if self.order.len() <= 1 {
+            return None;
+        }
+
+        let index = self.order.iter().position(|&i| i == id)?;
+        self.order.remove(index);
+
+        if self.current == id {
+            self.current = self.order[index.min(self.order.len() - 1)];
+        }
+
+        self.sessions.remove(&id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Uuid, &EncounterEngine)> {
+        self.order.iter().filter_map(|id| self.sessions.get(id).map(|engine| (*id, engine)))
+    }
+
+    /// Every open encounter, in no particular order.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Uuid, &mut EncounterEngine)> {
+        self.sessions.iter_mut().map(|(id, engine)| (*id, engine))
+    }
+}
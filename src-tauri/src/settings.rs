@@ -0,0 +1,34 @@
+use std::{fs, path::PathBuf};
+
+use common_data_lib::settings::Settings;
+
+use crate::error::Error;
+
+pub fn get_settings_path() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or(PathBuf::from("/home"))
+        .join("InitiativeApp")
+        .join("settings.toml")
+}
+
+/// Loads the settings file, falling back to the defaults if it has not been created yet.
+pub fn load_settings() -> Result<Settings, Error> {
+    let path = get_settings_path();
+    if !path.exists() {
+        return Ok(Settings::default());
+    }
+
+    let content = fs::read_to_string(&path)?;
+    Ok(toml::from_str(&content)?)
+}
+
+pub fn save_settings(settings: &Settings) -> Result<(), Error> {
+    let path = get_settings_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::write(&path, toml::to_string_pretty(settings)?)?;
+
+    Ok(())
+}
@@ -1,39 +1,122 @@
+use uuid::Uuid;
 use yew::prelude::*;
 
-use crate::pages::{welcome_page::WelcomePage, conflicts_page::ConflictsPage, encounter_page::EncounterPage};
+use crate::{pages::{welcome_page::WelcomePage, conflicts_page::ConflictsPage, encounter_page::EncounterPage, settings_page::SettingsPage}, components::{encounter_tabs::EncounterTabStrip, unsaved_changes_modal::UnsavedChangesModal}, glue::{listen_for_close_requested, close_window_with_callback}, hooks::prelude::*};
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum AppPage {
     WelcomePage,
     ConflictsPage,
     EncounterPage,
+    SettingsPage,
 }
 
 #[function_component(App)]
 pub fn app() -> Html {
     let current_page = use_state_eq(|| AppPage::WelcomePage);
+    let tabs = use_encounter_tabs();
+    let close_request = use_state_eq(|| None::<Uuid>);
 
-    match *current_page {
-        AppPage::WelcomePage => render_welcome_page(current_page.clone()),
-        AppPage::ConflictsPage => render_conflicts_page(current_page.clone()),
-        AppPage::EncounterPage => render_encounter_page(current_page.clone())
+    use_effect_with_deps({
+        let close_request = close_request.clone();
+        move |_| {
+            listen_for_close_requested(move |id| {
+                close_request.set(Some(id));
+            });
+        }
+    }, ());
+
+    // The backend names an encounter with unsaved changes, which is shown so that saving it saves the right one.
+    use_effect_with_deps({
+        let tabs = tabs.clone();
+        move |close_request: &Option<Uuid>| {
+            if let Some(id) = *close_request {
+                if tabs.current() != Some(id) {
+                    tabs.switch(id);
+                }
+            }
+        }
+    }, *close_request);
+
+    // Each encounter opens on the page that suits it, which is the turn order once it has been finalized.
+    use_effect_with_deps({
+        let current_page = current_page.clone();
+        let in_progress = tabs.as_ref()
+            .and_then(|tabs| tabs.tabs.iter().find(|tab| tab.id() == tabs.current))
+            .map(|tab| tab.in_progress())
+            .unwrap_or(false);
+
+        move |_| {
+            current_page.set(if in_progress { AppPage::EncounterPage } else { AppPage::WelcomePage });
+        }
+    }, tabs.current());
+
+    let close = {
+        let close_request = close_request.clone();
+        Callback::from(move |_| {
+            close_request.set(None);
+            close_window_with_callback(false, |_| {});
+        })
+    };
+
+    let discard_and_close = Callback::from(|_| {
+        close_window_with_callback(true, |_| {});
+    });
+
+    let cancel_close = {
+        let close_request = close_request.clone();
+        Callback::from(move |_| {
+            close_request.set(None);
+        })
+    };
+
+    // Nothing can be shown until the backend says which encounter is open.
+    let encounter_id = match tabs.current() {
+        Some(id) => id,
+        None => return html! {}
+    };
+
+    let page_html = match *current_page {
+        AppPage::WelcomePage => render_welcome_page(current_page.clone(), encounter_id),
+        AppPage::ConflictsPage => render_conflicts_page(current_page.clone(), encounter_id),
+        AppPage::EncounterPage => render_encounter_page(current_page.clone(), encounter_id),
+        AppPage::SettingsPage => render_settings_page(current_page.clone())
+    };
+
+    html! {
+        <>
+            if *current_page != AppPage::SettingsPage {
+                <EncounterTabStrip tabs={tabs.clone()} />
+            }
+            {page_html}
+            if close_request.is_some() {
+                <UnsavedChangesModal on_saved={close} on_discard={discard_and_close} on_cancel={cancel_close} />
+            }
+        </>
     }
 }
 
-fn render_welcome_page(current_page: UseStateHandle<AppPage>) -> Html {
+// Pages are keyed by encounter so that switching tabs loads the new encounter's state from scratch.
+fn render_welcome_page(current_page: UseStateHandle<AppPage>, encounter_id: Uuid) -> Html {
     html! {
-        <WelcomePage current_page={current_page} />
+        <WelcomePage key={encounter_id.to_string()} current_page={current_page} />
     }
 }
 
-fn render_conflicts_page(current_page: UseStateHandle<AppPage>) -> Html {
+fn render_conflicts_page(current_page: UseStateHandle<AppPage>, encounter_id: Uuid) -> Html {
     html! {
-        <ConflictsPage current_page={current_page} />
+        <ConflictsPage key={encounter_id.to_string()} current_page={current_page} />
     }
 }
 
-fn render_encounter_page(current_page: UseStateHandle<AppPage>) -> Html {
+fn render_encounter_page(current_page: UseStateHandle<AppPage>, encounter_id: Uuid) -> Html {
     html! {
-        <EncounterPage current_page={current_page} />
+        <EncounterPage key={encounter_id.to_string()} current_page={current_page} />
     }
-}
\ No newline at end of file
+}
+
+fn render_settings_page(current_page: UseStateHandle<AppPage>) -> Html {
+    html! {
+        <SettingsPage current_page={current_page} />
+    }
+}
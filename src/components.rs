@@ -1,3 +1,12 @@
 pub mod accordion;
+pub mod creature_notes;
+pub mod damage_types;
+pub mod drag_handle;
+pub mod encounter_tabs;
 pub mod menu;
-pub mod modal;
\ No newline at end of file
+pub mod modal;
+pub mod name_input;
+pub mod notification;
+pub mod shortcut_cheat_sheet;
+pub mod stat_block;
+pub mod unsaved_changes_modal;
\ No newline at end of file
@@ -0,0 +1,137 @@
+use common_data_lib::creatures::CreaturePatch;
+use pulldown_cmark::{html, Event as MarkdownEvent, Options, Parser, Tag};
+use uuid::Uuid;
+use web_sys::HtmlTextAreaElement;
+use yew::prelude::*;
+
+use crate::glue::update_creature_with_callback;
+
+/// Renders Markdown notes as formatted text. Any HTML written in the notes is shown as text rather than run.
+pub fn render_markdown(text: &str) -> Html {
+    Html::from_html_unchecked(AttrValue::from(markdown_to_html(text)))
+}
+
+/// Notes come from shared encounter files, so links and images are only kept when they point at a web or mail
+/// address. Anything else, such as a `javascript:` link, is left as its plain text.
+fn markdown_to_html(text: &str) -> String {
+    let parser = Parser::new_ext(text, Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TABLES | Options::ENABLE_TASKLISTS)
+        .filter_map(|event| match event {
+            MarkdownEvent::Html(html) => Some(MarkdownEvent::Text(html)),
+            MarkdownEvent::Start(Tag::Link(_, url, _)) | MarkdownEvent::End(Tag::Link(_, url, _))
+            | MarkdownEvent::Start(Tag::Image(_, url, _)) | MarkdownEvent::End(Tag::Image(_, url, _))
+                if !is_safe_url(&url) => None,
+            event => Some(event)
+        });
+
+    let mut rendered = String::new();
+    html::push_html(&mut rendered, parser);
+    rendered
+}
+
+fn is_safe_url(url: &str) -> bool {
+    let url = url.trim_start().to_ascii_lowercase();
+    ["http://", "https://", "mailto:"].iter().any(|scheme| url.starts_with(scheme))
+}
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct CreatureNotesProps {
+    pub id: Uuid,
+    pub name: String,
+    pub notes: String,
+    pub on_saved: Callback<()>
+}
+
+/// A creature's notes shown as formatted text, with a button to edit the Markdown they're written in.
+#[function_component(CreatureNotes)]
+pub fn creature_notes(props: &CreatureNotesProps) -> Html {
+    let CreatureNotesProps { id, name, notes, on_saved } = props.clone();
+    let draft = use_state_eq(|| None::<String>);
+
+    let edit = {
+        let draft = draft.clone();
+        let notes = notes.clone();
+        Callback::from(move |_: MouseEvent| {
+            draft.set(Some(notes.clone()));
+        })
+    };
+
+    let update_draft = {
+        let draft = draft.clone();
+        Callback::from(move |e: Event| {
+            let target: HtmlTextAreaElement = e.target_unchecked_into();
+            draft.set(Some(target.value()));
+        })
+    };
+
+    let save = {
+        let draft = draft.clone();
+        Callback::from(move |_: MouseEvent| {
+            let draft = draft.clone();
+            let on_saved = on_saved.clone();
+            let patch = CreaturePatch { notes: (*draft).clone(), ..CreaturePatch::default() };
+            update_creature_with_callback(id, patch, move |result| {
+                match result {
+                    Ok(_) => {
+                        draft.set(None);
+                        on_saved.emit(());
+                    },
+                    Err(err) => log::error!("Could not save notes: {}", err)
+                }
+            });
+        })
+    };
+
+    let cancel = {
+        let draft = draft.clone();
+        Callback::from(move |_: MouseEvent| {
+            draft.set(None);
+        })
+    };
+
+    let body = match &*draft {
+        Some(text) => html! {
+            <>
+                <textarea class="notes-editor" value={text.clone()} onchange={update_draft} aria-label={format!("Notes for {}", name)} />
+                <div class="flex-row button-group">
+                    <button class="flex-grow-1" onclick={save}>{"Save"}</button>
+                    <button class="flex-grow-1" onclick={cancel}>{"Cancel"}</button>
+                </div>
+            </>
+        },
+        None if notes.trim().is_empty() => html! {
+            <p class="notes-empty">{"No notes yet."}</p>
+        },
+        None => html! {
+            <div class="notes-text">{render_markdown(&notes)}</div>
+        }
+    };
+
+    html! {
+        <section class="creature-notes flex-column">
+            <div class="flex-row notes-heading">
+                <h3 class="flex-grow-1">{name.clone()}</h3>
+                if draft.is_none() {
+                    <button class="blank" onclick={edit}>{"Edit notes"}</button>
+                }
+            </div>
+            {body}
+        </section>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown_links() {
+        assert_eq!(
+            markdown_to_html("[wiki](https://example.com) <b>bold</b>"),
+            "<p><a href=\"https://example.com\">wiki</a> &lt;b&gt;bold&lt;/b&gt;</p>\n"
+        );
+        assert_eq!(markdown_to_html("[x](javascript:alert(1))"), "<p>x</p>\n");
+        assert_eq!(markdown_to_html("[x]( JavaScript:alert(1))"), "<p>x</p>\n");
+        assert_eq!(markdown_to_html("![map](javascript:alert(1))"), "<p>map</p>\n");
+        assert_eq!(markdown_to_html("<javascript:alert(1)>"), "<p>javascript:alert(1)</p>\n");
+    }
+}
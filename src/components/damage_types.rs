@@ -0,0 +1,128 @@
+use common_data_lib::{creatures::CreaturePatch, damage::parse_damage_types, stat_block::StatBlock, BackendError};
+use uuid::Uuid;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::{components::stat_block::{join_damage_types, property}, glue::update_creature_with_callback, Error};
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct DamageTypesEditorProps {
+    pub id: Uuid,
+    pub name: String,
+    pub stat_block: StatBlock,
+    /// Whether to list the damage types here, for creatures whose stat block isn't shown.
+    pub show_types: bool,
+    pub on_saved: Callback<()>
+}
+
+/// The damage types a creature is vulnerable, resistant or immune to, with a button to change them. Damage dealt to
+/// the creature is adjusted for these.
+#[function_component(DamageTypesEditor)]
+pub fn damage_types_editor(props: &DamageTypesEditorProps) -> Html {
+    let DamageTypesEditorProps { id, name, stat_block, show_types, on_saved } = props.clone();
+    let editing = use_state_eq(|| false);
+    let error_message = use_state_eq(|| None::<String>);
+    let vulnerabilities_ref = use_node_ref();
+    let resistances_ref = use_node_ref();
+    let immunities_ref = use_node_ref();
+
+    let edit = {
+        let editing = editing.clone();
+        Callback::from(move |_: MouseEvent| {
+            editing.set(true);
+        })
+    };
+
+    let save = {
+        let editing = editing.clone();
+        let error_message = error_message.clone();
+        let refs = [vulnerabilities_ref.clone(), resistances_ref.clone(), immunities_ref.clone()];
+        Callback::from(move |_: MouseEvent| {
+            let parsed = refs.iter()
+                .map(|input_ref| input_ref.cast::<HtmlInputElement>().map(|input| input.value()).unwrap_or_default())
+                .map(|text| parse_damage_types(&text))
+                .collect::<Result<Vec<_>, _>>();
+
+            let mut lists = match parsed {
+                Ok(lists) => lists.into_iter(),
+                Err(BackendError::ArgumentError { message, .. }) => return error_message.set(Some(message)),
+                Err(error) => return error_message.set(Some(error.to_string()))
+            };
+
+            let patch = CreaturePatch {
+                damage_vulnerabilities: lists.next(),
+                damage_resistances: lists.next(),
+                damage_immunities: lists.next(),
+                ..CreaturePatch::default()
+            };
+
+            let editing = editing.clone();
+            let error_message = error_message.clone();
+            let on_saved = on_saved.clone();
+            update_creature_with_callback(id, patch, move |result| {
+                match result {
+                    Ok(_) => {
+                        error_message.set(None);
+                        editing.set(false);
+                        on_saved.emit(());
+                    },
+                    Err(Error::BackendError(BackendError::ArgumentError { message, .. })) => error_message.set(Some(message)),
+                    Err(error) => error_message.set(Some(error.to_string()))
+                }
+            });
+        })
+    };
+
+    let cancel = {
+        let editing = editing.clone();
+        let error_message = error_message.clone();
+        Callback::from(move |_: MouseEvent| {
+            error_message.set(None);
+            editing.set(false);
+        })
+    };
+
+    let field = |label: &str, input_ref: &NodeRef, value: &str| html! {
+        <label class="flex-column damage-types-field">
+            {label}
+            <input ref={input_ref.clone()} type="text" value={value.to_string()} placeholder="fire, cold" aria-describedby="damage-types-hint" />
+        </label>
+    };
+
+    let body = if *editing {
+        html! {
+            <>
+                {field("Vulnerabilities", &vulnerabilities_ref, &join_damage_types(&stat_block.damage_vulnerabilities))}
+                {field("Resistances", &resistances_ref, &join_damage_types(&stat_block.damage_resistances))}
+                {field("Immunities", &immunities_ref, &join_damage_types(&stat_block.damage_immunities))}
+                <p id="damage-types-hint" class="field-hint">{"Separate damage types with commas."}</p>
+                if let Some(message) = &*error_message {
+                    <p class="error-text">{message}</p>
+                }
+                <div class="flex-row button-group">
+                    <button class="flex-grow-1" onclick={save}>{"Save"}</button>
+                    <button class="flex-grow-1" onclick={cancel}>{"Cancel"}</button>
+                </div>
+            </>
+        }
+    } else if show_types {
+        html! {
+            <>
+                {property("Damage Vulnerabilities", &join_damage_types(&stat_block.damage_vulnerabilities))}
+                {property("Damage Resistances", &join_damage_types(&stat_block.damage_resistances))}
+                {property("Damage Immunities", &join_damage_types(&stat_block.damage_immunities))}
+            </>
+        }
+    } else {
+        html! {}
+    };
+
+    html! {
+        <section class="damage-types flex-column" aria-label={format!("Damage types for {}", name)}>
+            if !*editing {
+                <button class="blank" onclick={edit}>{"Edit damage types"}</button>
+            }
+            {body}
+        </section>
+    }
+}
@@ -0,0 +1,72 @@
+use yew::prelude::*;
+
+use crate::hooks::prelude::*;
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct DragHandleProps {
+    pub drag_state: UseDragAndDropHandle,
+    pub index: usize,
+    pub item_count: usize,
+    pub label: AttrValue
+}
+
+/// A grip that can be dragged by touch or focused and moved with the keyboard.
+#[function_component(DragHandle)]
+pub fn drag_handle(props: &DragHandleProps) -> Html {
+    let DragHandleProps { drag_state, index, item_count, label } = props.clone();
+    let DragHandleCallbacks {
+        on_pointer_down,
+        on_pointer_move,
+        on_pointer_up,
+        on_pointer_cancel,
+        on_key_down
+    } = drag_state.handle_callbacks(index, item_count);
+
+    let is_dragging = drag_state.is_dragging_index(index);
+
+    html! {
+        <span
+            class={classes!("drag-handle", is_dragging.then_some("dragging"))}
+            role="button"
+            tabindex="0"
+            aria-label={format!("Reorder {}", label)}
+            aria-pressed={is_dragging.to_string()}
+            onpointerdown={on_pointer_down}
+            onpointermove={on_pointer_move}
+            onpointerup={on_pointer_up}
+            onpointercancel={on_pointer_cancel}
+            onkeydown={on_key_down}>
+            {"\u{2807}"}
+        </span>
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct DragAnnouncerProps {
+    pub drag_state: UseDragAndDropHandle
+}
+
+/// Reads out keyboard reordering to screen readers.
+#[function_component(DragAnnouncer)]
+pub fn drag_announcer(props: &DragAnnouncerProps) -> Html {
+    html! {
+        <p class="visually-hidden" aria-live="assertive" role="status">{props.drag_state.announcement()}</p>
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct DragPreviewProps {
+    pub drag_state: UseDragAndDropHandle,
+    pub label: AttrValue
+}
+
+/// Follows a touch drag with the name of the item being moved. It has to be placed inside the drag's scroll container.
+#[function_component(DragPreview)]
+pub fn drag_preview(props: &DragPreviewProps) -> Html {
+    match props.drag_state.preview_position() {
+        Some((x, y)) => html! {
+            <div class="drag-preview" style={format!("left: {}px; top: {}px;", x, y)} aria-hidden="true">{props.label.clone()}</div>
+        },
+        None => html! {}
+    }
+}
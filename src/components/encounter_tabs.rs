@@ -0,0 +1,172 @@
+use common_data_lib::document::EncounterTab;
+use uuid::Uuid;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::{components::unsaved_changes_modal::UnsavedChangesModal, hooks::prelude::*};
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct EncounterTabStripProps {
+    pub tabs: UseEncounterTabsHandle
+}
+
+/// A tab for each open encounter. Double click a tab to rename it.
+#[function_component(EncounterTabStrip)]
+pub fn encounter_tab_strip(props: &EncounterTabStripProps) -> Html {
+    let EncounterTabStripProps { tabs } = props.clone();
+    let renaming = use_state_eq(|| None::<Uuid>);
+    let closing = use_state_eq(|| None::<Uuid>);
+
+    let encounter_tabs = match &*tabs {
+        Some(encounter_tabs) => encounter_tabs.clone(),
+        None => return html! {}
+    };
+
+    let can_close = encounter_tabs.tabs.len() > 1;
+
+    let tabs_html = encounter_tabs.tabs.iter()
+        .map(|tab| html! {
+            <EncounterTabButton
+                tab={tab.clone()}
+                tabs={tabs.clone()}
+                active={tab.id() == encounter_tabs.current}
+                renaming={renaming.clone()}
+                closing={closing.clone()}
+                can_close={can_close} />
+        })
+        .collect::<Html>();
+
+    let create = {
+        let tabs = tabs.clone();
+        Callback::from(move |_: MouseEvent| tabs.create())
+    };
+
+    let modal_html = match *closing {
+        Some(id) => {
+            let close = {
+                let tabs = tabs.clone();
+                let closing = closing.clone();
+                Callback::from(move |_| {
+                    closing.set(None);
+                    tabs.close(id);
+                })
+            };
+
+            let cancel = {
+                let closing = closing.clone();
+                Callback::from(move |_| closing.set(None))
+            };
+
+            html! {
+                <UnsavedChangesModal on_saved={close.clone()} on_discard={close} on_cancel={cancel} />
+            }
+        },
+        None => html! {}
+    };
+
+    html! {
+        <>
+            <div class="flex-row encounter-tabs" role="tablist">
+                {tabs_html}
+                <button class="blank new-tab" onclick={create} title="New encounter">{"+"}</button>
+            </div>
+            {modal_html}
+        </>
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+struct EncounterTabButtonProps {
+    tab: EncounterTab,
+    tabs: UseEncounterTabsHandle,
+    active: bool,
+    renaming: UseStateHandle<Option<Uuid>>,
+    closing: UseStateHandle<Option<Uuid>>,
+    can_close: bool
+}
+
+#[function_component(EncounterTabButton)]
+fn encounter_tab_button(props: &EncounterTabButtonProps) -> Html {
+    let EncounterTabButtonProps { tab, tabs, active, renaming, closing, can_close } = props.clone();
+    let id = tab.id();
+
+    let switch = {
+        let tabs = tabs.clone();
+        Callback::from(move |_: MouseEvent| {
+            if !active {
+                tabs.switch(id);
+            }
+        })
+    };
+
+    let start_rename = {
+        let renaming = renaming.clone();
+        Callback::from(move |_: MouseEvent| renaming.set(Some(id)))
+    };
+
+    let rename = {
+        let tabs = tabs.clone();
+        let renaming = renaming.clone();
+        let name = tab.name().to_string();
+        Callback::from(move |e: Event| {
+            let target: HtmlInputElement = e.target_unchecked_into();
+            renaming.set(None);
+            if target.value() != name {
+                tabs.rename(id, target.value());
+            }
+        })
+    };
+
+    let finish_rename_on_enter = Callback::from(|e: KeyboardEvent| {
+        if e.key() == "Enter" {
+            let target: HtmlInputElement = e.target_unchecked_into();
+            let _ = target.blur();
+        }
+    });
+
+    let duplicate = {
+        let tabs = tabs.clone();
+        Callback::from(move |e: MouseEvent| {
+            e.stop_propagation();
+            tabs.duplicate(id);
+        })
+    };
+
+    // Unsaved changes are offered a save first, which needs the encounter to be the current one.
+    let close = {
+        let dirty = tab.dirty();
+        Callback::from(move |e: MouseEvent| {
+            e.stop_propagation();
+            if dirty {
+                if !active {
+                    tabs.switch(id);
+                }
+
+                closing.set(Some(id));
+            } else {
+                tabs.close(id);
+            }
+        })
+    };
+
+    let label_html = if *renaming == Some(id) {
+        html! {
+            <input class="tab-name" value={tab.name().to_string()} onchange={rename} onkeydown={finish_rename_on_enter} autofocus=true />
+        }
+    } else {
+        let marker = if tab.dirty() { "*" } else { "" };
+        html! {
+            <span class="tab-name" ondblclick={start_rename} title="Double click to rename">{format!("{}{}", tab.name(), marker)}</span>
+        }
+    };
+
+    html! {
+        <div class={classes!("flex-row", "encounter-tab", active.then_some("active"))} role="tab" aria-selected={active.to_string()} onclick={switch}>
+            {label_html}
+            <button class="blank" onclick={duplicate} title="Duplicate encounter">{"⧉"}</button>
+            if can_close {
+                <button class="blank" onclick={close} title="Close encounter">{"×"}</button>
+            }
+        </div>
+    }
+}
@@ -0,0 +1,61 @@
+use common_data_lib::{creatures::CreaturePatch, BackendError};
+use uuid::Uuid;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::{glue::update_creature_with_callback, Error};
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct NameInputProps {
+    pub id: Uuid,
+    pub name: String,
+    pub on_renamed: Callback<()>
+}
+
+/// A creature's name that can be edited in place. Names the backend turns down are put back, with the reason shown
+/// until the next attempt.
+#[function_component(NameInput)]
+pub fn name_input(props: &NameInputProps) -> Html {
+    let NameInputProps { id, name, on_renamed } = props.clone();
+    let error = use_state_eq(|| None::<String>);
+
+    let rename = {
+        let error = error.clone();
+        Callback::from(move |e: Event| {
+            let target: HtmlInputElement = e.target_unchecked_into();
+            if target.value() == name {
+                return;
+            }
+
+            let error = error.clone();
+            let on_renamed = on_renamed.clone();
+            let name = name.clone();
+            let patch = CreaturePatch { name: Some(target.value()), ..CreaturePatch::default() };
+            update_creature_with_callback(id, patch, move |result: Result<_, Error>| {
+                match result {
+                    Ok(_) => {
+                        error.set(None);
+                        on_renamed.emit(());
+                    },
+                    Err(err) => {
+                        target.set_value(&name);
+                        error.set(Some(match err {
+                            Error::BackendError(BackendError::ArgumentError { message, .. }) => message,
+                            err => err.to_string()
+                        }));
+                    }
+                }
+            });
+        })
+    };
+
+    html! {
+        <input
+            class={classes!("flex-grow-1", "name-input", error.is_some().then_some("error-text"))}
+            value={props.name.clone()}
+            title={(*error).clone()}
+            aria-label="Name"
+            aria-invalid={error.is_some().to_string()}
+            onchange={rename} />
+    }
+}
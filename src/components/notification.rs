@@ -0,0 +1,25 @@
+use yew::prelude::*;
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct NotificationProps {
+    pub on_dismiss: Callback<()>,
+    #[prop_or_default]
+    pub children: Children
+}
+
+#[function_component(Notification)]
+pub fn notification(props: &NotificationProps) -> Html {
+    let NotificationProps { on_dismiss, children } = props.clone();
+    let dismiss = Callback::from(move |_: MouseEvent| {
+        on_dismiss.emit(());
+    });
+
+    html! {
+        <div class="notification flex-row" role="status">
+            <div class="flex-grow-1">
+                {children}
+            </div>
+            <button class="blank" onclick={dismiss}>{"Dismiss"}</button>
+        </div>
+    }
+}
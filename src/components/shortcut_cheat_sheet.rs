@@ -0,0 +1,46 @@
+use common_data_lib::keymap::{KeyAction, Keymap};
+use yew::prelude::*;
+
+use crate::components::modal::Modal;
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct ShortcutCheatSheetProps {
+    pub keymap: Keymap,
+    pub on_close: Callback<()>
+}
+
+#[function_component(ShortcutCheatSheet)]
+pub fn shortcut_cheat_sheet(props: &ShortcutCheatSheetProps) -> Html {
+    let ShortcutCheatSheetProps { keymap, on_close } = props.clone();
+    let close = Callback::from(move |_: MouseEvent| {
+        on_close.emit(());
+    });
+
+    let rows = KeyAction::all().iter()
+        .map(|action| {
+            let keys = keymap.bindings_for(*action)
+                .map(|binding| binding.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            html! {
+                <tr>
+                    <td><p>{action.to_string()}</p></td>
+                    <td class="shortcut-keys"><p>{if keys.is_empty() { "Unbound".to_string() } else { keys }}</p></td>
+                </tr>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <Modal>
+            <h2 class="heading">{"Keyboard shortcuts"}</h2>
+            <table class="shortcut-table">
+                {rows}
+            </table>
+            <div class="flex-row button-group modal-buttons">
+                <button class="flex-grow-1" onclick={close}>{"Close"}</button>
+            </div>
+        </Modal>
+    }
+}
@@ -0,0 +1,107 @@
+use common_data_lib::{damage::DamageType, stat_block::{format_bonus, Ability, StatBlock, StatBlockEntry}};
+use yew::prelude::*;
+
+use crate::components::creature_notes::render_markdown;
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct StatBlockViewProps {
+    pub name: String,
+    pub stat_block: StatBlock
+}
+
+/// A creature's stat block laid out the way the source books print it.
+#[function_component(StatBlockView)]
+pub fn stat_block_view(props: &StatBlockViewProps) -> Html {
+    let StatBlockViewProps { name, stat_block } = props.clone();
+
+    let armor_class = if stat_block.armor_source.is_empty() {
+        stat_block.armor_class.to_string()
+    } else {
+        format!("{} ({})", stat_block.armor_class, stat_block.armor_source)
+    };
+
+    let saving_throws = stat_block.saving_throws.iter()
+        .map(|(ability, bonus)| format!("{} {}", ability.abbreviation(), format_bonus(*bonus)))
+        .collect::<Vec<_>>();
+
+    let skills = stat_block.skills.iter()
+        .map(|(skill, bonus)| format!("{} {}", skill, format_bonus(*bonus)))
+        .collect::<Vec<_>>();
+
+    html! {
+        <section class="stat-block" aria-label={format!("Stat block for {}", name)}>
+            <h3 class="stat-block-name">{&name}</h3>
+            if !stat_block.description.is_empty() {
+                <p class="stat-block-description">{&stat_block.description}</p>
+            }
+            <hr />
+            {property("Armor Class", &armor_class)}
+            {property("Hit Dice", &stat_block.hit_dice)}
+            {property("Speed", &stat_block.speed)}
+            <hr />
+            <table class="stat-block-abilities">
+                <tr>
+                    {Ability::all().iter().map(|ability| html! {
+                        <th title={ability.to_string()}>{ability.abbreviation()}</th>
+                    }).collect::<Html>()}
+                </tr>
+                <tr>
+                    {Ability::all().iter().map(|&ability| html! {
+                        <td>{format!("{} ({})", stat_block.abilities.score(ability), format_bonus(stat_block.abilities.modifier(ability)))}</td>
+                    }).collect::<Html>()}
+                </tr>
+            </table>
+            <hr />
+            {property("Saving Throws", &saving_throws.join(", "))}
+            {property("Skills", &skills.join(", "))}
+            {property("Damage Vulnerabilities", &join_damage_types(&stat_block.damage_vulnerabilities))}
+            {property("Damage Resistances", &join_damage_types(&stat_block.damage_resistances))}
+            {property("Damage Immunities", &join_damage_types(&stat_block.damage_immunities))}
+            {property("Condition Immunities", &stat_block.condition_immunities.join(", "))}
+            {property("Senses", &stat_block.senses.join(", "))}
+            {property("Languages", &stat_block.languages.join(", "))}
+            {property("Challenge", &stat_block.challenge)}
+            {entries(None, &stat_block.traits)}
+            {entries(Some("Actions"), &stat_block.actions)}
+            {entries(Some("Reactions"), &stat_block.reactions)}
+            {entries(Some("Legendary Actions"), &stat_block.legendary_actions)}
+        </section>
+    }
+}
+
+pub fn join_damage_types(values: &[DamageType]) -> String {
+    values.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+/// A labelled line of the stat block, left out when there's nothing to show.
+pub fn property(label: &str, value: &str) -> Html {
+    if value.is_empty() {
+        return html! {};
+    }
+
+    html! {
+        <p class="stat-block-property"><strong>{label}</strong>{" "}{value}</p>
+    }
+}
+
+fn entries(heading: Option<&str>, entries: &[StatBlockEntry]) -> Html {
+    if entries.is_empty() {
+        return html! {};
+    }
+
+    html! {
+        <>
+            if let Some(heading) = heading {
+                <h4 class="stat-block-heading">{heading}</h4>
+            } else {
+                <hr />
+            }
+            {entries.iter().map(|entry| html! {
+                <div class="stat-block-entry">
+                    <strong>{format!("{}.", entry.name)}</strong>
+                    {render_markdown(&entry.description)}
+                </div>
+            }).collect::<Html>()}
+        </>
+    }
+}
@@ -0,0 +1,43 @@
+use yew::prelude::*;
+
+use crate::{components::modal::Modal, glue::save_or_prompt_encounter_with_callback};
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct UnsavedChangesModalProps {
+    pub on_saved: Callback<()>,
+    pub on_discard: Callback<()>,
+    pub on_cancel: Callback<()>
+}
+
+#[function_component(UnsavedChangesModal)]
+pub fn unsaved_changes_modal(props: &UnsavedChangesModalProps) -> Html {
+    let UnsavedChangesModalProps { on_saved, on_discard, on_cancel } = props.clone();
+
+    let save = Callback::from(move |_: MouseEvent| {
+        let on_saved = on_saved.clone();
+        save_or_prompt_encounter_with_callback(move |saved: bool| {
+            if saved {
+                on_saved.emit(());
+            }
+        });
+    });
+
+    let discard = Callback::from(move |_: MouseEvent| {
+        on_discard.emit(());
+    });
+
+    let cancel = Callback::from(move |_: MouseEvent| {
+        on_cancel.emit(());
+    });
+
+    html! {
+        <Modal>
+            <p>{"The current encounter has unsaved changes. Would you like to save them first?"}</p>
+            <div class="flex-row button-group modal-buttons">
+                <button class="flex-grow-1" onclick={save}>{"Save"}</button>
+                <button class="flex-grow-1" onclick={discard}>{"Discard"}</button>
+                <button class="flex-grow-1" onclick={cancel}>{"Cancel"}</button>
+            </div>
+        </Modal>
+    }
+}
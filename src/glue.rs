@@ -1,12 +1,25 @@
-use std::path::PathBuf;
+use std::{cell::Cell, path::PathBuf};
 
-use common_data_lib::{BackendError, creatures::{Creature, ConflictGroup}};
+use common_data_lib::{BackendError, campaign::{CampaignEncounter, CampaignWorkspace, EncounterStatus, Roster}, cards::CardEdge, damage::Damage, creatures::{Creature, ConflictGroup, CreaturePatch, Side}, csv_files::CsvColumnMapping, document::{DocumentState, EncounterTabs}, encounter::{EncounterView, ResumePosition, WaitReason}, initiative::InitiativeMode, recent_files::RecentFilesList, settings::Settings};
 use serde::Serialize;
 use uuid::Uuid;
 use wasm_bindgen::prelude::*;
 use yew::Callback;
 
-use crate::{Error, emit_callback_if_ok};
+use crate::{Error, emit_callback_if_ok, emit_result_callback};
+
+thread_local! {
+    static CURRENT_ENCOUNTER: Cell<Uuid> = const { Cell::new(Uuid::nil()) };
+}
+
+/// Sets the encounter that the encounter commands act on.
+pub fn set_current_encounter(id: Uuid) {
+    CURRENT_ENCOUNTER.with(|current| current.set(id));
+}
+
+pub fn current_encounter() -> Uuid {
+    CURRENT_ENCOUNTER.with(|current| current.get())
+}
 
 #[derive(Debug, Serialize)]
 struct PathArgs {
@@ -45,8 +58,253 @@ pub struct SaveDialogOptions {
     pub filters: Option<Vec<ExtensionFilter>>,
 }
 
+pub async fn get_settings() -> Result<Settings, Error> {
+    let result = invoke_no_args("get_settings").await.map_err(js_to_error)?;
+    serde_wasm_bindgen::from_value(result).map_err(Error::SerdeWasmBindgenError)
+}
+
+pub fn get_settings_with_callback(callback: impl Into<Callback<Settings>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(get_settings(), callback.into()));
+}
+
+#[derive(Debug, Serialize)]
+struct UpdateSettingsArgs {
+    settings: Settings
+}
+
+pub async fn update_settings(settings: Settings) -> Result<(), Error> {
+    let args = serde_wasm_bindgen::to_value(&UpdateSettingsArgs { settings }).map_err(Error::SerdeWasmBindgenError)?;
+    invoke("update_settings", args).await.map_err(js_to_error)?;
+    Ok(())
+}
+
+pub fn update_settings_with_callback(settings: Settings, callback: impl Into<Callback<Result<(), Error>>>) {
+    wasm_bindgen_futures::spawn_local(emit_result_callback(update_settings(settings), callback.into()));
+}
+
+pub async fn get_recent_files() -> Result<RecentFilesList, Error> {
+    let result = invoke_no_args("get_recent_files").await.map_err(js_to_error)?;
+    serde_wasm_bindgen::from_value(result).map_err(Error::SerdeWasmBindgenError)
+}
+
+pub fn get_recent_files_with_callback(callback: impl Into<Callback<RecentFilesList>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(get_recent_files(), callback.into()));
+}
+
+pub async fn get_last_directory() -> Result<Option<PathBuf>, Error> {
+    let result = invoke_no_args("get_last_directory").await.map_err(js_to_error)?;
+    serde_wasm_bindgen::from_value(result).map_err(Error::SerdeWasmBindgenError)
+}
+
+#[derive(Debug, Serialize)]
+struct EncounterArgs {
+    #[serde(rename = "encounterId")]
+    encounter_id: Uuid
+}
+
+pub async fn list_encounters() -> Result<EncounterTabs, Error> {
+    let value = invoke_no_args("list_encounters").await.map_err(js_to_error)?;
+    serde_wasm_bindgen::from_value(value).map_err(Error::SerdeWasmBindgenError)
+}
+
+pub fn list_encounters_with_callback(callback: impl Into<Callback<EncounterTabs>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(list_encounters(), callback.into()));
+}
+
+pub async fn create_encounter() -> Result<Uuid, Error> {
+    let value = invoke_no_args("create_encounter").await.map_err(js_to_error)?;
+    serde_wasm_bindgen::from_value(value).map_err(Error::SerdeWasmBindgenError)
+}
+
+pub fn create_encounter_with_callback(callback: impl Into<Callback<Uuid>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(create_encounter(), callback.into()));
+}
+
+pub async fn duplicate_encounter(encounter_id: Uuid) -> Result<Uuid, Error> {
+    let args = serde_wasm_bindgen::to_value(&EncounterArgs { encounter_id }).map_err(Error::SerdeWasmBindgenError)?;
+    let value = invoke("duplicate_encounter", args).await.map_err(js_to_error)?;
+    serde_wasm_bindgen::from_value(value).map_err(Error::SerdeWasmBindgenError)
+}
+
+pub fn duplicate_encounter_with_callback(encounter_id: Uuid, callback: impl Into<Callback<Uuid>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(duplicate_encounter(encounter_id), callback.into()));
+}
+
+#[derive(Debug, Serialize)]
+struct RenameEncounterArgs {
+    #[serde(rename = "encounterId")]
+    encounter_id: Uuid,
+    name: String
+}
+
+pub async fn rename_encounter(encounter_id: Uuid, name: impl Into<String>) -> Result<(), Error> {
+    let args = serde_wasm_bindgen::to_value(&RenameEncounterArgs { encounter_id, name: name.into() }).map_err(Error::SerdeWasmBindgenError)?;
+    invoke("rename_encounter", args).await.map_err(js_to_error)?;
+    Ok(())
+}
+
+pub fn rename_encounter_with_callback(encounter_id: Uuid, name: impl Into<String>, callback: impl Into<Callback<()>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(rename_encounter(encounter_id, name.into()), callback.into()));
+}
+
+pub async fn close_encounter(encounter_id: Uuid) -> Result<(), Error> {
+    let args = serde_wasm_bindgen::to_value(&EncounterArgs { encounter_id }).map_err(Error::SerdeWasmBindgenError)?;
+    invoke("close_encounter", args).await.map_err(js_to_error)?;
+    Ok(())
+}
+
+pub fn close_encounter_with_callback(encounter_id: Uuid, callback: impl Into<Callback<()>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(close_encounter(encounter_id), callback.into()));
+}
+
+pub async fn switch_encounter(encounter_id: Uuid) -> Result<(), Error> {
+    let args = serde_wasm_bindgen::to_value(&EncounterArgs { encounter_id }).map_err(Error::SerdeWasmBindgenError)?;
+    invoke("switch_encounter", args).await.map_err(js_to_error)?;
+    Ok(())
+}
+
+pub fn switch_encounter_with_callback(encounter_id: Uuid, callback: impl Into<Callback<()>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(switch_encounter(encounter_id), callback.into()));
+}
+
+#[derive(Debug, Serialize)]
+struct DirectoryArgs {
+    directory: PathBuf
+}
+
+pub async fn get_campaign() -> Result<Option<CampaignWorkspace>, Error> {
+    let value = invoke_no_args("get_campaign").await.map_err(js_to_error)?;
+    serde_wasm_bindgen::from_value(value).map_err(Error::SerdeWasmBindgenError)
+}
+
+pub fn get_campaign_with_callback(callback: impl Into<Callback<Option<CampaignWorkspace>>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(get_campaign(), callback.into()));
+}
+
+pub async fn create_campaign(directory: impl Into<PathBuf>) -> Result<CampaignWorkspace, Error> {
+    let args = serde_wasm_bindgen::to_value(&DirectoryArgs { directory: directory.into() }).map_err(Error::SerdeWasmBindgenError)?;
+    let value = invoke("create_campaign", args).await.map_err(js_to_error)?;
+    serde_wasm_bindgen::from_value(value).map_err(Error::SerdeWasmBindgenError)
+}
+
+pub fn create_campaign_with_callback(directory: impl Into<PathBuf>, callback: impl Into<Callback<Result<CampaignWorkspace, Error>>>) {
+    wasm_bindgen_futures::spawn_local(emit_result_callback(create_campaign(directory.into()), callback.into()));
+}
+
+pub async fn open_campaign(directory: impl Into<PathBuf>) -> Result<CampaignWorkspace, Error> {
+    let args = serde_wasm_bindgen::to_value(&DirectoryArgs { directory: directory.into() }).map_err(Error::SerdeWasmBindgenError)?;
+    let value = invoke("open_campaign", args).await.map_err(js_to_error)?;
+    serde_wasm_bindgen::from_value(value).map_err(Error::SerdeWasmBindgenError)
+}
+
+pub fn open_campaign_with_callback(directory: impl Into<PathBuf>, callback: impl Into<Callback<Result<CampaignWorkspace, Error>>>) {
+    wasm_bindgen_futures::spawn_local(emit_result_callback(open_campaign(directory.into()), callback.into()));
+}
+
+pub async fn close_campaign() -> Result<(), Error> {
+    invoke_no_args("close_campaign").await.map_err(js_to_error)?;
+    Ok(())
+}
+
+pub fn close_campaign_with_callback(callback: impl Into<Callback<()>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(close_campaign(), callback.into()));
+}
+
+#[derive(Debug, Serialize)]
+struct AddCampaignEncounterArgs {
+    name: String
+}
+
+pub async fn add_campaign_encounter(name: impl Into<String>) -> Result<CampaignEncounter, Error> {
+    let args = serde_wasm_bindgen::to_value(&AddCampaignEncounterArgs { name: name.into() }).map_err(Error::SerdeWasmBindgenError)?;
+    let value = invoke("add_campaign_encounter", args).await.map_err(js_to_error)?;
+    serde_wasm_bindgen::from_value(value).map_err(Error::SerdeWasmBindgenError)
+}
+
+pub fn add_campaign_encounter_with_callback(name: impl Into<String>, callback: impl Into<Callback<CampaignEncounter>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(add_campaign_encounter(name.into()), callback.into()));
+}
+
+#[derive(Debug, Serialize)]
+struct CampaignEncounterArgs {
+    id: Uuid
+}
+
+pub async fn remove_campaign_encounter(id: Uuid) -> Result<(), Error> {
+    let args = serde_wasm_bindgen::to_value(&CampaignEncounterArgs { id }).map_err(Error::SerdeWasmBindgenError)?;
+    invoke("remove_campaign_encounter", args).await.map_err(js_to_error)?;
+    Ok(())
+}
+
+pub fn remove_campaign_encounter_with_callback(id: Uuid, callback: impl Into<Callback<()>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(remove_campaign_encounter(id), callback.into()));
+}
+
+#[derive(Debug, Serialize)]
+struct SetEncounterStatusArgs {
+    id: Uuid,
+    status: EncounterStatus
+}
+
+pub async fn set_campaign_encounter_status(id: Uuid, status: EncounterStatus) -> Result<(), Error> {
+    let args = serde_wasm_bindgen::to_value(&SetEncounterStatusArgs { id, status }).map_err(Error::SerdeWasmBindgenError)?;
+    invoke("set_campaign_encounter_status", args).await.map_err(js_to_error)?;
+    Ok(())
+}
+
+pub fn set_campaign_encounter_status_with_callback(id: Uuid, status: EncounterStatus, callback: impl Into<Callback<()>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(set_campaign_encounter_status(id, status), callback.into()));
+}
+
+#[derive(Debug, Serialize)]
+struct RosterArgs {
+    roster: Roster
+}
+
+pub async fn add_selected_to_campaign_roster(roster: Roster) -> Result<(), Error> {
+    let args = serde_wasm_bindgen::to_value(&RosterArgs { roster }).map_err(Error::SerdeWasmBindgenError)?;
+    invoke_encounter("add_selected_to_campaign_roster", args).await.map_err(js_to_error)?;
+    Ok(())
+}
+
+pub fn add_selected_to_campaign_roster_with_callback(roster: Roster, callback: impl Into<Callback<()>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(add_selected_to_campaign_roster(roster), callback.into()));
+}
+
+#[derive(Debug, Serialize)]
+struct RemoveFromRosterArgs {
+    roster: Roster,
+    id: Uuid
+}
+
+pub async fn remove_from_campaign_roster(roster: Roster, id: Uuid) -> Result<(), Error> {
+    let args = serde_wasm_bindgen::to_value(&RemoveFromRosterArgs { roster, id }).map_err(Error::SerdeWasmBindgenError)?;
+    invoke("remove_from_campaign_roster", args).await.map_err(js_to_error)?;
+    Ok(())
+}
+
+pub fn remove_from_campaign_roster_with_callback(roster: Roster, id: Uuid, callback: impl Into<Callback<()>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(remove_from_campaign_roster(roster, id), callback.into()));
+}
+
+#[derive(Debug, Serialize)]
+struct AddRosterToEncounterArgs {
+    roster: Roster,
+    ids: Vec<Uuid>
+}
+
+pub async fn add_campaign_roster_to_encounter(roster: Roster, ids: Vec<Uuid>) -> Result<(), Error> {
+    let args = serde_wasm_bindgen::to_value(&AddRosterToEncounterArgs { roster, ids }).map_err(Error::SerdeWasmBindgenError)?;
+    invoke_encounter("add_campaign_roster_to_encounter", args).await.map_err(js_to_error)?;
+    Ok(())
+}
+
+pub fn add_campaign_roster_to_encounter_with_callback(roster: Roster, ids: Vec<Uuid>, callback: impl Into<Callback<()>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(add_campaign_roster_to_encounter(roster, ids), callback.into()));
+}
+
 pub async fn get_creatures() -> Result<Vec<Creature>, Error> {
-    let result = invoke_no_args("get_creatures").await.map_err(js_to_error)?;
+    let result = invoke_encounter("get_creatures", JsValue::UNDEFINED).await.map_err(js_to_error)?;
     serde_wasm_bindgen::from_value(result).map_err(Error::SerdeWasmBindgenError)
 }
 
@@ -61,7 +319,7 @@ struct AddCreaturesArgs {
 
 pub async fn add_creatures(creatures: impl Into<String>) -> Result<(), Error> {
     let args = serde_wasm_bindgen::to_value(&AddCreaturesArgs { creatures: creatures.into() }).map_err(Error::SerdeWasmBindgenError)?;
-    invoke("add_creatures", args).await.map_err(js_to_error)?;
+    invoke_encounter("add_creatures", args).await.map_err(js_to_error)?;
     Ok(())
 }
 
@@ -77,7 +335,7 @@ struct RemoveCreatureArgs {
 
 pub async fn remove_creature(id: Uuid) -> Result<Creature, Error> {
     let args = serde_wasm_bindgen::to_value(&RemoveCreatureArgs { id }).map_err(Error::SerdeWasmBindgenError)?;
-    let result = invoke("remove_creature", args).await.map_err(js_to_error)?;
+    let result = invoke_encounter("remove_creature", args).await.map_err(js_to_error)?;
     serde_wasm_bindgen::from_value(result).map_err(Error::SerdeWasmBindgenError)
 }
 
@@ -93,7 +351,7 @@ struct SetSelectedArgs {
 
 pub async fn set_creature_selected(id: Uuid, selected: bool) -> Result<(), Error> {
     let args = serde_wasm_bindgen::to_value(&SetSelectedArgs { id, selected }).map_err(Error::SerdeWasmBindgenError)?;
-    invoke("set_creature_selected", args).await.map_err(js_to_error)?;
+    invoke_encounter("set_creature_selected", args).await.map_err(js_to_error)?;
     Ok(())
 }
 
@@ -109,7 +367,7 @@ struct SetInitiativeArgs {
 
 pub async fn set_creature_initiative(id: Uuid, initiative: isize) -> Result<(), Error> {
     let args = serde_wasm_bindgen::to_value(&SetInitiativeArgs { id, initiative }).map_err(Error::SerdeWasmBindgenError)?;
-    invoke("set_creature_initiative", args).await.map_err(js_to_error)?;
+    invoke_encounter("set_creature_initiative", args).await.map_err(js_to_error)?;
     Ok(())
 }
 
@@ -117,6 +375,138 @@ pub fn set_creature_initiative_with_callback(id: Uuid, initiative: isize, callba
     wasm_bindgen_futures::spawn_local(emit_callback_if_ok(set_creature_initiative(id, initiative), callback.into()));
 }
 
+#[derive(Debug, Serialize)]
+struct UpdateCreatureArgs {
+    id: Uuid,
+    patch: CreaturePatch
+}
+
+pub async fn update_creature(id: Uuid, patch: CreaturePatch) -> Result<Creature, Error> {
+    let args = serde_wasm_bindgen::to_value(&UpdateCreatureArgs { id, patch }).map_err(Error::SerdeWasmBindgenError)?;
+    let value = invoke_encounter("update_creature", args).await.map_err(js_to_error)?;
+    serde_wasm_bindgen::from_value(value).map_err(Error::SerdeWasmBindgenError)
+}
+
+pub fn update_creature_with_callback(id: Uuid, patch: CreaturePatch, callback: impl Into<Callback<Result<Creature, Error>>>) {
+    wasm_bindgen_futures::spawn_local(emit_result_callback(update_creature(id, patch), callback.into()));
+}
+
+#[derive(Debug, Serialize)]
+struct DuplicateCreatureArgs {
+    id: Uuid,
+    reroll: bool
+}
+
+pub async fn duplicate_creature(id: Uuid, reroll: bool) -> Result<Creature, Error> {
+    let args = serde_wasm_bindgen::to_value(&DuplicateCreatureArgs { id, reroll }).map_err(Error::SerdeWasmBindgenError)?;
+    let value = invoke_encounter("duplicate_creature", args).await.map_err(js_to_error)?;
+    serde_wasm_bindgen::from_value(value).map_err(Error::SerdeWasmBindgenError)
+}
+
+pub fn duplicate_creature_with_callback(id: Uuid, reroll: bool, callback: impl Into<Callback<Creature>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(duplicate_creature(id, reroll), callback.into()));
+}
+
+#[derive(Debug, Serialize)]
+struct SetHiddenArgs {
+    id: Uuid,
+    hidden: bool,
+    #[serde(rename = "moveToInitiative")]
+    move_to_initiative: bool
+}
+
+pub async fn set_creature_hidden(id: Uuid, hidden: bool, move_to_initiative: bool) -> Result<(), Error> {
+    let args = SetHiddenArgs { id, hidden, move_to_initiative };
+    let args = serde_wasm_bindgen::to_value(&args).map_err(Error::SerdeWasmBindgenError)?;
+    invoke_encounter("set_creature_hidden", args).await.map_err(js_to_error)?;
+    Ok(())
+}
+
+pub fn set_creature_hidden_with_callback(id: Uuid, hidden: bool, move_to_initiative: bool, callback: impl Into<Callback<()>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(set_creature_hidden(id, hidden, move_to_initiative), callback.into()));
+}
+
+#[derive(Debug, Serialize)]
+struct AddSlotArgs {
+    id: Uuid,
+    initiative: isize
+}
+
+pub async fn add_initiative_slot(id: Uuid, initiative: isize) -> Result<Creature, Error> {
+    let args = serde_wasm_bindgen::to_value(&AddSlotArgs { id, initiative }).map_err(Error::SerdeWasmBindgenError)?;
+    let result = invoke_encounter("add_initiative_slot", args).await.map_err(js_to_error)?;
+    serde_wasm_bindgen::from_value(result).map_err(Error::SerdeWasmBindgenError)
+}
+
+pub fn add_initiative_slot_with_callback(id: Uuid, initiative: isize, callback: impl Into<Callback<Creature>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(add_initiative_slot(id, initiative), callback.into()));
+}
+
+#[derive(Debug, Serialize)]
+struct SetCardEdgesArgs {
+    id: Uuid,
+    edges: Vec<CardEdge>
+}
+
+pub async fn set_creature_card_edges(id: Uuid, edges: Vec<CardEdge>) -> Result<(), Error> {
+    let args = serde_wasm_bindgen::to_value(&SetCardEdgesArgs { id, edges }).map_err(Error::SerdeWasmBindgenError)?;
+    invoke_encounter("set_creature_card_edges", args).await.map_err(js_to_error)?;
+    Ok(())
+}
+
+pub fn set_creature_card_edges_with_callback(id: Uuid, edges: Vec<CardEdge>, callback: impl Into<Callback<()>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(set_creature_card_edges(id, edges), callback.into()));
+}
+
+pub async fn deal_initiative_cards() -> Result<(), Error> {
+    invoke_encounter("deal_initiative_cards", JsValue::UNDEFINED).await.map_err(js_to_error)?;
+    Ok(())
+}
+
+pub fn deal_initiative_cards_with_callback(callback: impl Into<Callback<()>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(deal_initiative_cards(), callback.into()));
+}
+
+#[derive(Debug, Serialize)]
+struct SetSideArgs {
+    id: Uuid,
+    side: Side
+}
+
+pub async fn set_creature_side(id: Uuid, side: Side) -> Result<(), Error> {
+    let args = serde_wasm_bindgen::to_value(&SetSideArgs { id, side }).map_err(Error::SerdeWasmBindgenError)?;
+    invoke_encounter("set_creature_side", args).await.map_err(js_to_error)?;
+    Ok(())
+}
+
+pub fn set_creature_side_with_callback(id: Uuid, side: Side, callback: impl Into<Callback<()>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(set_creature_side(id, side), callback.into()));
+}
+
+pub async fn get_initiative_mode() -> Result<InitiativeMode, Error> {
+    let value = invoke_encounter("get_initiative_mode", JsValue::UNDEFINED).await.map_err(js_to_error)?;
+    serde_wasm_bindgen::from_value(value).map_err(Error::SerdeWasmBindgenError)
+}
+
+pub fn get_initiative_mode_with_callback(callback: impl Into<Callback<InitiativeMode>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(get_initiative_mode(), callback.into()));
+}
+
+#[derive(Debug, Serialize)]
+struct SetInitiativeModeArgs {
+    mode: InitiativeMode
+}
+
+pub async fn set_initiative_mode(mode: InitiativeMode) -> Result<(), Error> {
+    let args = serde_wasm_bindgen::to_value(&SetInitiativeModeArgs { mode }).map_err(Error::SerdeWasmBindgenError)?;
+    invoke_encounter("set_initiative_mode", args).await.map_err(js_to_error)?;
+    Ok(())
+}
+
+pub fn set_initiative_mode_with_callback(mode: InitiativeMode, callback: impl Into<Callback<()>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(set_initiative_mode(mode), callback.into()));
+}
+
 #[derive(Debug, Serialize)]
 struct SetAllSelectedArgs {
     selected: bool
@@ -124,7 +514,7 @@ struct SetAllSelectedArgs {
 
 pub async fn set_all_creatures_selected(selected: bool) -> Result<(), Error> {
     let args = serde_wasm_bindgen::to_value(&SetAllSelectedArgs { selected }).map_err(Error::SerdeWasmBindgenError)?;
-    invoke("set_all_creatures_selected", args).await.map_err(js_to_error)?;
+    invoke_encounter("set_all_creatures_selected", args).await.map_err(js_to_error)?;
     Ok(())
 }
 
@@ -133,7 +523,7 @@ pub fn set_all_creatures_selected_with_callback(selected: bool, callback: impl I
 }
 
 pub async fn reset_all_initiatives() -> Result<(), Error> {
-    invoke_no_args("reset_all_initiatives").await.map_err(js_to_error)?;
+    invoke_encounter("reset_all_initiatives", JsValue::UNDEFINED).await.map_err(js_to_error)?;
     Ok(())
 }
 
@@ -150,7 +540,7 @@ struct GetConflictsArgs {
 pub async fn get_initiative_conflicts(set_conflicts: bool) -> Result<Vec<ConflictGroup>, Error> {
     let args = GetConflictsArgs { set_conflicts };
     let args_value = serde_wasm_bindgen::to_value(&args).map_err(Error::SerdeWasmBindgenError)?;
-    let value = invoke("get_initiative_conflicts", args_value).await.map_err(js_to_error)?;
+    let value = invoke_encounter("get_initiative_conflicts", args_value).await.map_err(js_to_error)?;
     serde_wasm_bindgen::from_value(value).map_err(Error::SerdeWasmBindgenError)
 }
 
@@ -171,7 +561,7 @@ struct MoveConflictArgs {
 pub async fn move_initiative_conflict(group_index: usize, move_index: usize, target_index: usize) -> Result<(), Error> {
     let args = MoveConflictArgs { group_index, move_index, target_index };
     let args_value = serde_wasm_bindgen::to_value(&args).map_err(Error::SerdeWasmBindgenError)?;
-    invoke("move_initiative_conflict", args_value).await.map_err(js_to_error)?;
+    invoke_encounter("move_initiative_conflict", args_value).await.map_err(js_to_error)?;
     Ok(())
 }
 
@@ -180,7 +570,7 @@ pub fn move_initiative_conflict_with_callback(group_index: usize, move_index: us
 }
 
 pub async fn finalize_initiative_order() -> Result<(), Error> {
-    invoke_no_args("finalize_initiative_order").await.map_err(js_to_error)?;
+    invoke_encounter("finalize_initiative_order", JsValue::UNDEFINED).await.map_err(js_to_error)?;
     Ok(())
 }
 
@@ -188,13 +578,153 @@ pub fn finalize_initiative_order_with_callback(callback: impl Into<Callback<()>>
     wasm_bindgen_futures::spawn_local(emit_callback_if_ok(finalize_initiative_order(), callback.into()));
 }
 
-pub async fn get_active_encounter_creatures() -> Result<Vec<Creature>, Error> {
-    let value = invoke_no_args("get_active_encounter_creatures").await.map_err(js_to_error)?;
+pub async fn get_active_encounter() -> Result<EncounterView, Error> {
+    let value = invoke_encounter("get_active_encounter", JsValue::UNDEFINED).await.map_err(js_to_error)?;
     serde_wasm_bindgen::from_value(value).map_err(Error::SerdeWasmBindgenError)
 }
 
-pub fn get_active_encounter_creatures_with_callback(callback: impl Into<Callback<Vec<Creature>>>) {
-    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(get_active_encounter_creatures(), callback.into()));
+pub fn get_active_encounter_with_callback(callback: impl Into<Callback<EncounterView>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(get_active_encounter(), callback.into()));
+}
+
+pub async fn next_turn() -> Result<(), Error> {
+    invoke_encounter("next_turn", JsValue::UNDEFINED).await.map_err(js_to_error)?;
+    Ok(())
+}
+
+pub fn next_turn_with_callback(callback: impl Into<Callback<()>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(next_turn(), callback.into()));
+}
+
+pub async fn previous_turn() -> Result<(), Error> {
+    invoke_encounter("previous_turn", JsValue::UNDEFINED).await.map_err(js_to_error)?;
+    Ok(())
+}
+
+pub fn previous_turn_with_callback(callback: impl Into<Callback<()>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(previous_turn(), callback.into()));
+}
+
+#[derive(Debug, Serialize)]
+struct PassTurnArgs {
+    id: Uuid
+}
+
+pub async fn pass_turn_to(id: Uuid) -> Result<(), Error> {
+    let args = serde_wasm_bindgen::to_value(&PassTurnArgs { id }).map_err(Error::SerdeWasmBindgenError)?;
+    invoke_encounter("pass_turn_to", args).await.map_err(js_to_error)?;
+    Ok(())
+}
+
+pub fn pass_turn_to_with_callback(id: Uuid, callback: impl Into<Callback<()>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(pass_turn_to(id), callback.into()));
+}
+
+#[derive(Debug, Serialize)]
+struct RoundInitiativesArgs {
+    initiatives: Vec<(Uuid, isize)>
+}
+
+pub async fn set_round_initiatives(initiatives: Vec<(Uuid, isize)>) -> Result<(), Error> {
+    let args = serde_wasm_bindgen::to_value(&RoundInitiativesArgs { initiatives }).map_err(Error::SerdeWasmBindgenError)?;
+    invoke_encounter("set_round_initiatives", args).await.map_err(js_to_error)?;
+    Ok(())
+}
+
+pub fn set_round_initiatives_with_callback(initiatives: Vec<(Uuid, isize)>, callback: impl Into<Callback<()>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(set_round_initiatives(initiatives), callback.into()));
+}
+
+pub async fn deal_round_cards() -> Result<(), Error> {
+    invoke_encounter("deal_round_cards", JsValue::UNDEFINED).await.map_err(js_to_error)?;
+    Ok(())
+}
+
+pub fn deal_round_cards_with_callback(callback: impl Into<Callback<()>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(deal_round_cards(), callback.into()));
+}
+
+#[derive(Debug, Serialize)]
+struct WaitArgs {
+    reason: WaitReason
+}
+
+pub async fn wait_active_creature(reason: WaitReason) -> Result<(), Error> {
+    let args = serde_wasm_bindgen::to_value(&WaitArgs { reason }).map_err(Error::SerdeWasmBindgenError)?;
+    invoke_encounter("wait_active_creature", args).await.map_err(js_to_error)?;
+    Ok(())
+}
+
+pub fn wait_active_creature_with_callback(reason: WaitReason, callback: impl Into<Callback<()>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(wait_active_creature(reason), callback.into()));
+}
+
+#[derive(Debug, Serialize)]
+struct ResumeArgs {
+    id: Uuid,
+    position: ResumePosition
+}
+
+pub async fn resume_creature(id: Uuid, position: ResumePosition) -> Result<(), Error> {
+    let args = serde_wasm_bindgen::to_value(&ResumeArgs { id, position }).map_err(Error::SerdeWasmBindgenError)?;
+    invoke_encounter("resume_creature", args).await.map_err(js_to_error)?;
+    Ok(())
+}
+
+pub fn resume_creature_with_callback(id: Uuid, position: ResumePosition, callback: impl Into<Callback<()>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(resume_creature(id, position), callback.into()));
+}
+
+#[derive(Debug, Serialize)]
+struct SetHitPointsArgs {
+    id: Uuid,
+    maximum: Option<usize>
+}
+
+pub async fn set_creature_hit_points(id: Uuid, maximum: Option<usize>) -> Result<(), Error> {
+    let args = SetHitPointsArgs { id, maximum };
+    let args = serde_wasm_bindgen::to_value(&args).map_err(Error::SerdeWasmBindgenError)?;
+    invoke_encounter("set_creature_hit_points", args).await.map_err(js_to_error)?;
+    Ok(())
+}
+
+pub fn set_creature_hit_points_with_callback(id: Uuid, maximum: Option<usize>, callback: impl Into<Callback<()>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(set_creature_hit_points(id, maximum), callback.into()));
+}
+
+#[derive(Debug, Serialize)]
+struct HitPointsChangeArgs {
+    id: Uuid,
+    amount: usize
+}
+
+#[derive(Debug, Serialize)]
+struct DamageCreatureArgs {
+    id: Uuid,
+    damage: Vec<Damage>
+}
+
+/// Returns the total damage taken after the creature's resistances, vulnerabilities and immunities.
+pub async fn damage_creature(id: Uuid, damage: Vec<Damage>) -> Result<usize, Error> {
+    let args = DamageCreatureArgs { id, damage };
+    let args = serde_wasm_bindgen::to_value(&args).map_err(Error::SerdeWasmBindgenError)?;
+    let result = invoke_encounter("damage_creature", args).await.map_err(js_to_error)?;
+    serde_wasm_bindgen::from_value(result).map_err(Error::SerdeWasmBindgenError)
+}
+
+pub fn damage_creature_with_callback(id: Uuid, damage: Vec<Damage>, callback: impl Into<Callback<Result<usize, Error>>>) {
+    wasm_bindgen_futures::spawn_local(emit_result_callback(damage_creature(id, damage), callback.into()));
+}
+
+pub async fn heal_creature(id: Uuid, amount: usize) -> Result<(), Error> {
+    let args = HitPointsChangeArgs { id, amount };
+    let args = serde_wasm_bindgen::to_value(&args).map_err(Error::SerdeWasmBindgenError)?;
+    invoke_encounter("heal_creature", args).await.map_err(js_to_error)?;
+    Ok(())
+}
+
+pub fn heal_creature_with_callback(id: Uuid, amount: usize, callback: impl Into<Callback<()>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(heal_creature(id, amount), callback.into()));
 }
 
 #[derive(Debug, Serialize)]
@@ -205,7 +735,7 @@ struct AddCreaturesToTncounterArgs {
 pub async fn add_creatures_to_active_encounter(creatures: impl Into<String>) -> Result<(), Error> {
     let args = AddCreaturesToTncounterArgs { creatures: creatures.into() };
     let args = serde_wasm_bindgen::to_value(&args).map_err(Error::SerdeWasmBindgenError)?;
-    let value = invoke("add_creatures_to_active_encounter", args).await.map_err(js_to_error)?;
+    let value = invoke_encounter("add_creatures_to_active_encounter", args).await.map_err(js_to_error)?;
     serde_wasm_bindgen::from_value(value).map_err(Error::SerdeWasmBindgenError)
 }
 
@@ -224,7 +754,7 @@ struct ChangeEncounterOrderArgs {
 pub async fn change_active_encounter_order(move_index: usize, target_index: usize) -> Result<(), Error> {
     let args = ChangeEncounterOrderArgs { move_index, target_index };
     let args = serde_wasm_bindgen::to_value(&args).map_err(Error::SerdeWasmBindgenError)?;
-    invoke("change_active_encounter_order", args).await.map_err(js_to_error)?;
+    invoke_encounter("change_active_encounter_order", args).await.map_err(js_to_error)?;
     Ok(())
 }
 
@@ -240,7 +770,7 @@ struct RemoveFromActiveEncounterArgs {
 pub async fn remove_from_active_encounter(id: Uuid) -> Result<(), Error> {
     let args = RemoveFromActiveEncounterArgs { id };
     let args = serde_wasm_bindgen::to_value(&args).map_err(Error::SerdeWasmBindgenError)?;
-    invoke("remove_from_active_encounter", args).await.map_err(js_to_error)?;
+    invoke_encounter("remove_from_active_encounter", args).await.map_err(js_to_error)?;
     Ok(())
 }
 
@@ -250,7 +780,7 @@ pub fn remove_from_active_encounter_with_callback(id: Uuid, callback: impl Into<
 
 pub async fn save_encounter(path: impl Into<PathBuf>) -> Result<(), Error> {
     let args = serde_wasm_bindgen::to_value(&PathArgs { path: path.into() }).map_err(Error::SerdeWasmBindgenError)?;
-    invoke("save_encounter", args).await.map_err(js_to_error)?;
+    invoke_encounter("save_encounter", args).await.map_err(js_to_error)?;
     Ok(())
 }
 
@@ -258,9 +788,43 @@ pub fn save_encounter_with_callback(path: impl Into<PathBuf>, callback: impl Int
     wasm_bindgen_futures::spawn_local(emit_callback_if_ok(save_encounter(path.into()), callback.into()));
 }
 
+pub async fn save_current_encounter() -> Result<bool, Error> {
+    let value = invoke_encounter("save_current_encounter", JsValue::UNDEFINED).await.map_err(js_to_error)?;
+    serde_wasm_bindgen::from_value(value).map_err(Error::SerdeWasmBindgenError)
+}
+
+/// Asks for a file to save the encounter to, returning false if the dialog was cancelled.
+pub async fn save_encounter_as() -> Result<bool, Error> {
+    match save_encounter_dialog().await? {
+        Some(path) => {
+            log::info!("Saving encounter: {:?}", &path);
+            save_encounter(path).await?;
+            Ok(true)
+        },
+        None => Ok(false)
+    }
+}
+
+pub fn save_encounter_as_with_callback(callback: impl Into<Callback<bool>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(save_encounter_as(), callback.into()));
+}
+
+/// Saves the encounter to its current file, falling back to asking for a file if it has never been saved.
+pub async fn save_or_prompt_encounter() -> Result<bool, Error> {
+    if save_current_encounter().await? {
+        return Ok(true);
+    }
+
+    save_encounter_as().await
+}
+
+pub fn save_or_prompt_encounter_with_callback(callback: impl Into<Callback<bool>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(save_or_prompt_encounter(), callback.into()));
+}
+
 pub async fn load_encounter(path: impl Into<PathBuf>) -> Result<(), Error> {
     let args = serde_wasm_bindgen::to_value(&PathArgs { path: path.into() }).map_err(Error::SerdeWasmBindgenError)?;
-    invoke("load_encounter", args).await.map_err(js_to_error)?;
+    invoke_encounter("load_encounter", args).await.map_err(js_to_error)?;
     Ok(())
 }
 
@@ -269,7 +833,7 @@ pub fn load_encounter_with_callback(path: impl Into<PathBuf>, callback: impl Int
 }
 
 pub async fn new_encounter() -> Result<(), Error> {
-    invoke_no_args("new_encounter").await.map_err(js_to_error)?;
+    invoke_encounter("new_encounter", JsValue::UNDEFINED).await.map_err(js_to_error)?;
     Ok(())
 }
 
@@ -277,9 +841,110 @@ pub fn new_encounter_with_callback(callback: impl Into<Callback<()>>) {
     wasm_bindgen_futures::spawn_local(emit_callback_if_ok(new_encounter(), callback.into()));
 }
 
+pub async fn get_document_state() -> Result<DocumentState, Error> {
+    let value = invoke_encounter("get_document_state", JsValue::UNDEFINED).await.map_err(js_to_error)?;
+    serde_wasm_bindgen::from_value(value).map_err(Error::SerdeWasmBindgenError)
+}
+
+pub fn get_document_state_with_callback(callback: impl Into<Callback<DocumentState>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(get_document_state(), callback.into()));
+}
+
+#[derive(Debug, Serialize)]
+struct CloseWindowArgs {
+    #[serde(rename = "discardChanges")]
+    discard_changes: bool
+}
+
+pub async fn close_window(discard_changes: bool) -> Result<(), Error> {
+    let args = serde_wasm_bindgen::to_value(&CloseWindowArgs { discard_changes }).map_err(Error::SerdeWasmBindgenError)?;
+    invoke("close_window", args).await.map_err(js_to_error)?;
+    Ok(())
+}
+
+pub fn close_window_with_callback(discard_changes: bool, callback: impl Into<Callback<()>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(close_window(discard_changes), callback.into()));
+}
+
+/// Calls the callback whenever the backend intercepts an attempt to close the window with unsaved changes, passing
+/// the ID of an encounter that needs saving.
+pub fn listen_for_close_requested(callback: impl Into<Callback<Uuid>>) {
+    let callback = callback.into();
+    let handler = Closure::<dyn FnMut(JsValue)>::new(move |event: JsValue| {
+        let payload = js_sys::Reflect::get(&event, &JsValue::from_str("payload")).unwrap_or(JsValue::UNDEFINED);
+        match serde_wasm_bindgen::from_value(payload) {
+            Ok(id) => callback.emit(id),
+            Err(err) => log::error!("Unable to read close request: {}", err)
+        }
+    });
+
+    wasm_bindgen_futures::spawn_local(async move {
+        listen("close-requested", &handler).await;
+
+        // The listener lives as long as the app, so the handler must never be dropped.
+        handler.forget();
+    });
+}
+
+/// Calls the callback whenever an open encounter is renamed, saved or changed for the first time since saving.
+pub fn listen_for_encounters_changed(callback: impl Into<Callback<()>>) {
+    let callback = callback.into();
+    let handler = Closure::<dyn FnMut(JsValue)>::new(move |_: JsValue| {
+        callback.emit(());
+    });
+
+    wasm_bindgen_futures::spawn_local(async move {
+        listen("encounters-changed", &handler).await;
+        handler.forget();
+    });
+}
+
+#[derive(Debug, Serialize)]
+struct ExportCsvArgs {
+    path: PathBuf,
+    #[serde(rename = "forGm")]
+    for_gm: bool
+}
+
+pub async fn export_creatures_csv(path: impl Into<PathBuf>, for_gm: bool) -> Result<(), Error> {
+    let args = serde_wasm_bindgen::to_value(&ExportCsvArgs { path: path.into(), for_gm }).map_err(Error::SerdeWasmBindgenError)?;
+    invoke_encounter("export_creatures_csv", args).await.map_err(js_to_error)?;
+    Ok(())
+}
+
+pub fn export_creatures_csv_with_callback(path: impl Into<PathBuf>, for_gm: bool, callback: impl Into<Callback<()>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(export_creatures_csv(path.into(), for_gm), callback.into()));
+}
+
+pub async fn get_csv_headers(path: impl Into<PathBuf>) -> Result<Vec<String>, Error> {
+    let args = serde_wasm_bindgen::to_value(&PathArgs { path: path.into() }).map_err(Error::SerdeWasmBindgenError)?;
+    let value = invoke("get_csv_headers", args).await.map_err(js_to_error)?;
+    serde_wasm_bindgen::from_value(value).map_err(Error::SerdeWasmBindgenError)
+}
+
+pub fn get_csv_headers_with_callback(path: impl Into<PathBuf>, callback: impl Into<Callback<Vec<String>>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(get_csv_headers(path.into()), callback.into()));
+}
+
+#[derive(Debug, Serialize)]
+struct ImportCsvArgs {
+    path: PathBuf,
+    mapping: CsvColumnMapping
+}
+
+pub async fn import_creatures_csv(path: impl Into<PathBuf>, mapping: CsvColumnMapping) -> Result<(), Error> {
+    let args = serde_wasm_bindgen::to_value(&ImportCsvArgs { path: path.into(), mapping }).map_err(Error::SerdeWasmBindgenError)?;
+    invoke_encounter("import_creatures_csv", args).await.map_err(js_to_error)?;
+    Ok(())
+}
+
+pub fn import_creatures_csv_with_callback(path: impl Into<PathBuf>, mapping: CsvColumnMapping, callback: impl Into<Callback<Result<(), Error>>>) {
+    wasm_bindgen_futures::spawn_local(emit_result_callback(import_creatures_csv(path.into(), mapping), callback.into()));
+}
+
 pub async fn open_encounter_dialog() -> Result<Option<PathBuf>, Error> {
     let args = OpenDialogOptions {
-        default_path: Some(dirs::home_dir().unwrap_or(PathBuf::from("/home")).to_string_lossy().to_string()),
+        default_path: Some(get_encounter_default_path().await),
         directory: false,
         multiple: false,
         recursive: false,
@@ -298,7 +963,7 @@ pub fn open_encounter_dialog_with_callback(callback: impl Into<Callback<Option<P
 
 pub async fn save_encounter_dialog() -> Result<Option<PathBuf>, Error> {
     let args = SaveDialogOptions {
-        default_path: Some(dirs::home_dir().unwrap_or(PathBuf::from("/home")).to_string_lossy().to_string()),
+        default_path: Some(get_encounter_default_path().await),
         filters: get_encounter_filters()
     };
 
@@ -312,12 +977,95 @@ pub fn save_encounter_dialog_with_callback(callback: impl Into<Callback<Option<P
     wasm_bindgen_futures::spawn_local(emit_callback_if_ok(save_encounter_dialog(), callback.into()));
 }
 
+pub async fn open_campaign_dialog() -> Result<Option<PathBuf>, Error> {
+    let args = OpenDialogOptions {
+        default_path: Some(get_encounter_default_path().await),
+        directory: true,
+        multiple: false,
+        recursive: false,
+        filters: None
+    };
+
+    let args_value = serde_wasm_bindgen::to_value(&args).map_err(Error::SerdeWasmBindgenError)?;
+    let result = serde_wasm_bindgen::from_value(open(args_value).await).map_err(Error::SerdeWasmBindgenError)?;
+
+    Ok(result)
+}
+
+pub fn open_campaign_dialog_with_callback(callback: impl Into<Callback<Option<PathBuf>>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(open_campaign_dialog(), callback.into()));
+}
+
+pub async fn open_csv_dialog() -> Result<Option<PathBuf>, Error> {
+    let args = OpenDialogOptions {
+        default_path: Some(dirs::home_dir().unwrap_or(PathBuf::from("/home")).to_string_lossy().to_string()),
+        directory: false,
+        multiple: false,
+        recursive: false,
+        filters: get_csv_filters()
+    };
+
+    let args_value = serde_wasm_bindgen::to_value(&args).map_err(Error::SerdeWasmBindgenError)?;
+    let result = serde_wasm_bindgen::from_value(open(args_value).await).map_err(Error::SerdeWasmBindgenError)?;
+
+    Ok(result)
+}
+
+pub fn open_csv_dialog_with_callback(callback: impl Into<Callback<Option<PathBuf>>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(open_csv_dialog(), callback.into()));
+}
+
+pub async fn save_csv_dialog() -> Result<Option<PathBuf>, Error> {
+    let args = SaveDialogOptions {
+        default_path: Some(dirs::home_dir().unwrap_or(PathBuf::from("/home")).to_string_lossy().to_string()),
+        filters: get_csv_filters()
+    };
+
+    let args_value = serde_wasm_bindgen::to_value(&args).map_err(Error::SerdeWasmBindgenError)?;
+    let result = serde_wasm_bindgen::from_value(save(args_value).await).map_err(Error::SerdeWasmBindgenError)?;
+
+    Ok(result)
+}
+
+pub fn save_csv_dialog_with_callback(callback: impl Into<Callback<Option<PathBuf>>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(save_csv_dialog(), callback.into()));
+}
+
+async fn get_encounter_default_path() -> String {
+    let last_directory = match get_last_directory().await {
+        Ok(last_directory) => last_directory,
+        Err(err) => {
+            log::warn!("Could not get the last used directory: {}", err);
+            None
+        }
+    };
+
+    last_directory
+        .unwrap_or(dirs::home_dir().unwrap_or(PathBuf::from("/home")))
+        .to_string_lossy()
+        .to_string()
+}
+
 fn get_encounter_filters() -> Option<Vec<ExtensionFilter>> {
     Some(vec![
-        ExtensionFilter::new("Encounter", vec!["enc", "encounter"])
+        ExtensionFilter::new("Encounter", vec!["enc", "encounter", "json"]),
+        ExtensionFilter::new("Encounter (TOML)", vec!["toml"])
+    ])
+}
+
+fn get_csv_filters() -> Option<Vec<ExtensionFilter>> {
+    Some(vec![
+        ExtensionFilter::new("CSV", vec!["csv"])
     ])
 }
 
+/// Invokes a command that acts on the current encounter, adding the encounter's ID to the arguments.
+async fn invoke_encounter(cmd: &str, args: JsValue) -> Result<JsValue, JsValue> {
+    let args = if args.is_object() { args } else { js_sys::Object::new().into() };
+    js_sys::Reflect::set(&args, &JsValue::from_str("encounterId"), &JsValue::from_str(&current_encounter().to_string()))?;
+    invoke(cmd, args).await
+}
+
 fn js_to_error(value: JsValue) -> Error {
     match serde_wasm_bindgen::from_value::<BackendError>(value) {
         Ok(err) => Error::BackendError(err),
@@ -333,6 +1081,9 @@ extern "C" {
     #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "tauri"], js_name = "invoke", catch)]
     async fn invoke_no_args(cmd: &str) -> Result<JsValue, JsValue>;
 
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "event"])]
+    async fn listen(event: &str, handler: &Closure<dyn FnMut(JsValue)>) -> JsValue;
+
     #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "dialog"])]
     async fn open(args: JsValue) -> JsValue;
 
@@ -1,13 +1,27 @@
+pub mod campaign_hook;
 pub mod creatures_hook;
 pub mod conflicts_hook;
 pub mod drag_and_drop_hook;
+pub mod encounter_hook;
+pub mod encounter_tabs_hook;
 pub mod general_hooks;
+pub mod initiative_mode_hook;
+pub mod keyboard_hook;
+pub mod recent_files_hook;
+pub mod settings_hook;
 pub mod sr_state_hook;
 
 pub mod prelude {
+    pub use crate::hooks::campaign_hook::*;
     pub use crate::hooks::creatures_hook::*;
     pub use crate::hooks::conflicts_hook::*;
     pub use crate::hooks::drag_and_drop_hook::*;
+    pub use crate::hooks::encounter_hook::*;
+    pub use crate::hooks::encounter_tabs_hook::*;
     pub use crate::hooks::general_hooks::*;
+    pub use crate::hooks::initiative_mode_hook::*;
+    pub use crate::hooks::keyboard_hook::*;
+    pub use crate::hooks::recent_files_hook::*;
+    pub use crate::hooks::settings_hook::*;
     pub use crate::hooks::sr_state_hook::*;
 }
\ No newline at end of file
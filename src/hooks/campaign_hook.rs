@@ -0,0 +1,53 @@
+use std::ops::Deref;
+
+use common_data_lib::campaign::CampaignWorkspace;
+use yew::prelude::*;
+
+use crate::glue::get_campaign_with_callback;
+
+#[derive(Debug, Clone)]
+pub struct UseCampaignHandle {
+    campaign: UseStateHandle<Option<CampaignWorkspace>>,
+    force_update_state: UseStateHandle<bool>
+}
+
+impl PartialEq for UseCampaignHandle {
+    fn eq(&self, other: &Self) -> bool {
+        self.campaign == other.campaign
+    }
+}
+
+impl Deref for UseCampaignHandle {
+    type Target = Option<CampaignWorkspace>;
+
+    fn deref(&self) -> &Self::Target {
+        self.campaign.deref()
+    }
+}
+
+impl UseCampaignHandle {
+    pub fn update(&self) {
+        self.force_update_state.set(!*self.force_update_state)
+    }
+
+    pub fn set(&self, campaign: Option<CampaignWorkspace>) {
+        self.campaign.set(campaign);
+    }
+}
+
+#[hook]
+pub fn use_campaign() -> UseCampaignHandle {
+    let campaign = use_state_eq(|| None);
+    let force_update_state = use_state_eq(|| false);
+
+    use_effect_with_deps({
+        let campaign = campaign.clone();
+        move |_| {
+            get_campaign_with_callback(Callback::from(move |value| {
+                campaign.set(value);
+            }));
+        }
+    }, force_update_state.clone());
+
+    UseCampaignHandle { campaign, force_update_state }
+}
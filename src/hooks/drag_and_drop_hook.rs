@@ -1,8 +1,15 @@
-use std::rc::Rc;
+use std::{cmp::Ordering, rc::Rc};
 
-use web_sys::Element;
+use uuid::Uuid;
+use web_sys::{Element, ScrollIntoViewOptions, ScrollLogicalPosition};
 use yew::prelude::*;
 
+const DROP_TARGET_ATTRIBUTE: &str = "data-drop-target";
+/// How close to the edge of the scroll container, in pixels, a drag has to be before the list scrolls.
+const AUTO_SCROLL_EDGE: f64 = 40.0;
+/// The most the list scrolls by for each drag event.
+const AUTO_SCROLL_STEP: f64 = 15.0;
+
 #[derive(Debug, Clone)]
 enum DragAndDropAction {
     OnDragStart(usize, DragEvent),
@@ -11,6 +18,11 @@ enum DragAndDropAction {
     OnDragLeave(usize, DragEvent),
     OnDragEnd(usize, DragEvent),
     OnDrop(usize, DragEvent),
+    OnPointerDown(usize, PointerEvent),
+    OnPointerMove(PointerEvent),
+    OnPointerUp,
+    OnPointerCancel,
+    OnKeyDown(usize, usize, KeyboardEvent),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -19,12 +31,22 @@ pub struct DropArguments {
     pub target_index: usize,
 }
 
+/// How the current drag was started, for drags that don't use the HTML5 drag events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DragInput {
+    Pointer,
+    Keyboard
+}
+
 #[derive(Debug, Clone, PartialEq)]
 struct DragAndDropReducer {
-    parent_ref: Option<NodeRef>,
+    scroll_ref: Option<NodeRef>,
+    list_id: Uuid,
     dragging_index: Option<usize>,
     target_index: Option<usize>,
     mouse_position: Option<(f64, f64)>,
+    input: Option<DragInput>,
+    announcement: String,
     on_drop_callback: Callback<DropArguments>
 }
 
@@ -36,37 +58,53 @@ impl Reducible for DragAndDropReducer {
             DragAndDropAction::OnDragStart(idx, _) => self.with_dragging_index(Some(idx)),
             DragAndDropAction::OnDragOver(_, e) => {
                 e.prevent_default();
+                self.auto_scroll(e.client_y());
 
-                if let Some(parent_ref) = &self.parent_ref {
-                    if let Some(element) = parent_ref.cast::<Element>() {
-                        let rect = element.get_bounding_client_rect();
-                        let x = (e.client_x() as f64) - rect.left();
-                        let y = (e.client_y() as f64) - rect.top();
-
-                        return self.with_mouse_position(Some((x, y)));
-                    }
+                match self.get_relative_position(e.client_x(), e.client_y()) {
+                    Some(pos) => self.with_mouse_position(Some(pos)),
+                    None => self
                 }
-
-                self
             },
             DragAndDropAction::OnDragEnter(idx, _) => self.with_target_index(Some(idx)),
             DragAndDropAction::OnDragLeave(_, _) => self.with_mouse_position(None),
             DragAndDropAction::OnDragEnd(_, _) => self.cleared(),
-            DragAndDropAction::OnDrop(_, _) => {
-                let dragging_index = match self.dragging_index {
-                    Some(idx) => idx,
-                    None => return self
-                };
+            DragAndDropAction::OnDrop(_, _) => self.dropped(),
+            DragAndDropAction::OnPointerDown(idx, e) => {
+                // Mice use the HTML5 drag events, which also show the browser's drag preview.
+                if e.pointer_type() == "mouse" || self.input.is_some() {
+                    return self;
+                }
 
-                let target_index = match self.target_index {
-                    Some(idx) => idx,
-                    None => return self
-                };
+                e.prevent_default();
+                self.picked_up(idx, DragInput::Pointer, String::new())
+            },
+            DragAndDropAction::OnPointerMove(e) => {
+                if self.input != Some(DragInput::Pointer) {
+                    return self;
+                }
 
-                self.on_drop_callback.emit(DropArguments { dragging_index, target_index });
+                e.prevent_default();
+                self.auto_scroll(e.client_y());
+
+                let target_index = self.get_target_at(e.client_x(), e.client_y()).or(self.target_index);
+                let mouse_position = self.get_relative_position(e.client_x(), e.client_y());
+                self.with_target_index(target_index).with_mouse_position(mouse_position)
+            },
+            DragAndDropAction::OnPointerUp => {
+                if self.input != Some(DragInput::Pointer) {
+                    return self;
+                }
+
+                self.dropped()
+            },
+            DragAndDropAction::OnPointerCancel => {
+                if self.input != Some(DragInput::Pointer) {
+                    return self;
+                }
 
                 self.cleared()
-            }
+            },
+            DragAndDropAction::OnKeyDown(idx, item_count, e) => self.handle_key(idx, item_count, e),
         }
     }
 }
@@ -74,62 +112,183 @@ impl Reducible for DragAndDropReducer {
 impl DragAndDropReducer {
     fn new(on_drop_callback: Callback<DropArguments>) -> Self {
         Self {
-            parent_ref: None,
+            scroll_ref: None,
+            list_id: Uuid::new_v4(),
             dragging_index: None,
             target_index: None,
             mouse_position: None,
+            input: None,
+            announcement: String::new(),
             on_drop_callback
         }
     }
 
-    fn new_with_mouse_position(node_ref: NodeRef, on_drop_callback: Callback<DropArguments>) -> Self {
+    fn new_with_scroll(scroll_ref: NodeRef, on_drop_callback: Callback<DropArguments>) -> Self {
         Self {
-            parent_ref: Some(node_ref),
-            dragging_index: None,
-            target_index: None,
-            mouse_position: None,
-            on_drop_callback
+            scroll_ref: Some(scroll_ref),
+            ..Self::new(on_drop_callback)
         }
     }
 
+    fn handle_key(self: Rc<Self>, idx: usize, item_count: usize, e: KeyboardEvent) -> Rc<Self> {
+        let key = e.key();
+        let (dragging_index, target_index) = match (self.input, self.dragging_index, self.target_index) {
+            (Some(DragInput::Keyboard), Some(dragging_index), Some(target_index)) => (dragging_index, target_index),
+            _ => {
+                if key != " " && key != "Enter" {
+                    return self;
+                }
+
+                e.prevent_default();
+                e.stop_propagation();
+                let announcement = format!("Picked up item {} of {}. Use the up and down arrow keys to move it, space to drop it or escape to cancel.", idx + 1, item_count);
+                return self.picked_up(idx, DragInput::Keyboard, announcement);
+            }
+        };
+
+        let last_index = item_count.saturating_sub(1);
+        let next = match key.as_str() {
+            "ArrowUp" => {
+                let target_index = target_index.saturating_sub(1);
+                self.scroll_to_item(target_index);
+                self.with_target_index(Some(target_index))
+                    .with_announcement(format!("Moved to position {} of {}.", target_index + 1, item_count))
+            },
+            "ArrowDown" => {
+                let target_index = (target_index + 1).min(last_index);
+                self.scroll_to_item(target_index);
+                self.with_target_index(Some(target_index))
+                    .with_announcement(format!("Moved to position {} of {}.", target_index + 1, item_count))
+            },
+            " " | "Enter" => self.dropped()
+                .with_announcement(format!("Dropped item at position {} of {}.", target_index + 1, item_count)),
+            "Escape" => self.cleared()
+                .with_announcement(format!("Cancelled reordering. The item is back at position {} of {}.", dragging_index + 1, item_count)),
+            _ => return self
+        };
+
+        e.prevent_default();
+        e.stop_propagation();
+
+        next
+    }
+
+    /// The position of a point within the scroll container's content, so it stays put as the list scrolls.
+    fn get_relative_position(&self, client_x: i32, client_y: i32) -> Option<(f64, f64)> {
+        let element = self.scroll_ref.as_ref()?.cast::<Element>()?;
+        let rect = element.get_bounding_client_rect();
+
+        Some((
+            (client_x as f64) - rect.left() + element.scroll_left() as f64,
+            (client_y as f64) - rect.top() + element.scroll_top() as f64
+        ))
+    }
+
+    /// Scrolls the list when a drag gets close to the top or bottom of it, faster the closer it gets.
+    fn auto_scroll(&self, client_y: i32) {
+        let element = match self.scroll_ref.as_ref().and_then(|r| r.cast::<Element>()) {
+            Some(element) => element,
+            None => return
+        };
+
+        let rect = element.get_bounding_client_rect();
+        let y = client_y as f64;
+        let top_distance = y - rect.top();
+        let bottom_distance = rect.bottom() - y;
+
+        let step = if top_distance < AUTO_SCROLL_EDGE {
+            -AUTO_SCROLL_STEP * (1.0 - top_distance.max(0.0) / AUTO_SCROLL_EDGE)
+        } else if bottom_distance < AUTO_SCROLL_EDGE {
+            AUTO_SCROLL_STEP * (1.0 - bottom_distance.max(0.0) / AUTO_SCROLL_EDGE)
+        } else {
+            return;
+        };
+
+        element.set_scroll_top(element.scroll_top() + step.round() as i32);
+    }
+
+    /// Keeps the item a keyboard drag is over in view.
+    fn scroll_to_item(&self, idx: usize) {
+        let selector = format!("[{}='{}:{}']", DROP_TARGET_ATTRIBUTE, self.list_id, idx);
+        let element = web_sys::window()
+            .and_then(|window| window.document())
+            .and_then(|document| document.query_selector(&selector).ok().flatten());
+
+        if let Some(element) = element {
+            let options = ScrollIntoViewOptions::new();
+            options.set_block(ScrollLogicalPosition::Nearest);
+            element.scroll_into_view_with_scroll_into_view_options(&options);
+        }
+    }
+
+    /// Finds the index of this list's drop target under a point on the screen.
+    fn get_target_at(&self, client_x: i32, client_y: i32) -> Option<usize> {
+        let document = web_sys::window()?.document()?;
+        let element = document.element_from_point(client_x as f32, client_y as f32)?;
+        let prefix = format!("{}:", self.list_id);
+        let target = element.closest(&format!("[{}^='{}']", DROP_TARGET_ATTRIBUTE, prefix)).ok()??;
+
+        target.get_attribute(DROP_TARGET_ATTRIBUTE)?
+            .strip_prefix(&prefix)?
+            .parse()
+            .ok()
+    }
+
+    fn picked_up(self: Rc<Self>, idx: usize, input: DragInput, announcement: String) -> Rc<Self> {
+        let mut new = (*self).clone();
+        new.dragging_index = Some(idx);
+        new.target_index = Some(idx);
+        new.mouse_position = None;
+        new.input = Some(input);
+        new.announcement = announcement;
+
+        Rc::new(new)
+    }
+
+    fn dropped(self: Rc<Self>) -> Rc<Self> {
+        if let (Some(dragging_index), Some(target_index)) = (self.dragging_index, self.target_index) {
+            self.on_drop_callback.emit(DropArguments { dragging_index, target_index });
+        }
+
+        self.cleared()
+    }
+
     fn cleared(self: Rc<Self>) -> Rc<Self> {
-        Rc::new(Self {
-            parent_ref: self.parent_ref.clone(),
-            dragging_index: None,
-            target_index: None,
-            mouse_position: None,
-            on_drop_callback: self.on_drop_callback.clone()
-        })
+        let mut new = (*self).clone();
+        new.dragging_index = None;
+        new.target_index = None;
+        new.mouse_position = None;
+        new.input = None;
+
+        Rc::new(new)
     }
 
     fn with_dragging_index(self: Rc<Self>, index: Option<usize>) -> Rc<Self> {
-        Rc::new(Self {
-            parent_ref: self.parent_ref.clone(),
-            dragging_index: index,
-            target_index: self.target_index,
-            mouse_position: self.mouse_position,
-            on_drop_callback: self.on_drop_callback.clone()
-        })
+        let mut new = (*self).clone();
+        new.dragging_index = index;
+
+        Rc::new(new)
     }
 
     fn with_target_index(self: Rc<Self>, index: Option<usize>) -> Rc<Self> {
-        Rc::new(Self {
-            parent_ref: self.parent_ref.clone(),
-            dragging_index: self.dragging_index,
-            target_index: index,
-            mouse_position: self.mouse_position,
-            on_drop_callback: self.on_drop_callback.clone()
-        })
+        let mut new = (*self).clone();
+        new.target_index = index;
+
+        Rc::new(new)
     }
 
     fn with_mouse_position(self: Rc<Self>, pos: Option<(f64, f64)>) -> Rc<Self> {
-        Rc::new(Self {
-            parent_ref: self.parent_ref.clone(),
-            dragging_index: self.dragging_index,
-            target_index: self.target_index,
-            mouse_position: pos,
-            on_drop_callback: self.on_drop_callback.clone()
-        })
+        let mut new = (*self).clone();
+        new.mouse_position = pos;
+
+        Rc::new(new)
+    }
+
+    fn with_announcement(self: Rc<Self>, announcement: String) -> Rc<Self> {
+        let mut new = (*self).clone();
+        new.announcement = announcement;
+
+        Rc::new(new)
     }
 }
 
@@ -143,6 +302,16 @@ pub struct DragAndDropCallbacks {
     pub on_drop: Callback<DragEvent>,
 }
 
+/// Callbacks for a drag handle, which lets touch and keyboard users reorder items.
+#[derive(Clone)]
+pub struct DragHandleCallbacks {
+    pub on_pointer_down: Callback<PointerEvent>,
+    pub on_pointer_move: Callback<PointerEvent>,
+    pub on_pointer_up: Callback<PointerEvent>,
+    pub on_pointer_cancel: Callback<PointerEvent>,
+    pub on_key_down: Callback<KeyboardEvent>,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct UseDragAndDropHandle {
     reducer: UseReducerHandle<DragAndDropReducer>
@@ -165,14 +334,52 @@ impl UseDragAndDropHandle {
     }
 
     pub fn is_target_index(&self, idx: usize) -> bool {
-        match self.reducer.dragging_index {
-            Some(dragging_index) => dragging_index == idx,
+        match self.reducer.target_index {
+            Some(target_index) => target_index == idx,
             None => false
         }
     }
 
-    pub fn mouse_position(&self) -> Option<(f64, f64)> {
-        self.reducer.mouse_position
+    /// The gap between items that the dragged item will be dropped into, where 0 is before the first item.
+    pub fn insertion_index(&self) -> Option<usize> {
+        let dragging_index = self.reducer.dragging_index?;
+        let target_index = self.reducer.target_index?;
+
+        match target_index.cmp(&dragging_index) {
+            Ordering::Less => Some(target_index),
+            Ordering::Greater => Some(target_index + 1),
+            Ordering::Equal => None
+        }
+    }
+
+    /// The classes that show the item being dragged and the line where it will be dropped,
+    /// moving the items either side of the line apart.
+    pub fn item_classes(&self, idx: usize, item_count: usize) -> Classes {
+        let insertion_index = self.insertion_index();
+        let is_last = idx + 1 == item_count;
+
+        classes!(
+            self.is_dragging_index(idx).then_some("dragging"),
+            (insertion_index == Some(idx)).then_some("drop-before"),
+            (is_last && insertion_index == Some(item_count)).then_some("drop-after"),
+            (!is_last && insertion_index == Some(idx + 1)).then_some("shift-up")
+        )
+    }
+
+    /// Where to show a preview of the dragged item within the scroll container. Only touch and pen drags have one,
+    /// as mouse drags already show the browser's drag image.
+    pub fn preview_position(&self) -> Option<(f64, f64)> {
+        self.reducer.mouse_position.filter(|_| self.reducer.input == Some(DragInput::Pointer))
+    }
+
+    /// Describes the latest keyboard move, for reading out from an ARIA live region.
+    pub fn announcement(&self) -> &str {
+        &self.reducer.announcement
+    }
+
+    /// The value of the `data-drop-target` attribute that lets touch drags find the item at `idx`.
+    pub fn drop_target_id(&self, idx: usize) -> String {
+        format!("{}:{}", self.reducer.list_id, idx)
     }
 
     pub fn callbacks(&self, idx: usize) -> DragAndDropCallbacks {
@@ -186,6 +393,16 @@ impl UseDragAndDropHandle {
         }
     }
 
+    pub fn handle_callbacks(&self, idx: usize, item_count: usize) -> DragHandleCallbacks {
+        DragHandleCallbacks {
+            on_pointer_down: self.on_pointer_down(idx),
+            on_pointer_move: self.on_pointer_move(),
+            on_pointer_up: self.on_pointer_up(),
+            on_pointer_cancel: self.on_pointer_cancel(),
+            on_key_down: self.on_key_down(idx, item_count)
+        }
+    }
+
     pub fn on_drag_start(&self, idx: usize) -> Callback<DragEvent> {
         let reducer = self.reducer.clone();
         Callback::from(move |e| {
@@ -227,6 +444,41 @@ impl UseDragAndDropHandle {
             reducer.dispatch(DragAndDropAction::OnDrop(idx, e));
         })
     }
+
+    pub fn on_pointer_down(&self, idx: usize) -> Callback<PointerEvent> {
+        let reducer = self.reducer.clone();
+        Callback::from(move |e| {
+            reducer.dispatch(DragAndDropAction::OnPointerDown(idx, e));
+        })
+    }
+
+    pub fn on_pointer_move(&self) -> Callback<PointerEvent> {
+        let reducer = self.reducer.clone();
+        Callback::from(move |e| {
+            reducer.dispatch(DragAndDropAction::OnPointerMove(e));
+        })
+    }
+
+    pub fn on_pointer_up(&self) -> Callback<PointerEvent> {
+        let reducer = self.reducer.clone();
+        Callback::from(move |_| {
+            reducer.dispatch(DragAndDropAction::OnPointerUp);
+        })
+    }
+
+    pub fn on_pointer_cancel(&self) -> Callback<PointerEvent> {
+        let reducer = self.reducer.clone();
+        Callback::from(move |_| {
+            reducer.dispatch(DragAndDropAction::OnPointerCancel);
+        })
+    }
+
+    pub fn on_key_down(&self, idx: usize, item_count: usize) -> Callback<KeyboardEvent> {
+        let reducer = self.reducer.clone();
+        Callback::from(move |e| {
+            reducer.dispatch(DragAndDropAction::OnKeyDown(idx, item_count, e));
+        })
+    }
 }
 
 #[hook]
@@ -237,8 +489,8 @@ pub fn use_drag_and_drop(callback: impl Into<Callback<DropArguments>>) -> UseDra
 }
 
 #[hook]
-pub fn use_drag_and_drop_with_mouse(node: NodeRef, callback: impl Into<Callback<DropArguments>>) -> UseDragAndDropHandle {
+pub fn use_drag_and_drop_with_scroll(scroll_container: NodeRef, callback: impl Into<Callback<DropArguments>>) -> UseDragAndDropHandle {
     let callback = callback.into();
-    let reducer = use_reducer(move || DragAndDropReducer::new_with_mouse_position(node, callback));
+    let reducer = use_reducer(move || DragAndDropReducer::new_with_scroll(scroll_container, callback));
     UseDragAndDropHandle { reducer }
-}
\ No newline at end of file
+}
@@ -0,0 +1,50 @@
+use std::ops::Deref;
+
+use common_data_lib::encounter::EncounterView;
+use yew::prelude::*;
+
+use crate::glue::get_active_encounter_with_callback;
+
+#[derive(Debug, Clone)]
+pub struct UseActiveEncounterHandle {
+    encounter: UseStateHandle<EncounterView>,
+    force_update_state: UseStateHandle<bool>
+}
+
+impl PartialEq for UseActiveEncounterHandle {
+    fn eq(&self, other: &Self) -> bool {
+        self.encounter == other.encounter
+    }
+}
+
+impl Deref for UseActiveEncounterHandle {
+    type Target = EncounterView;
+
+    fn deref(&self) -> &Self::Target {
+        self.encounter.deref()
+    }
+}
+
+impl UseActiveEncounterHandle {
+    pub fn update(&self) {
+        self.force_update_state.set(!*self.force_update_state)
+    }
+}
+
+#[hook]
+pub fn use_active_encounter() -> UseActiveEncounterHandle {
+    let encounter = use_state_eq(EncounterView::default);
+    let force_update_state = use_state_eq(|| false);
+
+    use_effect_with_deps({
+        let encounter_state = encounter.clone();
+        move |_| {
+            log::info!("Getting encounter");
+            get_active_encounter_with_callback(Callback::from(move |encounter| {
+                encounter_state.set(encounter);
+            }));
+        }
+    }, force_update_state.clone());
+
+    UseActiveEncounterHandle { encounter, force_update_state }
+}
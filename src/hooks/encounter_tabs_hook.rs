@@ -0,0 +1,103 @@
+use std::ops::Deref;
+
+use common_data_lib::document::EncounterTabs;
+use uuid::Uuid;
+use yew::prelude::*;
+
+use crate::glue::{list_encounters_with_callback, set_current_encounter, switch_encounter_with_callback, create_encounter_with_callback, duplicate_encounter_with_callback, rename_encounter_with_callback, close_encounter_with_callback, listen_for_encounters_changed};
+
+#[derive(Debug, Clone)]
+pub struct UseEncounterTabsHandle {
+    tabs: UseStateHandle<Option<EncounterTabs>>,
+    force_update_state: UseStateHandle<bool>
+}
+
+impl PartialEq for UseEncounterTabsHandle {
+    fn eq(&self, other: &Self) -> bool {
+        self.tabs == other.tabs
+    }
+}
+
+impl Deref for UseEncounterTabsHandle {
+    type Target = Option<EncounterTabs>;
+
+    fn deref(&self) -> &Self::Target {
+        self.tabs.deref()
+    }
+}
+
+impl UseEncounterTabsHandle {
+    pub fn update(&self) {
+        self.force_update_state.set(!*self.force_update_state)
+    }
+
+    pub fn current(&self) -> Option<Uuid> {
+        self.tabs.as_ref().map(|tabs| tabs.current)
+    }
+
+    pub fn switch(&self, id: Uuid) {
+        let handle = self.clone();
+        switch_encounter_with_callback(id, move |_| {
+            handle.update();
+        });
+    }
+
+    pub fn create(&self) {
+        let handle = self.clone();
+        create_encounter_with_callback(move |_| {
+            handle.update();
+        });
+    }
+
+    pub fn duplicate(&self, id: Uuid) {
+        let handle = self.clone();
+        duplicate_encounter_with_callback(id, move |_| {
+            handle.update();
+        });
+    }
+
+    pub fn rename(&self, id: Uuid, name: String) {
+        let handle = self.clone();
+        rename_encounter_with_callback(id, name, move |_| {
+            handle.update();
+        });
+    }
+
+    pub fn close(&self, id: Uuid) {
+        let handle = self.clone();
+        close_encounter_with_callback(id, move |_| {
+            handle.update();
+        });
+    }
+}
+
+/// The open encounters. Switching tabs changes the encounter that every other command acts on.
+#[hook]
+pub fn use_encounter_tabs() -> UseEncounterTabsHandle {
+    let tabs = use_state_eq(|| None);
+    let force_update_state = use_state_eq(|| false);
+
+    let fetch_tabs = {
+        let tabs_state = tabs.clone();
+        Callback::from(move |_| {
+            let tabs_state = tabs_state.clone();
+            list_encounters_with_callback(move |tabs: EncounterTabs| {
+                set_current_encounter(tabs.current);
+                tabs_state.set(Some(tabs));
+            });
+        })
+    };
+
+    use_effect_with_deps({
+        let fetch_tabs = fetch_tabs.clone();
+        move |_| {
+            listen_for_encounters_changed(fetch_tabs);
+        }
+    }, ());
+
+    use_effect_with_deps(move |_| {
+        fetch_tabs.emit(());
+    }, force_update_state.clone());
+
+    UseEncounterTabsHandle { tabs, force_update_state }
+}
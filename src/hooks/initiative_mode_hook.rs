@@ -0,0 +1,56 @@
+use std::ops::Deref;
+
+use common_data_lib::initiative::InitiativeMode;
+use yew::prelude::*;
+
+use crate::glue::{get_initiative_mode_with_callback, set_initiative_mode_with_callback};
+
+#[derive(Debug, Clone)]
+pub struct UseInitiativeModeHandle {
+    mode: UseStateHandle<InitiativeMode>,
+    force_update_state: UseStateHandle<bool>
+}
+
+impl PartialEq for UseInitiativeModeHandle {
+    fn eq(&self, other: &Self) -> bool {
+        self.mode == other.mode
+    }
+}
+
+impl Deref for UseInitiativeModeHandle {
+    type Target = InitiativeMode;
+
+    fn deref(&self) -> &Self::Target {
+        self.mode.deref()
+    }
+}
+
+impl UseInitiativeModeHandle {
+    pub fn update(&self) {
+        self.force_update_state.set(!*self.force_update_state)
+    }
+
+    pub fn set(&self, mode: InitiativeMode) {
+        let handle = self.clone();
+        set_initiative_mode_with_callback(mode, move |_| {
+            handle.update();
+        });
+    }
+}
+
+#[hook]
+pub fn use_initiative_mode() -> UseInitiativeModeHandle {
+    let mode = use_state_eq(InitiativeMode::default);
+    let force_update_state = use_state_eq(|| false);
+
+    use_effect_with_deps({
+        let mode_state = mode.clone();
+        move |_| {
+            get_initiative_mode_with_callback(Callback::from(move |mode| {
+                mode_state.set(mode);
+            }));
+        }
+    }, force_update_state.clone());
+
+    UseInitiativeModeHandle { mode, force_update_state }
+}
@@ -0,0 +1,59 @@
+use common_data_lib::keymap::{KeyAction, Keymap};
+use wasm_bindgen::{prelude::*, JsCast};
+use web_sys::{Element, KeyboardEvent};
+use yew::prelude::*;
+
+/// Listens for key presses anywhere in the window and calls `on_action` with the action they are bound to.
+/// Key presses are ignored while typing into a form field or editable text, and on a focused button, so shortcuts
+/// don't interfere with text entry or with Space and Enter pressing the button.
+#[hook]
+pub fn use_keyboard_shortcuts<F>(keymap: Keymap, enabled: bool, on_action: F) where F: Fn(KeyAction) + 'static {
+    let action_ref = use_mut_ref(|| None::<Box<dyn Fn(KeyAction)>>);
+    *action_ref.borrow_mut() = Some(Box::new(on_action));
+
+    use_effect_with_deps(move |(keymap, enabled)| {
+        let window = web_sys::window();
+        let listener = match (&window, *enabled) {
+            (Some(window), true) => {
+                let keymap = keymap.clone();
+                let listener = Closure::<dyn Fn(KeyboardEvent)>::new(move |e: KeyboardEvent| {
+                    if is_for_focused_control(&e) {
+                        return;
+                    }
+
+                    if let Some(action) = keymap.action_for(&e.key(), e.alt_key(), e.ctrl_key() || e.meta_key()) {
+                        e.prevent_default();
+                        if let Some(on_action) = &*action_ref.borrow() {
+                            on_action(action);
+                        }
+                    }
+                });
+
+                if let Err(err) = window.add_event_listener_with_callback("keydown", listener.as_ref().unchecked_ref()) {
+                    log::warn!("Unable to listen for key presses: {:?}", err);
+                }
+
+                Some(listener)
+            },
+            _ => None
+        };
+
+        move || {
+            if let (Some(window), Some(listener)) = (window, listener) {
+                if let Err(err) = window.remove_event_listener_with_callback("keydown", listener.as_ref().unchecked_ref()) {
+                    log::warn!("Unable to stop listening for key presses: {:?}", err);
+                }
+            }
+        }
+    }, (keymap, enabled));
+}
+
+/// Elements that handle key presses themselves, either for typing or for pressing them.
+const CONTROLS: &str = "input, textarea, select, button, [role=button], [contenteditable]:not([contenteditable=false])";
+
+fn is_for_focused_control(e: &KeyboardEvent) -> bool {
+    e.target()
+        .and_then(|target| target.dyn_into::<Element>().ok())
+        .and_then(|element| element.closest(CONTROLS).ok().flatten())
+        .is_some()
+}
@@ -0,0 +1,65 @@
+use std::ops::Deref;
+
+use common_data_lib::recent_files::{RecentFile, RecentFilesList};
+use yew::prelude::*;
+
+use crate::glue::get_recent_files_with_callback;
+
+#[derive(Debug, Clone)]
+pub struct UseRecentFilesHandle {
+    files: UseStateHandle<Vec<RecentFile>>,
+    missing: UseStateHandle<Vec<RecentFile>>,
+    force_update_state: UseStateHandle<bool>
+}
+
+impl PartialEq for UseRecentFilesHandle {
+    fn eq(&self, other: &Self) -> bool {
+        self.files == other.files && self.missing == other.missing
+    }
+}
+
+impl Deref for UseRecentFilesHandle {
+    type Target = Vec<RecentFile>;
+
+    fn deref(&self) -> &Self::Target {
+        self.files.deref()
+    }
+}
+
+impl UseRecentFilesHandle {
+    pub fn update(&self) {
+        self.force_update_state.set(!*self.force_update_state)
+    }
+
+    /// The files that were removed from the list the last time it was updated because they could not be found.
+    pub fn missing(&self) -> &[RecentFile] {
+        &self.missing
+    }
+
+    pub fn clear_missing(&self) {
+        self.missing.set(Vec::new());
+    }
+}
+
+#[hook]
+pub fn use_recent_files() -> UseRecentFilesHandle {
+    let files = use_state_eq(Vec::new);
+    let missing = use_state_eq(Vec::new);
+    let force_update_state = use_state_eq(|| false);
+
+    use_effect_with_deps({
+        let files = files.clone();
+        let missing = missing.clone();
+        move |_| {
+            log::info!("Getting recent files");
+            get_recent_files_with_callback(Callback::from(move |list: RecentFilesList| {
+                files.set(list.files);
+                if !list.missing.is_empty() {
+                    missing.set(list.missing);
+                }
+            }));
+        }
+    }, force_update_state.clone());
+
+    UseRecentFilesHandle { files, missing, force_update_state }
+}
@@ -0,0 +1,56 @@
+use std::ops::Deref;
+
+use common_data_lib::settings::Settings;
+use yew::prelude::*;
+
+use crate::glue::get_settings_with_callback;
+
+#[derive(Debug, Clone)]
+pub struct UseSettingsHandle {
+    settings: UseStateHandle<Settings>,
+    force_update_state: UseStateHandle<bool>
+}
+
+impl PartialEq for UseSettingsHandle {
+    fn eq(&self, other: &Self) -> bool {
+        self.settings == other.settings
+    }
+}
+
+impl Deref for UseSettingsHandle {
+    type Target = Settings;
+
+    fn deref(&self) -> &Self::Target {
+        self.settings.deref()
+    }
+}
+
+impl UseSettingsHandle {
+    /// Re-reads the settings from the backend, discarding any unsaved changes.
+    pub fn update(&self) {
+        self.force_update_state.set(!*self.force_update_state)
+    }
+
+    /// Changes the local copy of the settings without saving them.
+    pub fn set(&self, settings: Settings) {
+        self.settings.set(settings);
+    }
+}
+
+#[hook]
+pub fn use_settings() -> UseSettingsHandle {
+    let settings = use_state_eq(Settings::default);
+    let force_update_state = use_state_eq(|| false);
+
+    use_effect_with_deps({
+        let settings_state = settings.clone();
+        move |_| {
+            log::info!("Getting settings");
+            get_settings_with_callback(Callback::from(move |settings| {
+                settings_state.set(settings);
+            }));
+        }
+    }, force_update_state.clone());
+
+    UseSettingsHandle { settings, force_update_state }
+}
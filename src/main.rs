@@ -16,6 +16,10 @@ pub async fn emit_callback_if_ok<T, E: std::fmt::Display, F: Future<Output = Res
     };
 }
 
+pub async fn emit_result_callback<T, E, F: Future<Output = Result<T, E>>>(future: F, callback: Callback<Result<T, E>>) {
+    callback.emit(future.await);
+}
+
 #[derive(Debug)]
 pub enum Error {
     BackendError(common_data_lib::BackendError),
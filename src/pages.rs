@@ -1,3 +1,4 @@
 pub mod conflicts_page;
 pub mod encounter_page;
+pub mod settings_page;
 pub mod welcome_page;
\ No newline at end of file
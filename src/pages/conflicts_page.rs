@@ -1,6 +1,6 @@
 use yew::prelude::*;
 
-use crate::{app::AppPage, hooks::prelude::*, glue::{move_initiative_conflict_with_callback, finalize_initiative_order_with_callback}};
+use crate::{app::AppPage, hooks::prelude::*, glue::{move_initiative_conflict_with_callback, finalize_initiative_order_with_callback}, components::drag_handle::{DragHandle, DragAnnouncer, DragPreview}};
 
 #[derive(Debug, Clone, PartialEq, Properties)]
 pub struct ConflictsPageProps {
@@ -11,6 +11,7 @@ pub struct ConflictsPageProps {
 pub fn conflicts_page(props: &ConflictsPageProps) -> Html {
     let ConflictsPageProps { current_page } = props.clone();
     let conflicts = use_conflicts(current_page.clone());
+    let scroll_ref = use_node_ref();
 
     let back = {
         let current_page = current_page.clone();
@@ -35,7 +36,7 @@ pub fn conflicts_page(props: &ConflictsPageProps) -> Html {
         .map(|(idx, _)| {
             let conflicts = conflicts.clone();
             html! {
-                <ConflictGroupElement conflicts={conflicts} group_index={idx} />
+                <ConflictGroupElement conflicts={conflicts} group_index={idx} scroll_ref={scroll_ref.clone()} />
             }
         })
         .collect::<Html>();
@@ -43,8 +44,8 @@ pub fn conflicts_page(props: &ConflictsPageProps) -> Html {
     html! {
         <div class="flex-row stretch">
             <main class="flex-column no-scroll">
-                <div class="flex-grow-1 scroll-y">
-                    <p>{"Your encounter has some creatures that have the same initiative value. You will need to confirm which order these creatures will take their turns in. Drag and drop the creatures to reorder them."}</p>
+                <div class="flex-grow-1 scroll-y drag-container" ref={scroll_ref}>
+                    <p>{"Your encounter has some creatures that have the same initiative value. You will need to confirm which order these creatures will take their turns in. Drag and drop the creatures to reorder them, or focus a creature's handle and use space and the arrow keys."}</p>
                     {groups}
                 </div>
                 <div class="flex-row button-group">
@@ -59,12 +60,13 @@ pub fn conflicts_page(props: &ConflictsPageProps) -> Html {
 #[derive(Debug, Clone, PartialEq, Properties)]
 struct ConflictGroupElementProps {
     conflicts: UseConflictsHandle,
-    group_index: usize
+    group_index: usize,
+    scroll_ref: NodeRef
 }
 
 #[function_component(ConflictGroupElement)]
 fn conflict_group_element(props: &ConflictGroupElementProps) -> Html {
-    let ConflictGroupElementProps { conflicts, group_index } = props.clone();
+    let ConflictGroupElementProps { conflicts, group_index, scroll_ref } = props.clone();
     let is_open = use_state_eq(|| true);
     let toggle_open = {
         let is_open = is_open.clone();
@@ -74,7 +76,7 @@ fn conflict_group_element(props: &ConflictGroupElementProps) -> Html {
     };
 
     let conflict_group = &conflicts[group_index];
-    let drag_state = use_drag_and_drop({
+    let drag_state = use_drag_and_drop_with_scroll(scroll_ref, {
         let conflicts = conflicts.clone();
         move |args: DropArguments| {
             let conflicts = conflicts.clone();
@@ -84,24 +86,36 @@ fn conflict_group_element(props: &ConflictGroupElementProps) -> Html {
         }
     });
 
+    let item_count = conflict_group.creatures().len();
     let items = conflict_group.creatures()
         .iter()
         .enumerate()
         .map(|(idx, creature)| {
             let drag_state = drag_state.clone();
+            let item_classes = classes!("flex-row", drag_state.item_classes(idx, item_count));
 
             html! {
                 <tr>
-                    <td ondragenter={drag_state.on_drag_enter(idx)} ondragover={drag_state.on_drag_over(idx)} ondrop={drag_state.on_drop(idx)} draggable="false">
-                        <p ondragstart={drag_state.on_drag_start(idx)} ondragend={drag_state.on_drag_end(idx)} draggable="true">{creature.name()}</p>
+                    <td ondragenter={drag_state.on_drag_enter(idx)} ondragover={drag_state.on_drag_over(idx)} ondrop={drag_state.on_drop(idx)} draggable="false" data-drop-target={drag_state.drop_target_id(idx)}>
+                        <p class={item_classes} ondragstart={drag_state.on_drag_start(idx)} ondragend={drag_state.on_drag_end(idx)} draggable="true">
+                            <DragHandle drag_state={drag_state.clone()} index={idx} item_count={item_count} label={creature.name().to_string()} />
+                            <span class="flex-grow-1">{creature.name()}</span>
+                        </p>
                     </td>
                 </tr>
             }
         })
         .collect::<Html>();
 
+    let dragged_name = drag_state.dragging_index()
+        .and_then(|idx| conflict_group.creatures().get(idx))
+        .map(|creature| creature.name().to_string())
+        .unwrap_or_default();
+
     html! {
         <div class="conflict-accordion flex-column">
+            <DragAnnouncer drag_state={drag_state.clone()} />
+            <DragPreview drag_state={drag_state.clone()} label={dragged_name} />
             <button class="flex-row blank" onclick={toggle_open}>
                 <h3 class="flex-grow-1">{format!("Creatures with initiative {}", conflict_group.initiative())}</h3>
                 <h3>{"-"}</h3>
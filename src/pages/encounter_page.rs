@@ -1,8 +1,44 @@
+use std::collections::HashMap;
+
+use common_data_lib::{creatures::Creature, damage::parse_damage, BackendError, encounter::{ResumePosition, WaitReason}, initiative::InitiativeMode, keymap::KeyAction};
+use uuid::Uuid;
 use web_sys::HtmlInputElement;
 use yew::prelude::*;
 use yew_icons::{Icon, IconId};
 
-use crate::{app::AppPage, hooks::prelude::*, glue::{change_active_encounter_order_with_callback, add_creatures_to_active_encounter_with_callback, remove_from_active_encounter_with_callback}, components::modal::Modal};
+use crate::{app::AppPage, hooks::prelude::*, glue::*, components::{creature_notes::CreatureNotes, damage_types::DamageTypesEditor, drag_handle::{DragHandle, DragAnnouncer, DragPreview}, modal::Modal, name_input::NameInput, shortcut_cheat_sheet::ShortcutCheatSheet, stat_block::StatBlockView}, Error};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HitPointsMode {
+    Damage,
+    Heal,
+    Maximum
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct HitPointsEdit {
+    id: Uuid,
+    name: String,
+    mode: HitPointsMode
+}
+
+impl HitPointsEdit {
+    /// Creatures without hit points have to be given a maximum before they can be damaged or healed.
+    fn new(creature: &Creature, mode: HitPointsMode) -> Self {
+        Self {
+            id: creature.id(),
+            name: creature.name().to_string(),
+            mode: if creature.hit_points().is_some() { mode } else { HitPointsMode::Maximum }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RevealRequest {
+    id: Uuid,
+    name: String,
+    initiative: isize
+}
 
 #[derive(Debug, Clone, PartialEq, Properties)]
 pub struct EncounterPageProps {
@@ -12,15 +48,133 @@ pub struct EncounterPageProps {
 #[function_component(EncounterPage)]
 pub fn encounter_page(props: &EncounterPageProps) -> Html {
     let EncounterPageProps { current_page } = props.clone();
-    let creatures = use_encounter_creatures();
+    let encounter = use_active_encounter();
+    let settings = use_settings();
     let is_modal_open = use_state_eq(|| false);
-    let drag_state = use_drag_and_drop({
-        let creatures = creatures.clone();
+    let hit_points_edit = use_state_eq(|| None::<HitPointsEdit>);
+    let is_cheat_sheet_open = use_state_eq(|| false);
+    let reveal_request = use_state_eq(|| None::<RevealRequest>);
+    // The selection follows whoever's turn it is until another creature is picked.
+    let selected_index = use_state_eq(|| None::<usize>);
+    let selection = selected_index
+        .filter(|idx| *idx < encounter.len())
+        .unwrap_or(encounter.current_turn());
+
+    let scroll_ref = use_node_ref();
+    let drag_state = use_drag_and_drop_with_scroll(scroll_ref.clone(), {
+        let encounter = encounter.clone();
+        let selected_index = selected_index.clone();
         move |args: DropArguments| {
-            let creatures = creatures.clone();
+            let encounter = encounter.clone();
+            selected_index.set(None);
             change_active_encounter_order_with_callback(args.dragging_index, args.target_index, move |_| {
-                creatures.update();
+                encounter.update();
+            });
+        }
+    });
+
+    let open_hit_points_modal = {
+        let encounter = encounter.clone();
+        let hit_points_edit = hit_points_edit.clone();
+        Callback::from(move |(idx, mode): (usize, HitPointsMode)| {
+            if let Some(creature) = encounter.get_by_index(idx).and_then(|c| encounter.state(c.id())) {
+                hit_points_edit.set(Some(HitPointsEdit::new(creature, mode)));
+            }
+        })
+    };
+
+    // Hiding happens straight away, but revealing asks whether the creature should move to its initiative.
+    let toggle_hidden = {
+        let encounter = encounter.clone();
+        let reveal_request = reveal_request.clone();
+        Callback::from(move |idx: usize| {
+            let creature = match encounter.get_by_index(idx) {
+                Some(creature) => creature,
+                None => return
+            };
+
+            if encounter.state(creature.id()).unwrap_or(creature).hidden() {
+                reveal_request.set(Some(RevealRequest {
+                    id: creature.id(),
+                    name: creature.name().to_string(),
+                    initiative: creature.initiative()
+                }));
+            } else {
+                let encounter = encounter.clone();
+                set_creature_hidden_with_callback(creature.id(), true, false, move |_| {
+                    encounter.update();
+                });
+            }
+        })
+    };
+
+    let next = {
+        let encounter = encounter.clone();
+        let selected_index = selected_index.clone();
+        Callback::from(move |_: ()| {
+            let encounter = encounter.clone();
+            let selected_index = selected_index.clone();
+            next_turn_with_callback(move |_| {
+                selected_index.set(None);
+                encounter.update();
+            });
+        })
+    };
+
+    let previous = {
+        let encounter = encounter.clone();
+        let selected_index = selected_index.clone();
+        Callback::from(move |_: ()| {
+            let encounter = encounter.clone();
+            let selected_index = selected_index.clone();
+            previous_turn_with_callback(move |_| {
+                selected_index.set(None);
+                encounter.update();
+            });
+        })
+    };
+
+    let move_selected = {
+        let encounter = encounter.clone();
+        let selected_index = selected_index.clone();
+        Callback::from(move |target_index: usize| {
+            if selection == target_index || target_index >= encounter.len() {
+                return;
+            }
+
+            let encounter = encounter.clone();
+            let selected_index = selected_index.clone();
+            change_active_encounter_order_with_callback(selection, target_index, move |_| {
+                selected_index.set(Some(target_index));
+                encounter.update();
             });
+        })
+    };
+
+    let shortcuts_enabled = !*is_modal_open && hit_points_edit.is_none() && reveal_request.is_none() && !encounter.awaiting_initiative();
+    use_keyboard_shortcuts(settings.keymap().clone(), shortcuts_enabled, {
+        let encounter = encounter.clone();
+        let selected_index = selected_index.clone();
+        let is_cheat_sheet_open = is_cheat_sheet_open.clone();
+        let open_hit_points_modal = open_hit_points_modal.clone();
+        let next = next.clone();
+        let previous = previous.clone();
+        move |action: KeyAction| {
+            if *is_cheat_sheet_open && action != KeyAction::CheatSheet {
+                return;
+            }
+
+            match action {
+                KeyAction::NextTurn => next.emit(()),
+                KeyAction::PreviousTurn => previous.emit(()),
+                KeyAction::Damage => open_hit_points_modal.emit((selection, HitPointsMode::Damage)),
+                KeyAction::Heal => open_hit_points_modal.emit((selection, HitPointsMode::Heal)),
+                KeyAction::SelectPrevious => selected_index.set(Some(selection.saturating_sub(1))),
+                KeyAction::SelectNext => selected_index.set(Some((selection + 1).min(encounter.len().saturating_sub(1)))),
+                KeyAction::MoveUp => move_selected.emit(selection.saturating_sub(1)),
+                KeyAction::MoveDown => move_selected.emit(selection + 1),
+                KeyAction::CheatSheet => is_cheat_sheet_open.set(!*is_cheat_sheet_open),
+            }
         }
     });
 
@@ -31,6 +185,27 @@ pub fn encounter_page(props: &EncounterPageProps) -> Html {
         })
     };
 
+    let open_cheat_sheet = {
+        let is_cheat_sheet_open = is_cheat_sheet_open.clone();
+        Callback::from(move |_: MouseEvent| {
+            is_cheat_sheet_open.set(true);
+        })
+    };
+
+    let close_cheat_sheet = {
+        let is_cheat_sheet_open = is_cheat_sheet_open.clone();
+        Callback::from(move |_: ()| {
+            is_cheat_sheet_open.set(false);
+        })
+    };
+
+    let select = {
+        let selected_index = selected_index.clone();
+        Callback::from(move |idx: usize| {
+            selected_index.set(Some(idx));
+        })
+    };
+
     let finish = {
         let current_page = current_page.clone();
 
@@ -39,36 +214,64 @@ pub fn encounter_page(props: &EncounterPageProps) -> Html {
         })
     };
 
-    let creature_items = creatures.iter()
-        .enumerate()
-        .filter_map(|(idx, c)| {
-            if c.selected() {
-                let creatures = creatures.clone();
-                let drag_state = drag_state.clone();
-                Some(html! {
-                    <EncounterCreatureListing drag_state={drag_state} creatures={creatures} creature_index={idx} />
-                })  
-            } else {
-                None
+    let creature_items = (0..encounter.len())
+        .map(|idx| {
+            let encounter = encounter.clone();
+            let drag_state = drag_state.clone();
+            html! {
+                <EncounterCreatureListing
+                    drag_state={drag_state}
+                    encounter={encounter.clone()}
+                    creature_index={idx}
+                    is_active={idx == encounter.current_turn()}
+                    is_selected={idx == selection}
+                    on_select={select.clone()}
+                    on_toggle_hidden={toggle_hidden.clone()}
+                    on_edit_hit_points={open_hit_points_modal.clone()} />
             }
         })
         .collect::<Html>();
 
+    let dragged_name = drag_state.dragging_index()
+        .and_then(|idx| encounter.get_by_index(idx))
+        .map(|creature| creature.name().to_string())
+        .unwrap_or_default();
+
     html! {
         <>
-            <AddCreaturesModal creatures={creatures} is_open={is_modal_open} />
+            <DragAnnouncer drag_state={drag_state.clone()} />
+            <AddCreaturesModal encounter={encounter.clone()} is_open={is_modal_open} />
+            <HitPointsModal encounter={encounter.clone()} edit={hit_points_edit} />
+            <RevealModal encounter={encounter.clone()} request={reveal_request} />
+            if encounter.awaiting_initiative() && encounter.mode() == InitiativeMode::Cards {
+                <DealCardsModal encounter={encounter.clone()} />
+            } else if encounter.awaiting_initiative() {
+                <RoundInitiativeModal encounter={encounter.clone()} />
+            }
+            if *is_cheat_sheet_open {
+                <ShortcutCheatSheet keymap={settings.keymap().clone()} on_close={close_cheat_sheet} />
+            }
             <div class="flex-row stretch">
                 <main class="flex-column no-scroll">
-                    <div class="flex-grow-1 scroll-y">
+                    <div class="flex-row turn-controls">
+                        <button onclick={previous.reform(|_: MouseEvent| ())}>{"Previous turn"}</button>
+                        <p class="flex-grow-1 round-display">{format!("Round {}", encounter.round())}</p>
+                        <button onclick={next.reform(|_: MouseEvent| ())}>{"Next turn"}</button>
+                    </div>
+                    <div class="flex-grow-1 scroll-y drag-container" ref={scroll_ref}>
+                        <DragPreview drag_state={drag_state.clone()} label={dragged_name} />
                         <table class="encounter-table stretch-width">
                         {creature_items}
                         </ table>
                     </div>
+                    <WaitingTray encounter={encounter.clone()} />
                     <div class="flex-row button-group">
                         <button class="flex-grow-1" onclick={open_modal}>{"Add creatures"}</button>
+                        <button class="flex-grow-1" onclick={open_cheat_sheet}>{"Shortcuts"}</button>
                         <button class="flex-grow-1" onclick={finish}>{"Finish encounter"}</button>
                     </div>
                 </main>
+                <NotesPanel encounter={encounter.clone()} />
             </div>
         </>
     }
@@ -77,15 +280,25 @@ pub fn encounter_page(props: &EncounterPageProps) -> Html {
 #[derive(Debug, Clone, PartialEq, Properties)]
 struct EncounterCreatureListingProps {
     drag_state: UseDragAndDropHandle,
-    creatures: UseCreaturesHandle,
+    encounter: UseActiveEncounterHandle,
     creature_index: usize,
+    is_active: bool,
+    is_selected: bool,
+    on_select: Callback<usize>,
+    on_toggle_hidden: Callback<usize>,
+    on_edit_hit_points: Callback<(usize, HitPointsMode)>,
 }
 
 #[function_component(EncounterCreatureListing)]
 fn encounter_creature_listing(props: &EncounterCreatureListingProps) -> Html {
-    let EncounterCreatureListingProps { drag_state, creatures, creature_index } = props.clone();
+    let EncounterCreatureListingProps { drag_state, encounter, creature_index, is_active, is_selected, on_select, on_toggle_hidden, on_edit_hit_points } = props.clone();
     let hover_remove_state = use_sr_state_eq(false);
-    let creature = &creatures[creature_index];
+    let creature = match encounter.get_by_index(creature_index) {
+        Some(creature) => creature.clone(),
+        None => return html! {}
+    };
+    // Extra initiative slots show the hit points and visibility of the creature they belong to.
+    let state = encounter.state(creature.id()).cloned().unwrap_or_else(|| creature.clone());
 
     let DragAndDropCallbacks {
         on_drag_start,
@@ -97,17 +310,66 @@ fn encounter_creature_listing(props: &EncounterCreatureListingProps) -> Html {
     } = drag_state.callbacks(creature_index);
 
     let remove_creature = {
-        let creatures = creatures.clone();
+        let encounter = encounter.clone();
         let id = creature.id();
         Callback::from(move |_: MouseEvent| {
-            let creatures = creatures.clone();
+            let encounter = encounter.clone();
 
             remove_from_active_encounter_with_callback(id, move |_| {
-                creatures.update();
+                encounter.update();
+            });
+        })
+    };
+
+    let select = Callback::from(move |_: MouseEvent| {
+        on_select.emit(creature_index);
+    });
+
+    // Shift-clicking rolls the copy a new initiative and hit points rather than sharing the original's.
+    let duplicate = {
+        let encounter = encounter.clone();
+        let id = creature.id();
+        Callback::from(move |e: MouseEvent| {
+            e.stop_propagation();
+            let encounter = encounter.clone();
+            duplicate_creature_with_callback(id, e.shift_key(), move |_| {
+                encounter.update();
+            });
+        })
+    };
+
+    let on_renamed = {
+        let encounter = encounter.clone();
+        Callback::from(move |_| {
+            encounter.update();
+        })
+    };
+
+    let pass_turn = {
+        let encounter = encounter.clone();
+        let id = creature.id();
+        Callback::from(move |e: MouseEvent| {
+            e.stop_propagation();
+            let encounter = encounter.clone();
+            pass_turn_to_with_callback(id, move |_| {
+                encounter.update();
             });
         })
     };
 
+    // In modes like popcorn initiative, the active creature picks who goes next from those yet to act this round,
+    // or anyone at all once the round is over.
+    let can_take_turn = encounter.mode().rules().active_creature_chooses_next() && {
+        let active_id = encounter.active_creature().map(|c| c.id());
+        let round_over = encounter.creatures().all(|c| Some(c.id()) == active_id || encounter.has_acted(c.id()));
+        round_over || (!is_active && !encounter.has_acted(creature.id()))
+    };
+
+    let toggle_hidden = on_toggle_hidden.reform(move |_: MouseEvent| creature_index);
+    let damage = on_edit_hit_points.reform(move |_: MouseEvent| (creature_index, HitPointsMode::Damage));
+    let heal = on_edit_hit_points.reform(move |_: MouseEvent| (creature_index, HitPointsMode::Heal));
+    let set_maximum = on_edit_hit_points.reform(move |_: MouseEvent| (creature_index, HitPointsMode::Maximum));
+
     let on_mouse_over = {
         let hover_remove_state = hover_remove_state.clone();
         Callback::from(move |_: MouseEvent| {
@@ -122,11 +384,47 @@ fn encounter_creature_listing(props: &EncounterCreatureListingProps) -> Html {
         })
     };
 
+    let row_classes = classes!(
+        "flex-row",
+        is_active.then_some("active-turn"),
+        is_selected.then_some("selected-creature"),
+        state.hidden().then_some("hidden-creature"),
+        drag_state.item_classes(creature_index, encounter.len())
+    );
+
     html! {
         <tr>
-            <td ondragover={on_drag_over} ondragenter={on_drag_enter} ondrop={on_drop} draggable="false">
-                <div ondragend={on_drag_end} ondragstart={on_drag_start} class="flex-row" draggable="true">
-                    <p class="flex-grow-1">{creature.name()}</p>
+            <td ondragover={on_drag_over} ondragenter={on_drag_enter} ondrop={on_drop} draggable="false" data-drop-target={drag_state.drop_target_id(creature_index)}>
+                <div ondragend={on_drag_end} ondragstart={on_drag_start} onclick={select} class={row_classes} draggable="true" aria-current={is_active.then_some("true")}>
+                    <DragHandle drag_state={drag_state.clone()} index={creature_index} item_count={encounter.len()} label={creature.name().to_string()} />
+                    <NameInput id={creature.id()} name={creature.name().to_string()} on_renamed={on_renamed} />
+                    if let Some(card) = creature.card().filter(|_| encounter.mode() == InitiativeMode::Cards) {
+                        <p class="dealt-card">{card.to_string()}</p>
+                    }
+                    if creature.is_slot() {
+                        <p class="slot-label">{format!("Initiative {}", creature.initiative())}</p>
+                    }
+                    if can_take_turn {
+                        <button class="pass-turn" onclick={pass_turn}>{"Goes next"}</button>
+                    }
+                    {match state.hit_points() {
+                        Some(hit_points) => html! {
+                            <>
+                                <button class="blank hit-points-button" onclick={damage} title="Damage">{"-"}</button>
+                                <button class="blank hit-points" onclick={set_maximum} title="Set maximum hit points">{hit_points.to_string()}</button>
+                                <button class="blank hit-points-button" onclick={heal} title="Heal">{"+"}</button>
+                            </>
+                        },
+                        None => html! {
+                            <button class="blank hit-points" onclick={set_maximum}>{"Set HP"}</button>
+                        }
+                    }}
+                    <button class="blank" onclick={duplicate} title="Add another (shift-click to roll its initiative and hit points)">
+                        <Icon class="fill-color" icon_id={IconId::BootstrapFiles} width="15px" height="15px" />
+                    </button>
+                    <button class="blank" onclick={toggle_hidden} title={if state.hidden() { "Reveal" } else { "Hide from players" }}>
+                        <Icon class="fill-color" icon_id={if state.hidden() {IconId::BootstrapEyeSlash} else {IconId::BootstrapEye}} width="15px" height="15px" />
+                    </button>
                     <button class="blank" onclick={remove_creature} onmouseover={on_mouse_over} onmouseout={on_mouse_out}>
                         <Icon class="fill-color" icon_id={if *hover_remove_state {IconId::BootstrapDashCircleFill} else {IconId::BootstrapDashCircle}} width="15px" height="15px" />
                     </button>
@@ -136,34 +434,445 @@ fn encounter_creature_listing(props: &EncounterCreatureListingProps) -> Html {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Properties)]
+struct NotesPanelProps {
+    encounter: UseActiveEncounterHandle
+}
+
+/// Shows the stat block and notes of the creature whose turn it is, or the notes of every creature whose name or notes
+/// match a search.
+#[function_component(NotesPanel)]
+fn notes_panel(props: &NotesPanelProps) -> Html {
+    let NotesPanelProps { encounter } = props.clone();
+    let search = use_state_eq(String::new);
+
+    let update_search = {
+        let search = search.clone();
+        Callback::from(move |e: InputEvent| {
+            let target: HtmlInputElement = e.target_unchecked_into();
+            search.set(target.value());
+        })
+    };
+
+    let on_saved = {
+        let encounter = encounter.clone();
+        Callback::from(move |_| {
+            encounter.update();
+        })
+    };
+
+    // Notes belong to the creature a slot was made for, so each creature is only shown once.
+    let mut shown = Vec::<Creature>::new();
+    let candidates = encounter.creatures()
+        .chain(encounter.waiting().iter().filter_map(|w| encounter.get(w.id())))
+        .filter_map(|c| encounter.state(c.id()));
+    for creature in candidates {
+        if !shown.iter().any(|c| c.id() == creature.id()) {
+            shown.push(creature.clone());
+        }
+    }
+
+    let searching = !search.trim().is_empty();
+    let shown = if !searching {
+        encounter.active_creature()
+            .and_then(|c| encounter.state(c.id()))
+            .cloned()
+            .into_iter()
+            .collect::<Vec<_>>()
+    } else {
+        shown.into_iter().filter(|c| c.matches_search(&search)).collect()
+    };
+
+    html! {
+        <aside class="notes-panel flex-column">
+            <input type="search" placeholder="Search names and notes" value={(*search).clone()} oninput={update_search} aria-label="Search names and notes" />
+            <div class="flex-grow-1 scroll-y">
+                if shown.is_empty() && searching {
+                    <p class="notes-empty">{"No creatures match this search."}</p>
+                }
+                {shown.iter().map(|c| html! {
+                    <div key={c.id().to_string()}>
+                        if !searching {
+                            if let Some(stat_block) = c.stat_block().filter(|block| block.has_details()) {
+                                <StatBlockView name={c.name().to_string()} stat_block={stat_block.clone()} />
+                            }
+                            <DamageTypesEditor
+                                id={c.id()}
+                                name={c.name().to_string()}
+                                stat_block={c.stat_block().cloned().unwrap_or_default()}
+                                show_types={!c.stat_block().is_some_and(|block| block.has_details())}
+                                on_saved={on_saved.clone()} />
+                        }
+                        <CreatureNotes id={c.id()} name={c.name().to_string()} notes={c.notes().to_string()} on_saved={on_saved.clone()} />
+                    </div>
+                }).collect::<Html>()}
+            </div>
+        </aside>
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+struct WaitingTrayProps {
+    encounter: UseActiveEncounterHandle
+}
+
+/// Lets the active creature delay or ready an action, and shows the creatures that are waiting to re-enter the order.
+#[function_component(WaitingTray)]
+fn waiting_tray(props: &WaitingTrayProps) -> Html {
+    let WaitingTrayProps { encounter } = props.clone();
+    let active_name = encounter.active_creature().map(|c| c.name().to_string());
+
+    let wait = |reason: WaitReason| {
+        let encounter = encounter.clone();
+        Callback::from(move |_: MouseEvent| {
+            let encounter = encounter.clone();
+            wait_active_creature_with_callback(reason, move |_| {
+                encounter.update();
+            });
+        })
+    };
+
+    let resume = |id: Uuid, position: ResumePosition| {
+        let encounter = encounter.clone();
+        Callback::from(move |_: MouseEvent| {
+            let encounter = encounter.clone();
+            resume_creature_with_callback(id, position, move |_| {
+                encounter.update();
+            });
+        })
+    };
+
+    let waiting_items = encounter.waiting().iter()
+        .filter_map(|waiting| encounter.get(waiting.id()).map(|creature| (waiting, creature)))
+        .map(|(waiting, creature)| {
+            let resume_buttons = match &active_name {
+                Some(active_name) => html! {
+                    <>
+                        <button onclick={resume(creature.id(), ResumePosition::Before)}>{format!("Before {}", active_name)}</button>
+                        <button onclick={resume(creature.id(), ResumePosition::After)}>{format!("After {}", active_name)}</button>
+                    </>
+                },
+                None => html! {
+                    <button onclick={resume(creature.id(), ResumePosition::Before)}>{"Resume"}</button>
+                }
+            };
+
+            html! {
+                <div class="flex-row list-item waiting-creature">
+                    <p class="flex-grow-1">{creature.name()}</p>
+                    <p class="waiting-reason">{format!("{} since round {}", waiting.reason(), waiting.round())}</p>
+                    {resume_buttons}
+                </div>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <div class="flex-column waiting-tray">
+            <div class="flex-row button-group">
+                <button class="flex-grow-1" onclick={wait(WaitReason::Delay)} disabled={active_name.is_none()}>{"Delay turn"}</button>
+                <button class="flex-grow-1" onclick={wait(WaitReason::Ready)} disabled={active_name.is_none()}>{"Ready action"}</button>
+            </div>
+            {waiting_items}
+        </div>
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+struct HitPointsModalProps {
+    encounter: UseActiveEncounterHandle,
+    edit: UseStateHandle<Option<HitPointsEdit>>
+}
+
+#[function_component(HitPointsModal)]
+fn hit_points_modal(props: &HitPointsModalProps) -> Html {
+    let HitPointsModalProps { encounter, edit } = props.clone();
+    let input_ref = use_node_ref();
+    let error_message = use_state_eq(|| None::<String>);
+
+    let apply = {
+        let edit = edit.clone();
+        let input_ref = input_ref.clone();
+        let error_message = error_message.clone();
+        Callback::from(move |_: ()| {
+            let HitPointsEdit { id, mode, .. } = match &*edit {
+                Some(value) => value.clone(),
+                None => return
+            };
+
+            let value = input_ref.cast::<HtmlInputElement>()
+                .map(|input| input.value())
+                .unwrap_or_default();
+
+            let encounter = encounter.clone();
+            let on_done = {
+                let edit = edit.clone();
+                let error_message = error_message.clone();
+                Callback::from(move |_: ()| {
+                    error_message.set(None);
+                    edit.set(None);
+                    encounter.update();
+                })
+            };
+
+            // Damage can be split into typed parts, like "8 slashing + 4 fire", so resistances are applied to each.
+            match mode {
+                HitPointsMode::Damage => match parse_damage(&value) {
+                    Ok(damage) => {
+                        let error_message = error_message.clone();
+                        damage_creature_with_callback(id, damage, move |result: Result<usize, Error>| match result {
+                            Ok(_) => on_done.emit(()),
+                            Err(Error::BackendError(BackendError::ArgumentError { message, .. })) => error_message.set(Some(message)),
+                            Err(error) => error_message.set(Some(error.to_string()))
+                        });
+                    },
+                    Err(BackendError::ArgumentError { message, .. }) => error_message.set(Some(message)),
+                    Err(error) => error_message.set(Some(error.to_string()))
+                },
+                HitPointsMode::Heal | HitPointsMode::Maximum => match value.trim().parse::<usize>() {
+                    Ok(amount) if mode == HitPointsMode::Heal => heal_creature_with_callback(id, amount, on_done),
+                    Ok(amount) => set_creature_hit_points_with_callback(id, Some(amount), on_done),
+                    Err(_) => error_message.set(Some(format!("'{}' is not a valid number of hit points", value.trim())))
+                }
+            }
+        })
+    };
+
+    let cancel = {
+        let edit = edit.clone();
+        let error_message = error_message.clone();
+        Callback::from(move |_: ()| {
+            error_message.set(None);
+            edit.set(None);
+        })
+    };
+
+    let on_key_down = {
+        let apply = apply.clone();
+        let cancel = cancel.clone();
+        Callback::from(move |e: KeyboardEvent| {
+            match e.key().as_str() {
+                "Enter" => apply.emit(()),
+                "Escape" => cancel.emit(()),
+                _ => {}
+            }
+        })
+    };
+
+    let (title, action) = match &*edit {
+        Some(HitPointsEdit { name, mode: HitPointsMode::Damage, .. }) => (format!("How much damage does {} take?", name), "Damage"),
+        Some(HitPointsEdit { name, mode: HitPointsMode::Heal, .. }) => (format!("How much is {} healed by?", name), "Heal"),
+        Some(HitPointsEdit { name, mode: HitPointsMode::Maximum, .. }) => (format!("What is the maximum hit points of {}?", name), "Set"),
+        None => return html! {}
+    };
+    let is_damage = matches!(&*edit, Some(HitPointsEdit { mode: HitPointsMode::Damage, .. }));
+
+    html! {
+        <Modal>
+            <p>{title}</p>
+            if is_damage {
+                <input ref={input_ref} type="text" placeholder="8 slashing + 4 fire" autofocus=true onkeydown={on_key_down} aria-describedby="damage-hint" />
+                <p id="damage-hint" class="field-hint">{"Give each part of the hit a damage type to apply resistances, vulnerabilities and immunities."}</p>
+            } else {
+                <input ref={input_ref} type="number" min="0" class="text-align-right" autofocus=true onkeydown={on_key_down} />
+            }
+            if let Some(message) = &*error_message {
+                <p class="error-text">{message}</p>
+            }
+            <div class="flex-row button-group modal-buttons">
+                <button class="flex-grow-1" onclick={apply.reform(|_: MouseEvent| ())}>{action}</button>
+                <button class="flex-grow-1" onclick={cancel.reform(|_: MouseEvent| ())}>{"Cancel"}</button>
+            </div>
+        </Modal>
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+struct RevealModalProps {
+    encounter: UseActiveEncounterHandle,
+    request: UseStateHandle<Option<RevealRequest>>
+}
+
+#[function_component(RevealModal)]
+fn reveal_modal(props: &RevealModalProps) -> Html {
+    let RevealModalProps { encounter, request } = props.clone();
+    let RevealRequest { id, name, initiative } = match &*request {
+        Some(value) => value.clone(),
+        None => return html! {}
+    };
+
+    let reveal = |move_to_initiative: bool| {
+        let encounter = encounter.clone();
+        let request = request.clone();
+        Callback::from(move |_: MouseEvent| {
+            let encounter = encounter.clone();
+            let request = request.clone();
+            set_creature_hidden_with_callback(id, false, move_to_initiative, move |_| {
+                request.set(None);
+                encounter.update();
+            });
+        })
+    };
+
+    let cancel = {
+        let request = request.clone();
+        Callback::from(move |_: MouseEvent| {
+            request.set(None);
+        })
+    };
+
+    html! {
+        <Modal>
+            <p>{format!("Reveal {} to the players?", name)}</p>
+            <p>{format!("{} can stay where it is in the order or move to its initiative of {}.", name, initiative)}</p>
+            <div class="flex-column modal-buttons">
+                <button onclick={reveal(false)}>{"Reveal in place"}</button>
+                <button onclick={reveal(true)}>{format!("Reveal at initiative {}", initiative)}</button>
+                <button onclick={cancel}>{"Cancel"}</button>
+            </div>
+        </Modal>
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+struct RoundInitiativeModalProps {
+    encounter: UseActiveEncounterHandle
+}
+
+/// Asks for everyone's new initiative at the start of a round, for modes that reroll every round.
+#[function_component(RoundInitiativeModal)]
+fn round_initiative_modal(props: &RoundInitiativeModalProps) -> Html {
+    let RoundInitiativeModalProps { encounter } = props.clone();
+    let values = use_state_eq(HashMap::<Uuid, String>::new);
+    let error_message = use_state_eq(|| None::<String>);
+
+    let apply = {
+        let encounter = encounter.clone();
+        let values = values.clone();
+        let error_message = error_message.clone();
+        Callback::from(move |_: MouseEvent| {
+            let mut initiatives = Vec::new();
+            for creature in encounter.creatures() {
+                let initiative = match values.get(&creature.id()) {
+                    Some(value) => match value.trim().parse::<isize>() {
+                        Ok(initiative) => initiative,
+                        Err(_) => {
+                            error_message.set(Some(format!("'{}' is not a valid initiative for {}", value.trim(), creature.name())));
+                            return;
+                        }
+                    },
+                    None => creature.initiative()
+                };
+
+                initiatives.push((creature.id(), initiative));
+            }
+
+            let encounter = encounter.clone();
+            let values = values.clone();
+            let error_message = error_message.clone();
+            set_round_initiatives_with_callback(initiatives, move |_| {
+                values.set(HashMap::new());
+                error_message.set(None);
+                encounter.update();
+            });
+        })
+    };
+
+    let rows = encounter.creatures()
+        .map(|creature| {
+            let set_value = {
+                let values = values.clone();
+                let id = creature.id();
+                Callback::from(move |e: Event| {
+                    let target: HtmlInputElement = e.target_unchecked_into();
+                    let mut new_values = (*values).clone();
+                    new_values.insert(id, target.value());
+                    values.set(new_values);
+                })
+            };
+
+            html! {
+                <tr>
+                    <td><p>{creature.name()}</p></td>
+                    <td><input type="number" class="text-align-right" value={creature.initiative().to_string()} onchange={set_value} /></td>
+                </tr>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <Modal>
+            <p>{format!("Roll initiative for round {}.", encounter.round())}</p>
+            <div class="scroll-y">
+                <table class="stretch-width">
+                    {rows}
+                </table>
+            </div>
+            if let Some(message) = &*error_message {
+                <p class="error-text">{message}</p>
+            }
+            <div class="flex-row button-group modal-buttons">
+                <button class="flex-grow-1" onclick={apply}>{"Start round"}</button>
+            </div>
+        </Modal>
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+struct DealCardsModalProps {
+    encounter: UseActiveEncounterHandle
+}
+
+#[function_component(DealCardsModal)]
+fn deal_cards_modal(props: &DealCardsModalProps) -> Html {
+    let DealCardsModalProps { encounter } = props.clone();
+    let round = encounter.round();
+
+    let deal = Callback::from(move |_: MouseEvent| {
+        let encounter = encounter.clone();
+        deal_round_cards_with_callback(move |_| {
+            encounter.update();
+        });
+    });
+
+    html! {
+        <Modal>
+            <p>{format!("Deal the action cards for round {}.", round)}</p>
+            <div class="flex-row button-group modal-buttons">
+                <button class="flex-grow-1" onclick={deal}>{"Deal cards"}</button>
+            </div>
+        </Modal>
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Properties)]
 struct AddCreaturesModalProps {
-    creatures: UseCreaturesHandle,
+    encounter: UseActiveEncounterHandle,
     is_open: UseStateHandle<bool>
 }
 
 #[function_component(AddCreaturesModal)]
 fn add_creatures_modal(props: &AddCreaturesModalProps) -> Html {
-    let AddCreaturesModalProps { creatures, is_open } = props.clone();
+    let AddCreaturesModalProps { encounter, is_open } = props.clone();
     let creatures_text = use_state_eq(|| String::new());
     let update_text = {
         let creatures_text = creatures_text.clone();
         Callback::from(move |e: Event| {
             let target: HtmlInputElement = e.target_unchecked_into();
-            creatures_text.set(target.value()); 
+            creatures_text.set(target.value());
         })
     };
 
     let add_creatures = {
         let creatures_text = creatures_text.clone();
-        let creatures = creatures.clone();
+        let encounter = encounter.clone();
         let is_open = is_open.clone();
         Callback::from(move |_: MouseEvent| {
-            let creatures = creatures.clone();
+            let encounter = encounter.clone();
             let is_open = is_open.clone();
 
             add_creatures_to_active_encounter_with_callback(&*creatures_text, move |_| {
-                creatures.update();
+                encounter.update();
                 is_open.set(false);
             });
         })
@@ -179,7 +888,7 @@ fn add_creatures_modal(props: &AddCreaturesModalProps) -> Html {
     let modal_html = html! {
         <Modal>
             <p>{"You can add new creatures to the encounter. You can add multiple creatures by separating them onto new lines."}</p>
-            <p>{"Creatures will be added at the end of the round in the order that they are entered."}</p>
+            <p>{"Follow a name with a comma and the initiative it rolled, such as \"Goblin, 14\", or leave it off to have one rolled. Creatures join the order at their initiative, and those that arrive ahead of the current turn act from the next round."}</p>
             <textarea onchange={update_text}>{&*creatures_text}</textarea>
             <div class="flex-row button-group">
                 <button class="flex-grow-1" onclick={add_creatures}>{"Add Creatures"}</button>
@@ -193,4 +902,4 @@ fn add_creatures_modal(props: &AddCreaturesModalProps) -> Html {
             {modal_html}
         }
     }
-}
\ No newline at end of file
+}
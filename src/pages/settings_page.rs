@@ -0,0 +1,283 @@
+use common_data_lib::{creatures::OrderMode, keymap::{KeyAction, KeyBinding, Keymap}, settings::{LogLevel, Settings}, BackendError};
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+use crate::{app::AppPage, hooks::prelude::*, glue::update_settings_with_callback, Error};
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct SettingsPageProps {
+    pub current_page: UseStateHandle<AppPage>
+}
+
+#[function_component(SettingsPage)]
+pub fn settings_page(props: &SettingsPageProps) -> Html {
+    let SettingsPageProps { current_page } = props.clone();
+    let settings = use_settings();
+    let error_message = use_state_eq(|| None::<String>);
+
+    let set_max_log_count = {
+        let settings = settings.clone();
+        Callback::from(move |e: Event| {
+            let target: HtmlInputElement = e.target_unchecked_into();
+            let mut new_settings = (*settings).clone();
+            match target.value().trim().parse() {
+                Ok(value) => new_settings.set_max_log_count(value),
+                Err(err) => log::warn!("Invalid log count '{}': {}", target.value(), err)
+            };
+
+            settings.set(new_settings);
+        })
+    };
+
+    let set_console_log_level = {
+        let settings = settings.clone();
+        Callback::from(move |e: Event| {
+            let target: HtmlSelectElement = e.target_unchecked_into();
+            let mut new_settings = (*settings).clone();
+            if let Some(level) = LogLevel::all().iter().find(|l| l.to_string() == target.value()) {
+                new_settings.set_console_log_level(*level);
+            }
+
+            settings.set(new_settings);
+        })
+    };
+
+    let set_default_order_mode = {
+        let settings = settings.clone();
+        Callback::from(move |e: Event| {
+            let target: HtmlSelectElement = e.target_unchecked_into();
+            let mut new_settings = (*settings).clone();
+            if let Some(mode) = OrderMode::all().iter().find(|m| m.to_string() == target.value()) {
+                new_settings.set_default_order_mode(*mode);
+            }
+
+            settings.set(new_settings);
+        })
+    };
+
+    let set_window_width = {
+        let settings = settings.clone();
+        Callback::from(move |e: Event| {
+            let target: HtmlInputElement = e.target_unchecked_into();
+            let mut new_settings = (*settings).clone();
+            match target.value().trim().parse() {
+                Ok(value) => new_settings.set_window_width(value),
+                Err(err) => log::warn!("Invalid window width '{}': {}", target.value(), err)
+            };
+
+            settings.set(new_settings);
+        })
+    };
+
+    let set_window_height = {
+        let settings = settings.clone();
+        Callback::from(move |e: Event| {
+            let target: HtmlInputElement = e.target_unchecked_into();
+            let mut new_settings = (*settings).clone();
+            match target.value().trim().parse() {
+                Ok(value) => new_settings.set_window_height(value),
+                Err(err) => log::warn!("Invalid window height '{}': {}", target.value(), err)
+            };
+
+            settings.set(new_settings);
+        })
+    };
+
+    let set_keymap = {
+        let settings = settings.clone();
+        Callback::from(move |keymap: Keymap| {
+            let mut new_settings = (*settings).clone();
+            new_settings.set_keymap(keymap);
+            settings.set(new_settings);
+        })
+    };
+
+    let save = {
+        let settings = settings.clone();
+        let current_page = current_page.clone();
+        let error_message = error_message.clone();
+        Callback::from(move |_: MouseEvent| {
+            let current_page = current_page.clone();
+            let error_message = error_message.clone();
+            update_settings_with_callback((*settings).clone(), move |result: Result<(), Error>| {
+                match result {
+                    Ok(_) => current_page.set(AppPage::WelcomePage),
+                    Err(Error::BackendError(BackendError::ArgumentError { message, .. })) => error_message.set(Some(message)),
+                    Err(err) => error_message.set(Some(err.to_string()))
+                }
+            });
+        })
+    };
+
+    let back = Callback::from(move |_: MouseEvent| {
+        current_page.set(AppPage::WelcomePage);
+    });
+
+    let log_level_options = LogLevel::all().iter()
+        .map(|level| html! {
+            <option value={level.to_string()} selected={*level == settings.console_log_level()}>{level.to_string()}</option>
+        })
+        .collect::<Html>();
+
+    let order_mode_options = OrderMode::all().iter()
+        .map(|mode| html! {
+            <option value={mode.to_string()} selected={*mode == settings.default_order_mode()}>{mode.to_string()}</option>
+        })
+        .collect::<Html>();
+
+    html! {
+        <div class="flex-row stretch">
+            <main class="flex-column no-scroll">
+                <h1 class="heading">{"Settings"}</h1>
+                <div class="flex-grow-1 scroll-y">
+                    <div class="flex-row list-item settings-item">
+                        <p class="flex-grow-1">{"Creature list order"}</p>
+                        <select onchange={set_default_order_mode}>{order_mode_options}</select>
+                    </div>
+                    <div class="flex-row list-item settings-item">
+                        <p class="flex-grow-1">{"Console log level"}</p>
+                        <select onchange={set_console_log_level}>{log_level_options}</select>
+                    </div>
+                    <div class="flex-row list-item settings-item">
+                        <p class="flex-grow-1">{"Log files to keep"}</p>
+                        <input type="number" min="1" class="text-align-right" value={settings.max_log_count().to_string()} onchange={set_max_log_count} />
+                    </div>
+                    <div class="flex-row list-item settings-item">
+                        <p class="flex-grow-1">{"Window width"}</p>
+                        <input type="number" min={Settings::MIN_WINDOW_WIDTH.to_string()} class="text-align-right" value={settings.window_width().to_string()} onchange={set_window_width} />
+                    </div>
+                    <div class="flex-row list-item settings-item">
+                        <p class="flex-grow-1">{"Window height"}</p>
+                        <input type="number" min={Settings::MIN_WINDOW_HEIGHT.to_string()} class="text-align-right" value={settings.window_height().to_string()} onchange={set_window_height} />
+                    </div>
+                    <h2 class="heading">{"Keyboard shortcuts"}</h2>
+                    <KeymapEditor keymap={settings.keymap().clone()} on_change={set_keymap} />
+                    <p>{"The window size and number of log files to keep are applied the next time the app starts."}</p>
+                    if let Some(message) = &*error_message {
+                        <p class="error-text">{message}</p>
+                    }
+                </div>
+                <div class="flex-row button-group">
+                    <button class="flex-grow-1" onclick={back}>{"Back"}</button>
+                    <button class="flex-grow-1" onclick={save}>{"Save"}</button>
+                </div>
+            </main>
+        </div>
+    }
+}
+
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+struct KeymapEditorProps {
+    keymap: Keymap,
+    on_change: Callback<Keymap>
+}
+
+#[function_component(KeymapEditor)]
+fn keymap_editor(props: &KeymapEditorProps) -> Html {
+    let KeymapEditorProps { keymap, on_change } = props.clone();
+    let capturing = use_state_eq(|| None::<KeyAction>);
+
+    let capture_key = {
+        let keymap = keymap.clone();
+        let on_change = on_change.clone();
+        let capturing = capturing.clone();
+        Callback::from(move |e: KeyboardEvent| {
+            let action = match *capturing {
+                Some(action) => action,
+                None => return
+            };
+
+            e.prevent_default();
+            match e.key().as_str() {
+                "Alt" | "Control" | "Shift" | "Meta" => return,
+                "Escape" => {},
+                key => {
+                    let mut binding = KeyBinding::new(action, key);
+                    if e.alt_key() {
+                        binding = binding.with_alt();
+                    }
+
+                    if e.ctrl_key() || e.meta_key() {
+                        binding = binding.with_ctrl();
+                    }
+
+                    let mut new_keymap = keymap.clone();
+                    new_keymap.bind(binding);
+                    on_change.emit(new_keymap);
+                }
+            }
+
+            capturing.set(None);
+        })
+    };
+
+    let stop_capturing = {
+        let capturing = capturing.clone();
+        Callback::from(move |_: FocusEvent| {
+            capturing.set(None);
+        })
+    };
+
+    let reset = {
+        let on_change = on_change.clone();
+        Callback::from(move |_: MouseEvent| {
+            on_change.emit(Keymap::default());
+        })
+    };
+
+    let rows = KeyAction::all().iter()
+        .map(|action| {
+            let bindings = keymap.bindings().iter()
+                .enumerate()
+                .filter(|(_, binding)| binding.action() == *action)
+                .map(|(idx, binding)| {
+                    let keymap = keymap.clone();
+                    let on_change = on_change.clone();
+                    let remove = Callback::from(move |_: MouseEvent| {
+                        let mut new_keymap = keymap.clone();
+                        new_keymap.remove(idx);
+                        on_change.emit(new_keymap);
+                    });
+
+                    html! {
+                        <button class="key-binding" onclick={remove} title="Remove shortcut">{format!("{} \u{2715}", binding)}</button>
+                    }
+                })
+                .collect::<Html>();
+
+            let capture = if *capturing == Some(*action) {
+                html! {
+                    <input class="key-capture" placeholder="Press a key" readonly=true autofocus=true onkeydown={capture_key.clone()} onblur={stop_capturing.clone()} />
+                }
+            } else {
+                let capturing = capturing.clone();
+                let action = *action;
+                let start = Callback::from(move |_: MouseEvent| {
+                    capturing.set(Some(action));
+                });
+
+                html! {
+                    <button class="key-binding" onclick={start}>{"Add"}</button>
+                }
+            };
+
+            html! {
+                <div class="flex-row list-item settings-item">
+                    <p class="flex-grow-1">{action.to_string()}</p>
+                    {bindings}
+                    {capture}
+                </div>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <>
+            {rows}
+            <div class="flex-row button-group">
+                <button class="flex-grow-1" onclick={reset}>{"Reset shortcuts"}</button>
+            </div>
+        </>
+    }
+}
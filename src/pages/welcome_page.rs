@@ -1,22 +1,39 @@
-use common_data_lib::creatures::Creature;
+use std::path::PathBuf;
+
+use common_data_lib::{campaign::{CampaignWorkspace, EncounterStatus, Roster}, cards::CardEdge, creatures::{Creature, CreaturePatch, Side}, csv_files::{CsvColumn, CsvColumnMapping}, document::DocumentState, initiative::InitiativeMode, recent_files::RecentFile, BackendError};
 use regex::Regex;
-use web_sys::{HtmlInputElement, HtmlTextAreaElement};
+use uuid::Uuid;
+use web_sys::{HtmlInputElement, HtmlTextAreaElement, HtmlSelectElement};
 use yew::prelude::*;
 use yew_icons::{IconId, Icon};
 
-use crate::{app::AppPage, components::{menu::Menu, accordion::Accordion, modal::Modal}, glue::*, hooks::prelude::*};
+use crate::{app::AppPage, components::{menu::Menu, accordion::Accordion, creature_notes::CreatureNotes, modal::Modal, name_input::NameInput, notification::Notification, unsaved_changes_modal::UnsavedChangesModal}, glue::*, hooks::prelude::*, Error};
 
 #[derive(Debug, Clone, PartialEq, Properties)]
 pub struct WelcomePageProps {
     pub current_page: UseStateHandle<AppPage>
 }
 
+#[derive(Debug, Clone, PartialEq)]
+enum FileAction {
+    New,
+    Open,
+    OpenRecent(PathBuf)
+}
+
 #[function_component(WelcomePage)]
 pub fn welcome_page(props: &WelcomePageProps) -> Html {
     let WelcomePageProps { current_page } = props;
     let creatures = use_creatures();
+    let initiative_mode = use_initiative_mode();
+    let recent_files = use_recent_files();
+    let campaign = use_campaign();
+    let campaign_error = use_state_eq(|| None::<String>);
     let is_menu_open = use_state_eq(|| false);
     let is_add_creatures_modal_open = use_sr_state_eq(false);
+    let creature_search = use_state_eq(String::new);
+    let csv_import = use_state_eq(|| None::<CsvImport>);
+    let pending_file_action = use_state_eq(|| None::<FileAction>);
 
     let open_modal = {
         let is_add_creatures_modal_open = is_add_creatures_modal_open.clone();
@@ -25,16 +42,73 @@ pub fn welcome_page(props: &WelcomePageProps) -> Html {
         })
     };
 
-    let new_encounter = {
+    let perform_file_action = {
         let creatures = creatures.clone();
+        let initiative_mode = initiative_mode.clone();
+        let recent_files = recent_files.clone();
         let is_menu_open = is_menu_open.clone();
-        Callback::from(move |_: MouseEvent| {
+        Callback::from(move |action: FileAction| {
             let creatures = creatures.clone();
+            let initiative_mode = initiative_mode.clone();
+            let recent_files = recent_files.clone();
             let is_menu_open = is_menu_open.clone();
-            new_encounter_with_callback(move |_| {
+            let on_loaded = Callback::from(move |_| {
                 creatures.update();
+                initiative_mode.update();
+                recent_files.update();
                 is_menu_open.set(false);
             });
+
+            match action {
+                FileAction::New => new_encounter_with_callback(on_loaded),
+                FileAction::Open => open_encounter_dialog_with_callback(move |path| {
+                    if let Some(path) = path {
+                        log::info!("Opening encounter: {:?}", &path);
+                        load_encounter_with_callback(path, on_loaded.clone());
+                    }
+                }),
+                FileAction::OpenRecent(path) => {
+                    log::info!("Opening recent encounter: {:?}", &path);
+                    load_encounter_with_callback(path, on_loaded);
+                }
+            }
+        })
+    };
+
+    let request_file_action = {
+        let perform_file_action = perform_file_action.clone();
+        let pending_file_action = pending_file_action.clone();
+        Callback::from(move |action: FileAction| {
+            let perform_file_action = perform_file_action.clone();
+            let pending_file_action = pending_file_action.clone();
+            get_document_state_with_callback(move |document: DocumentState| {
+                if document.dirty() {
+                    pending_file_action.set(Some(action.clone()));
+                } else {
+                    perform_file_action.emit(action.clone());
+                }
+            });
+        })
+    };
+
+    let new_encounter = {
+        let request_file_action = request_file_action.clone();
+        Callback::from(move |_: MouseEvent| {
+            request_file_action.emit(FileAction::New);
+        })
+    };
+
+    let open_encounter = {
+        let request_file_action = request_file_action.clone();
+        Callback::from(move |_: MouseEvent| {
+            request_file_action.emit(FileAction::Open);
+        })
+    };
+
+    let open_recent_file = {
+        let request_file_action = request_file_action.clone();
+        Callback::from(move |path: PathBuf| {
+            request_file_action.emit(FileAction::OpenRecent(path));
         })
     };
 
@@ -51,19 +125,67 @@ pub fn welcome_page(props: &WelcomePageProps) -> Html {
         })
     };
 
-    let open_encounter = {
-        let creatures = creatures.clone();
+    let save_encounter = {
+        let recent_files = recent_files.clone();
         let is_menu_open = is_menu_open.clone();
         Callback::from(move |_: MouseEvent| {
-            let creatures = creatures.clone();
+            let recent_files = recent_files.clone();
             let is_menu_open = is_menu_open.clone();
-            open_encounter_dialog_with_callback(move |path| {
-                let creatures = creatures.clone();
+            save_or_prompt_encounter_with_callback(move |saved: bool| {
+                if saved {
+                    recent_files.update();
+                    is_menu_open.set(false);
+                }
+            });
+        })
+    };
+
+    let save_encounter_as = {
+        let recent_files = recent_files.clone();
+        let is_menu_open = is_menu_open.clone();
+        Callback::from(move |_: MouseEvent| {
+            let recent_files = recent_files.clone();
+            let is_menu_open = is_menu_open.clone();
+            save_encounter_as_with_callback(move |saved: bool| {
+                if saved {
+                    recent_files.update();
+                    is_menu_open.set(false);
+                }
+            });
+        })
+    };
+
+    let continue_file_action = {
+        let pending_file_action = pending_file_action.clone();
+        Callback::from(move |_| {
+            if let Some(action) = &*pending_file_action {
+                perform_file_action.emit(action.clone());
+            }
+
+            pending_file_action.set(None);
+        })
+    };
+
+    let cancel_file_action = {
+        let pending_file_action = pending_file_action.clone();
+        Callback::from(move |_| {
+            pending_file_action.set(None);
+        })
+    };
+
+    let import_csv = {
+        let csv_import = csv_import.clone();
+        let is_menu_open = is_menu_open.clone();
+        Callback::from(move |_: MouseEvent| {
+            let csv_import = csv_import.clone();
+            let is_menu_open = is_menu_open.clone();
+            open_csv_dialog_with_callback(move |path: Option<PathBuf>| {
+                let csv_import = csv_import.clone();
                 let is_menu_open = is_menu_open.clone();
                 if let Some(path) = path {
-                    log::info!("Opening encounter: {:?}", &path);
-                    load_encounter_with_callback(path, move |_| {
-                        creatures.update();
+                    log::info!("Reading CSV headers: {:?}", &path);
+                    get_csv_headers_with_callback(path.clone(), move |headers| {
+                        csv_import.set(Some(CsvImport { path: path.clone(), headers }));
                         is_menu_open.set(false);
                     });
                 }
@@ -71,15 +193,16 @@ pub fn welcome_page(props: &WelcomePageProps) -> Html {
         })
     };
 
-    let save_enocunter = {
+    // The players' copy leaves out hidden creatures and notes, which only the GM's copy keeps.
+    let export_csv = |for_gm: bool| {
         let is_menu_open = is_menu_open.clone();
         Callback::from(move |_: MouseEvent| {
             let is_menu_open = is_menu_open.clone();
-            save_encounter_dialog_with_callback(move |path| {
+            save_csv_dialog_with_callback(move |path| {
                 let is_menu_open = is_menu_open.clone();
                 if let Some(path) = path {
-                    log::info!("Saving encounter: {:?}", &path);
-                    save_encounter_with_callback(path, move |_| {
+                    log::info!("Exporting creatures: {:?}", &path);
+                    export_creatures_csv_with_callback(path, for_gm, move |_| {
                         is_menu_open.set(false);
                     });
                 }
@@ -87,6 +210,97 @@ pub fn welcome_page(props: &WelcomePageProps) -> Html {
         })
     };
 
+    let on_campaign_opened = {
+        let campaign = campaign.clone();
+        let campaign_error = campaign_error.clone();
+        let is_menu_open = is_menu_open.clone();
+        Callback::from(move |result: Result<CampaignWorkspace, Error>| {
+            match result {
+                Ok(workspace) => {
+                    campaign.set(Some(workspace));
+                    campaign_error.set(None);
+                    is_menu_open.set(false);
+                },
+                Err(Error::BackendError(BackendError::ArgumentError { message, .. })) => campaign_error.set(Some(message)),
+                Err(err) => campaign_error.set(Some(err.to_string()))
+            }
+        })
+    };
+
+    let new_campaign = {
+        let on_campaign_opened = on_campaign_opened.clone();
+        Callback::from(move |_: MouseEvent| {
+            let on_campaign_opened = on_campaign_opened.clone();
+            open_campaign_dialog_with_callback(move |directory: Option<PathBuf>| {
+                if let Some(directory) = directory {
+                    log::info!("Creating campaign: {:?}", &directory);
+                    create_campaign_with_callback(directory, on_campaign_opened.clone());
+                }
+            });
+        })
+    };
+
+    let open_campaign = {
+        let on_campaign_opened = on_campaign_opened.clone();
+        Callback::from(move |_: MouseEvent| {
+            let on_campaign_opened = on_campaign_opened.clone();
+            open_campaign_dialog_with_callback(move |directory: Option<PathBuf>| {
+                if let Some(directory) = directory {
+                    log::info!("Opening campaign: {:?}", &directory);
+                    open_campaign_with_callback(directory, on_campaign_opened.clone());
+                }
+            });
+        })
+    };
+
+    let close_campaign = {
+        let campaign = campaign.clone();
+        let is_menu_open = is_menu_open.clone();
+        Callback::from(move |_: MouseEvent| {
+            let campaign = campaign.clone();
+            let is_menu_open = is_menu_open.clone();
+            close_campaign_with_callback(move |_| {
+                campaign.set(None);
+                is_menu_open.set(false);
+            });
+        })
+    };
+
+    let dismiss_campaign_error = {
+        let campaign_error = campaign_error.clone();
+        Callback::from(move |_| {
+            campaign_error.set(None);
+        })
+    };
+
+    let open_campaign_encounter = {
+        let request_file_action = request_file_action.clone();
+        Callback::from(move |path: PathBuf| {
+            request_file_action.emit(FileAction::OpenRecent(path));
+        })
+    };
+
+    let open_settings = {
+        let current_page = current_page.clone();
+        Callback::from(move |_: MouseEvent| {
+            current_page.set(AppPage::SettingsPage);
+        })
+    };
+
+    let dismiss_missing_files = {
+        let recent_files = recent_files.clone();
+        Callback::from(move |_| {
+            recent_files.clear_missing();
+        })
+    };
+
+    let close_csv_import = {
+        let csv_import = csv_import.clone();
+        Callback::from(move |_| {
+            csv_import.set(None);
+        })
+    };
+
     let next_page = {
         let current_page = current_page.clone();
         Callback::from(move |_: MouseEvent| {
@@ -101,17 +315,52 @@ pub fn welcome_page(props: &WelcomePageProps) -> Html {
                 <Accordion title="File">
                     <button class="menu-button" onclick={new_encounter}>{"New"}</button>
                     <button class="menu-button" onclick={open_encounter}>{"Open"}</button>
-                    <button class="menu-button" onclick={save_enocunter}>{"Save"}</button>
+                    <button class="menu-button" onclick={save_encounter}>{"Save"}</button>
+                    <button class="menu-button" onclick={save_encounter_as}>{"Save As"}</button>
+                    <button class="menu-button" onclick={import_csv}>{"Import CSV"}</button>
+                    <button class="menu-button" onclick={export_csv(false)}>{"Export CSV for players"}</button>
+                    <button class="menu-button" onclick={export_csv(true)}>{"Export CSV for GM"}</button>
+                    {render_recent_files(&recent_files, open_recent_file)}
+                </Accordion>
+                <Accordion title="Campaign">
+                    <button class="menu-button" onclick={new_campaign}>{"New campaign"}</button>
+                    <button class="menu-button" onclick={open_campaign}>{"Open campaign"}</button>
+                    if campaign.is_some() {
+                        <button class="menu-button" onclick={close_campaign}>{"Close campaign"}</button>
+                    }
                 </Accordion>
                 <Accordion title="Edit">
                     <button class="menu-button" onclick={reset_encounter}>{"Clear initiatives"}</button>
                 </Accordion>
+                <button class="menu-button" onclick={open_settings}>{"Settings"}</button>
             </Menu>
             <AddCreaturesModal creatures={creatures.clone()} is_visible={is_add_creatures_modal_open.clone()} />
+            if pending_file_action.is_some() {
+                <UnsavedChangesModal on_saved={continue_file_action.clone()} on_discard={continue_file_action} on_cancel={cancel_file_action} />
+            }
+            if let Some(import) = &*csv_import {
+                <ImportCsvModal creatures={creatures.clone()} import={import.clone()} on_close={close_csv_import} />
+            }
             <main class="no-scroll flex-column">
-                <h1 class="heading">{"Welcome!"}</h1>
-                <p>{"This tool can be used to help track the initiative order of creatures in your encounters."}</p>
-                {render_creatures(creatures.clone())}
+                if !recent_files.missing().is_empty() {
+                    <Notification on_dismiss={dismiss_missing_files}>
+                        <p>{"The following recent files could not be found and have been removed from the recent files list:"}</p>
+                        {recent_files.missing().iter().map(|f| html! { <p>{f.path().to_string_lossy().to_string()}</p> }).collect::<Html>()}
+                    </Notification>
+                }
+                if let Some(message) = &*campaign_error {
+                    <Notification on_dismiss={dismiss_campaign_error}>
+                        <p class="error-text">{message}</p>
+                    </Notification>
+                }
+                if campaign.is_some() {
+                    <CampaignDashboard campaign={campaign.clone()} creatures={creatures.clone()} on_open_encounter={open_campaign_encounter} />
+                } else {
+                    <h1 class="heading">{"Welcome!"}</h1>
+                    <p>{"This tool can be used to help track the initiative order of creatures in your encounters."}</p>
+                }
+                <InitiativeModeControl mode={initiative_mode.clone()} creatures={creatures.clone()} />
+                {render_creatures(creatures.clone(), *initiative_mode, creature_search.clone())}
                 <div class="flex-row button-group">
                     <button class="flex-grow-1" onclick={open_modal}>{"+"}</button>
                     <button class="flex-grow-1" onclick={next_page} disabled={!creatures.has_selected()}>{"Continue"}</button>
@@ -121,11 +370,51 @@ pub fn welcome_page(props: &WelcomePageProps) -> Html {
     }
 }
 
-fn render_creatures(creatures: UseCreaturesHandle) -> Html {
+fn render_recent_files(recent_files: &[RecentFile], open_recent_file: Callback<PathBuf>) -> Html {
+    if recent_files.is_empty() {
+        return html! {};
+    }
+
+    let items = recent_files.iter()
+        .map(|file| {
+            let open_recent_file = open_recent_file.clone();
+            let path = file.path().to_path_buf();
+            let onclick = Callback::from(move |_: MouseEvent| {
+                open_recent_file.emit(path.clone());
+            });
+
+            let last_modified = file.last_modified()
+                .map(format_timestamp)
+                .unwrap_or_default();
+
+            html! {
+                <button class="menu-button recent-file flex-column" onclick={onclick} title={file.path().to_string_lossy().to_string()}>
+                    <span>{file.name()}</span>
+                    <span class="recent-file-modified">{last_modified}</span>
+                </button>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <>
+            <hr />
+            <p class="menu-subheading">{"Recent"}</p>
+            {items}
+        </>
+    }
+}
+
+fn format_timestamp(seconds: u64) -> String {
+    let date = js_sys::Date::new(&wasm_bindgen::JsValue::from_f64(seconds as f64 * 1000.0));
+    String::from(date.to_locale_string("default", &wasm_bindgen::JsValue::UNDEFINED))
+}
+
+fn render_creatures(creatures: UseCreaturesHandle, mode: InitiativeMode, search: UseStateHandle<String>) -> Html {
     if creatures.is_empty() {
         render_empty_creatures()
     } else {
-        render_non_empty_creatures(creatures)
+        render_non_empty_creatures(creatures, mode, search)
     }
 }
 
@@ -137,25 +426,41 @@ fn render_empty_creatures() -> Html {
     }
 }
 
-fn render_non_empty_creatures(creatures: UseCreaturesHandle) -> Html {
+fn render_non_empty_creatures(creatures: UseCreaturesHandle, mode: InitiativeMode, search: UseStateHandle<String>) -> Html {
+    let update_search = {
+        let search = search.clone();
+        Callback::from(move |e: InputEvent| {
+            let target: HtmlInputElement = e.target_unchecked_into();
+            search.set(target.value());
+        })
+    };
+
     html! {
         <>
             <SelectAllControl creatures={creatures.clone()} />
+            <input type="search" placeholder="Search names and notes" value={(*search).clone()} oninput={update_search} aria-label="Search names and notes" />
             <hr />
             <div class="flex-column flex-grow-1 scroll-y">
-                {get_creatures_list(creatures.clone())}
+                {get_creatures_list(creatures.clone(), mode, &search)}
             </div>
         </>
     }
 }
 
-fn get_creatures_list(creatures: UseCreaturesHandle) -> Html {
+fn get_creatures_list(creatures: UseCreaturesHandle, mode: InitiativeMode, search: &str) -> Html {
+    // Slots have no notes of their own, so they're shown whenever the creature they belong to matches.
+    let matches = |creature: &Creature| {
+        let owner = creature.slot_of().and_then(|id| creatures.iter().find(|c| c.id() == id)).unwrap_or(creature);
+        search.trim().is_empty() || owner.matches_search(search)
+    };
+
     creatures.iter()
+        .filter(|c| matches(c))
         .map(|c| {
             let creatures = creatures.clone();
 
             html! {
-                <CreatureListing creature={c.clone()} update={creatures.update_callback()} />
+                <CreatureListing creature={c.clone()} update={creatures.update_callback()} mode={mode} />
             }
         })
         .collect()
@@ -164,13 +469,22 @@ fn get_creatures_list(creatures: UseCreaturesHandle) -> Html {
 #[derive(Debug, Clone, PartialEq, Properties)]
 struct CreatureListingProps {
     pub creature: Creature,
-    pub update: Callback<()>
+    pub update: Callback<()>,
+    pub mode: InitiativeMode
 }
 
 #[function_component(CreatureListing)]
 fn creature_listing(props: &CreatureListingProps) -> Html {
-    let CreatureListingProps { creature, update } = props;
+    let CreatureListingProps { creature, update, mode } = props;
     let hover_remove_state = use_sr_state_eq(false);
+    let is_notes_open = use_state_eq(|| false);
+
+    let toggle_notes = {
+        let is_notes_open = is_notes_open.clone();
+        Callback::from(move |_: MouseEvent| {
+            is_notes_open.set(!*is_notes_open);
+        })
+    };
 
     let update_initiative = {
         let initiative = creature.initiative();
@@ -190,6 +504,28 @@ fn creature_listing(props: &CreatureListingProps) -> Html {
         })
     };
 
+    let update_modifier = {
+        let modifier = creature.initiative_modifier();
+        let update = update.clone();
+        let id = creature.id();
+        Callback::from(move |e: Event| {
+            let update = update.clone();
+            let target: HtmlInputElement = e.target_unchecked_into();
+            let patch = CreaturePatch {
+                initiative_modifier: Some(validate_initiative_input(&target.value()).unwrap_or(modifier)),
+                ..CreaturePatch::default()
+            };
+
+            update_creature_with_callback(id, patch, move |result: Result<Creature, Error>| {
+                if let Err(err) = result {
+                    log::error!("Could not set initiative modifier: {}", err);
+                }
+
+                update.emit(());
+            });
+        })
+    };
+
     let remove_creature = {
         let hover_remove_state = hover_remove_state.clone();
         let update = update.clone();
@@ -219,6 +555,80 @@ fn creature_listing(props: &CreatureListingProps) -> Html {
         })
     };
 
+    let toggle_hidden = {
+        let update = update.clone();
+        let id = creature.id();
+        let hidden = !creature.hidden();
+        Callback::from(move |_: MouseEvent| {
+            let update = update.clone();
+            set_creature_hidden_with_callback(id, hidden, false, move |_: ()| {
+                update.emit(());
+            });
+        })
+    };
+
+    let add_slot = {
+        let update = update.clone();
+        let id = creature.id();
+        // Most extra turns come ten initiative after the first, such as a boss acting at X and X-10.
+        let initiative = creature.initiative() - 10;
+        Callback::from(move |_: MouseEvent| {
+            let update = update.clone();
+            add_initiative_slot_with_callback(id, initiative, move |_: Creature| {
+                update.emit(());
+            });
+        })
+    };
+
+    // Shift-clicking rolls the copy a new initiative and hit points rather than sharing the original's.
+    let duplicate = {
+        let update = update.clone();
+        let id = creature.id();
+        Callback::from(move |e: MouseEvent| {
+            let update = update.clone();
+            duplicate_creature_with_callback(id, e.shift_key(), move |_: Creature| {
+                update.emit(());
+            });
+        })
+    };
+
+    let toggle_side = {
+        let update = update.clone();
+        let id = creature.id();
+        let side = match creature.side() {
+            Side::Players => Side::Monsters,
+            Side::Monsters => Side::Players
+        };
+        Callback::from(move |_: MouseEvent| {
+            let update = update.clone();
+            set_creature_side_with_callback(id, side, move |_: ()| {
+                update.emit(());
+            });
+        })
+    };
+
+    let toggle_edge = |edge: CardEdge| {
+        let update = update.clone();
+        let id = creature.id();
+        let mut edges = creature.card_edges().to_vec();
+        match edges.iter().position(|e| *e == edge) {
+            Some(index) => { edges.remove(index); },
+            None => edges.push(edge)
+        }
+
+        Callback::from(move |_: MouseEvent| {
+            let update = update.clone();
+            set_creature_card_edges_with_callback(id, edges.clone(), move |_: ()| {
+                update.emit(());
+            });
+        })
+    };
+
+    let card_html = match creature.card() {
+        Some(card) if *mode == InitiativeMode::Cards => html! { <p class="dealt-card">{card.to_string()}</p> },
+        _ => html! {}
+    };
+
     let on_mouse_over = {
         let hover_remove_state = hover_remove_state.clone();
         Callback::from(move |_: MouseEvent| {
@@ -233,15 +643,57 @@ fn creature_listing(props: &CreatureListingProps) -> Html {
         })
     };
 
+    if creature.is_slot() {
+        return html! {
+            <div class="flex-row list-item initiative-slot">
+                <p class="flex-grow-1" title={format!("Another turn for {}", creature.name())}>{format!("↳ {}", creature.name())}</p>
+                {card_html}
+                <input class="text-align-right flex-grow-large" value={creature.initiative().to_string()} onchange={update_initiative} />
+                <button class="blank" onclick={remove_creature} onmouseover={on_mouse_over} onmouseout={on_mouse_out}>
+                    <Icon class="fill-color" icon_id={if *hover_remove_state {IconId::BootstrapDashCircleFill} else {IconId::BootstrapDashCircle}} width="15px" height="15px" />
+                </button>
+            </div>
+        };
+    }
+
     html! {
-        <div class="flex-row list-item">
-            <input type="checkbox" checked={creature.selected()} onchange={set_selected} />
-            <p class="flex-grow-1">{creature.name()}</p>
-            <input class="text-align-right flex-grow-large" value={creature.initiative().to_string()} onchange={update_initiative} />
-            <button class="blank" onclick={remove_creature} onmouseover={on_mouse_over} onmouseout={on_mouse_out}>
-                <Icon class="fill-color" icon_id={if *hover_remove_state {IconId::BootstrapDashCircleFill} else {IconId::BootstrapDashCircle}} width="15px" height="15px" />
-            </button>
-        </div>
+        <>
+            <div class={classes!("flex-row", "list-item", creature.hidden().then_some("hidden-creature"))}>
+                <input type="checkbox" checked={creature.selected()} onchange={set_selected} />
+                <NameInput id={creature.id()} name={creature.name().to_string()} on_renamed={update.clone()} />
+                {card_html}
+                <input class="text-align-right flex-grow-large" value={creature.initiative().to_string()} onchange={update_initiative} />
+                <input class="text-align-right modifier-input" value={format!("{:+}", creature.initiative_modifier())} onchange={update_modifier} title="Initiative modifier" aria-label="Initiative modifier" />
+                if matches!(mode, InitiativeMode::Side { .. }) {
+                    <button class="side-toggle" onclick={toggle_side} title="Change side">{creature.side().to_string()}</button>
+                }
+                if *mode == InitiativeMode::Cards {
+                    {CardEdge::all().iter().map(|edge| html! {
+                        <button class={classes!("edge-toggle", creature.card_edges().contains(edge).then_some("active"))} onclick={toggle_edge(*edge)} title={edge.to_string()}>
+                            {edge.abbreviation()}
+                        </button>
+                    }).collect::<Html>()}
+                }
+                <button class="blank" onclick={toggle_notes} title="Notes" aria-expanded={is_notes_open.to_string()}>
+                    <Icon class="fill-color" icon_id={IconId::BootstrapPencil} width="15px" height="15px" />
+                </button>
+                <button class="blank" onclick={duplicate} title="Add another (shift-click to roll its initiative and hit points)">
+                    <Icon class="fill-color" icon_id={IconId::BootstrapFiles} width="15px" height="15px" />
+                </button>
+                <button class="blank" onclick={add_slot} title="Add another turn each round">
+                    <Icon class="fill-color" icon_id={IconId::BootstrapPlusCircle} width="15px" height="15px" />
+                </button>
+                <button class="blank" onclick={toggle_hidden} title={if creature.hidden() { "Hidden from players" } else { "Visible to players" }}>
+                    <Icon class="fill-color" icon_id={if creature.hidden() {IconId::BootstrapEyeSlash} else {IconId::BootstrapEye}} width="15px" height="15px" />
+                </button>
+                <button class="blank" onclick={remove_creature} onmouseover={on_mouse_over} onmouseout={on_mouse_out}>
+                    <Icon class="fill-color" icon_id={if *hover_remove_state {IconId::BootstrapDashCircleFill} else {IconId::BootstrapDashCircle}} width="15px" height="15px" />
+                </button>
+            </div>
+            if *is_notes_open {
+                <CreatureNotes id={creature.id()} name={creature.name().to_string()} notes={creature.notes().to_string()} on_saved={update.clone()} />
+            }
+        </>
     }
 }
 
@@ -318,6 +770,282 @@ fn select_all_control(props: &SelectAllControlProps) -> Html {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Properties)]
+struct InitiativeModeControlProps {
+    mode: UseInitiativeModeHandle,
+    creatures: UseCreaturesHandle
+}
+
+#[function_component(InitiativeModeControl)]
+fn initiative_mode_control(props: &InitiativeModeControlProps) -> Html {
+    let InitiativeModeControlProps { mode, creatures } = props.clone();
+
+    let select_mode = {
+        let mode = mode.clone();
+        Callback::from(move |e: Event| {
+            let target: HtmlSelectElement = e.target_unchecked_into();
+            let new_mode = target.value().parse::<usize>().ok()
+                .and_then(|idx| InitiativeMode::all().get(idx).copied());
+
+            match new_mode {
+                Some(new_mode) if !new_mode.same_kind(&mode) => mode.set(new_mode),
+                _ => {}
+            }
+        })
+    };
+
+    let deal_cards = Callback::from(move |_: MouseEvent| {
+        let creatures = creatures.clone();
+        deal_initiative_cards_with_callback(move |_| {
+            creatures.update();
+        });
+    });
+
+    let mode_options = match *mode {
+        InitiativeMode::Side { players, monsters } => {
+            let set_roll = |side: Side| {
+                let mode = mode.clone();
+                Callback::from(move |e: Event| {
+                    let target: HtmlInputElement = e.target_unchecked_into();
+                    let roll = validate_initiative_input(&target.value());
+                    match (*mode, roll) {
+                        (InitiativeMode::Side { monsters, .. }, Some(roll)) if side == Side::Players => mode.set(InitiativeMode::Side { players: roll, monsters }),
+                        (InitiativeMode::Side { players, .. }, Some(roll)) => mode.set(InitiativeMode::Side { players, monsters: roll }),
+                        _ => mode.update()
+                    }
+                })
+            };
+
+            html! {
+                <div class="flex-row side-rolls">
+                    <p>{"Players"}</p>
+                    <input class="text-align-right" value={players.to_string()} onchange={set_roll(Side::Players)} />
+                    <p>{"Monsters"}</p>
+                    <input class="text-align-right" value={monsters.to_string()} onchange={set_roll(Side::Monsters)} />
+                </div>
+            }
+        },
+        InitiativeMode::Cards => html! {
+            <button class="deal-cards" onclick={deal_cards}>{"Deal cards"}</button>
+        },
+        _ => html! {}
+    };
+
+    let options = InitiativeMode::all().iter()
+        .enumerate()
+        .map(|(idx, option)| html! {
+            <option value={idx.to_string()} selected={option.same_kind(&mode)}>{option.to_string()}</option>
+        })
+        .collect::<Html>();
+
+    html! {
+        <div class="flex-row initiative-mode">
+            <p>{"Initiative"}</p>
+            <select onchange={select_mode}>
+                {options}
+            </select>
+            {mode_options}
+        </div>
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+struct CampaignDashboardProps {
+    campaign: UseCampaignHandle,
+    creatures: UseCreaturesHandle,
+    on_open_encounter: Callback<PathBuf>
+}
+
+/// The open campaign's prepared encounters, rosters and history, shown above the current encounter's creatures.
+#[function_component(CampaignDashboard)]
+fn campaign_dashboard(props: &CampaignDashboardProps) -> Html {
+    let CampaignDashboardProps { campaign, creatures, on_open_encounter } = props.clone();
+    let new_encounter_name = use_state_eq(String::new);
+
+    let workspace = match &*campaign {
+        Some(workspace) => workspace.clone(),
+        None => return html! {}
+    };
+
+    let update_name = {
+        let new_encounter_name = new_encounter_name.clone();
+        Callback::from(move |e: Event| {
+            let target: HtmlInputElement = e.target_unchecked_into();
+            new_encounter_name.set(target.value());
+        })
+    };
+
+    let prepare_encounter = {
+        let campaign = campaign.clone();
+        let new_encounter_name = new_encounter_name.clone();
+        Callback::from(move |_: MouseEvent| {
+            let campaign = campaign.clone();
+            let new_encounter_name = new_encounter_name.clone();
+            add_campaign_encounter_with_callback((*new_encounter_name).clone(), move |_| {
+                new_encounter_name.set(String::new());
+                campaign.update();
+            });
+        })
+    };
+
+    let encounters_html = workspace.campaign.encounters().iter()
+        .map(|encounter| {
+            let id = encounter.id();
+            let open = {
+                let on_open_encounter = on_open_encounter.clone();
+                let path = workspace.encounter_path(encounter);
+                Callback::from(move |_: MouseEvent| {
+                    on_open_encounter.emit(path.clone());
+                })
+            };
+
+            let set_status = {
+                let campaign = campaign.clone();
+                Callback::from(move |e: Event| {
+                    let campaign = campaign.clone();
+                    let target: HtmlSelectElement = e.target_unchecked_into();
+                    if let Some(status) = EncounterStatus::all().get(target.selected_index() as usize) {
+                        set_campaign_encounter_status_with_callback(id, *status, move |_| {
+                            campaign.update();
+                        });
+                    }
+                })
+            };
+
+            let remove = {
+                let campaign = campaign.clone();
+                Callback::from(move |_: MouseEvent| {
+                    let campaign = campaign.clone();
+                    remove_campaign_encounter_with_callback(id, move |_| {
+                        campaign.update();
+                    });
+                })
+            };
+
+            let options = EncounterStatus::all().iter()
+                .map(|status| html! {
+                    <option selected={*status == encounter.status()}>{status.to_string()}</option>
+                })
+                .collect::<Html>();
+
+            html! {
+                <div class="flex-row list-item campaign-encounter">
+                    <p class="flex-grow-1">{encounter.name()}</p>
+                    <select onchange={set_status}>{options}</select>
+                    <button onclick={open}>{"Open"}</button>
+                    <button class="blank" onclick={remove} title="Remove from campaign">
+                        <Icon class="fill-color" icon_id={IconId::BootstrapDashCircle} width="15px" height="15px" />
+                    </button>
+                </div>
+            }
+        })
+        .collect::<Html>();
+
+    let history_html = workspace.campaign.history().iter()
+        .rev()
+        .map(|entry| html! {
+            <p>{format!("{} - {}: {}", format_timestamp(entry.timestamp()), entry.encounter(), entry.status())}</p>
+        })
+        .collect::<Html>();
+
+    html! {
+        <div class="flex-column campaign-dashboard">
+            <h1 class="heading">{workspace.campaign.name()}</h1>
+            <h3>{"Encounters"}</h3>
+            {encounters_html}
+            <div class="flex-row button-group">
+                <input class="flex-grow-1" placeholder="Encounter name" value={(*new_encounter_name).clone()} onchange={update_name} />
+                <button onclick={prepare_encounter} disabled={new_encounter_name.trim().is_empty()}>{"Prepare"}</button>
+            </div>
+            <CampaignRoster campaign={campaign.clone()} creatures={creatures.clone()} roster={Roster::Party} />
+            <CampaignRoster campaign={campaign.clone()} creatures={creatures.clone()} roster={Roster::Library} />
+            if !workspace.campaign.history().is_empty() {
+                <Accordion title="History">
+                    {history_html}
+                </Accordion>
+            }
+        </div>
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+struct CampaignRosterProps {
+    campaign: UseCampaignHandle,
+    creatures: UseCreaturesHandle,
+    roster: Roster
+}
+
+#[function_component(CampaignRoster)]
+fn campaign_roster(props: &CampaignRosterProps) -> Html {
+    let CampaignRosterProps { campaign, creatures, roster } = props.clone();
+    let roster_creatures = campaign.as_ref()
+        .map(|workspace| workspace.campaign.roster(roster).to_vec())
+        .unwrap_or_default();
+
+    let add_to_encounter = {
+        let creatures = creatures.clone();
+        move |ids: Vec<Uuid>| {
+            let creatures = creatures.clone();
+            Callback::from(move |_: MouseEvent| {
+                let creatures = creatures.clone();
+                add_campaign_roster_to_encounter_with_callback(roster, ids.clone(), move |_| {
+                    creatures.update();
+                });
+            })
+        }
+    };
+
+    let keep_selected = {
+        let campaign = campaign.clone();
+        Callback::from(move |_: MouseEvent| {
+            let campaign = campaign.clone();
+            add_selected_to_campaign_roster_with_callback(roster, move |_| {
+                campaign.update();
+            });
+        })
+    };
+
+    let rows = roster_creatures.iter()
+        .map(|creature| {
+            let id = creature.id();
+            let remove = {
+                let campaign = campaign.clone();
+                Callback::from(move |_: MouseEvent| {
+                    let campaign = campaign.clone();
+                    remove_from_campaign_roster_with_callback(roster, id, move |_| {
+                        campaign.update();
+                    });
+                })
+            };
+
+            html! {
+                <div class="flex-row list-item">
+                    <p class="flex-grow-1">{creature.name()}</p>
+                    if let Some(hit_points) = creature.hit_points() {
+                        <p>{format!("{} HP", hit_points.maximum())}</p>
+                    }
+                    <button onclick={add_to_encounter(vec![id])}>{"Add"}</button>
+                    <button class="blank" onclick={remove} title={format!("Remove from {}", roster.to_string().to_lowercase())}>
+                        <Icon class="fill-color" icon_id={IconId::BootstrapDashCircle} width="15px" height="15px" />
+                    </button>
+                </div>
+            }
+        })
+        .collect::<Html>();
+
+    let all_ids = roster_creatures.iter().map(|creature| creature.id()).collect::<Vec<_>>();
+
+    html! {
+        <Accordion title={roster.to_string()}>
+            {rows}
+            <div class="flex-row button-group">
+                <button class="flex-grow-1" onclick={add_to_encounter(all_ids)} disabled={roster_creatures.is_empty()}>{"Add all to encounter"}</button>
+                <button class="flex-grow-1" onclick={keep_selected} disabled={!creatures.has_selected()}>{"Keep selected creatures"}</button>
+            </div>
+        </Accordion>
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Properties)]
 struct AddCreaturesModalProps {
     creatures: UseCreaturesHandle,
@@ -373,4 +1101,109 @@ fn add_creatures_modal(props: &AddCreaturesModalProps) -> Html {
             {content_html}
         }
     }
-}
\ No newline at end of file
+}
+#[derive(Debug, Clone, PartialEq)]
+struct CsvImport {
+    path: PathBuf,
+    headers: Vec<String>
+}
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+struct ImportCsvModalProps {
+    creatures: UseCreaturesHandle,
+    import: CsvImport,
+    on_close: Callback<()>
+}
+
+#[function_component(ImportCsvModal)]
+fn import_csv_modal(props: &ImportCsvModalProps) -> Html {
+    let ImportCsvModalProps { creatures, import, on_close } = props.clone();
+    let mapping = use_state_eq({
+        let headers = import.headers.clone();
+        move || CsvColumnMapping::from_headers(&headers)
+    });
+    let error_message = use_state_eq(|| None::<String>);
+
+    let import_creatures = {
+        let mapping = mapping.clone();
+        let error_message = error_message.clone();
+        let path = import.path.clone();
+        let on_close = on_close.clone();
+        Callback::from(move |_: MouseEvent| {
+            let creatures = creatures.clone();
+            let error_message = error_message.clone();
+            let on_close = on_close.clone();
+            import_creatures_csv_with_callback(path.clone(), (*mapping).clone(), move |result: Result<(), Error>| {
+                match result {
+                    Ok(_) => {
+                        creatures.update();
+                        on_close.emit(());
+                    },
+                    Err(Error::BackendError(BackendError::ArgumentError { message, .. })) => error_message.set(Some(message)),
+                    Err(err) => error_message.set(Some(err.to_string()))
+                }
+            });
+        })
+    };
+
+    let cancel = Callback::from(move |_: MouseEvent| {
+        on_close.emit(());
+    });
+
+    let rows = import.headers.iter()
+        .enumerate()
+        .map(|(idx, header)| {
+            let selected_column = mapping.get(idx);
+            let set_column = {
+                let mapping = mapping.clone();
+                Callback::from(move |e: Event| {
+                    let target: HtmlSelectElement = e.target_unchecked_into();
+                    let mut new_mapping = (*mapping).clone();
+                    new_mapping.set(idx, CsvColumn::from_header(&target.value()));
+                    mapping.set(new_mapping);
+                })
+            };
+
+            let options = CsvColumn::all().iter()
+                .map(|column| html! {
+                    <option value={column.header()} selected={selected_column == Some(*column)}>{column.header()}</option>
+                })
+                .collect::<Html>();
+
+            html! {
+                <tr>
+                    <td><p>{header}</p></td>
+                    <td>
+                        <select onchange={set_column}>
+                            <option value="" selected={selected_column.is_none()}>{"Ignore"}</option>
+                            {options}
+                        </select>
+                    </td>
+                </tr>
+            }
+        })
+        .collect::<Html>();
+
+    let error_html = match &*error_message {
+        Some(message) => message.lines()
+            .map(|line| html! { <p class="error-text">{line}</p> })
+            .collect::<Html>(),
+        None => html! {}
+    };
+
+    html! {
+        <Modal>
+            <p>{"Choose which creature field each column of the CSV file contains. Columns set to 'Ignore' will not be imported."}</p>
+            <div class="csv-mapping scroll-y">
+                <table class="stretch-width">
+                    {rows}
+                </table>
+            </div>
+            {error_html}
+            <div class="flex-row button-group">
+                <button class="flex-grow-1" onclick={import_creatures} disabled={!mapping.has_name()}>{"Import"}</button>
+                <button class="flex-grow-1" onclick={cancel}>{"Cancel"}</button>
+            </div>
+        </Modal>
+    }
+}